@@ -4,28 +4,126 @@ use std::ops::Range;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BridgeModule {
     pub name: String,
+    pub source_map: BridgeSourceMap,
     pub items: Vec<BridgeItem>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeSourceMap {
+    pub file: String,
+    pub line_starts: Vec<usize>,
+    pub multibyte_chars: Vec<(usize, usize)>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum BridgeItem {
     Function(BridgeFunction),
     Struct(BridgeStruct),
+    Enum(BridgeEnum),
+    Impl(BridgeImpl),
+    Trait(BridgeTrait),
+    Use(BridgeUse),
+    Module(BridgeModule),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeUse {
+    pub path: String,
+    pub span: Range<usize>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BridgeFunction {
     pub name: String,
+    pub is_pub: bool,
+    pub generics: Vec<BridgeGenericParam>,
+    pub receiver: Option<BridgeReceiver>,
     pub args: Vec<BridgeArg>,
     pub ret_type: Option<String>,
+    pub throws: Option<String>,
     pub body: BridgeBlock,
+    pub docs: Vec<String>,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BridgeReceiver {
+    Value,
+    Ref,
+    RefMut,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeImpl {
+    pub generics: Vec<BridgeGenericParam>,
+    pub trait_name: Option<String>,
+    pub target: String,
+    pub methods: Vec<BridgeFunction>,
+    pub docs: Vec<String>,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeTrait {
+    pub name: String,
+    pub is_pub: bool,
+    pub generics: Vec<BridgeGenericParam>,
+    pub methods: Vec<BridgeTraitMethod>,
+    pub docs: Vec<String>,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeTraitMethod {
+    pub name: String,
+    pub receiver: Option<BridgeReceiver>,
+    pub args: Vec<BridgeArg>,
+    pub ret_type: Option<String>,
+    pub throws: Option<String>,
+    pub default: Option<BridgeBlock>,
+    pub docs: Vec<String>,
     pub span: Range<usize>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BridgeStruct {
     pub name: String,
+    pub is_pub: bool,
+    pub generics: Vec<BridgeGenericParam>,
     pub fields: Vec<BridgeField>,
+    pub docs: Vec<String>,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeEnum {
+    pub name: String,
+    pub is_pub: bool,
+    pub generics: Vec<BridgeGenericParam>,
+    pub variants: Vec<BridgeVariant>,
+    pub docs: Vec<String>,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeVariant {
+    pub name: String,
+    pub data: BridgeVariantData,
+    pub docs: Vec<String>,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum BridgeVariantData {
+    Unit,
+    Tuple(Vec<String>),
+    Struct(Vec<BridgeField>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeGenericParam {
+    pub name: String,
+    pub bounds: Vec<String>,
     pub span: Range<usize>,
 }
 
@@ -39,7 +137,9 @@ pub struct BridgeArg {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BridgeField {
     pub name: String,
+    pub is_pub: bool,
     pub ty: String,
+    pub docs: Vec<String>,
     pub span: Range<usize>,
 }
 
@@ -52,12 +152,30 @@ pub struct BridgeBlock {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum BridgeStmt {
     Let(BridgeLetStmt),
+    Assign(BridgeAssign),
+    CompoundAssign(BridgeCompoundAssign),
     Expr(BridgeExpr),
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeAssign {
+    pub target: BridgeExpr,
+    pub value: BridgeExpr,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeCompoundAssign {
+    pub target: BridgeExpr,
+    pub op: BridgeBinOp,
+    pub value: BridgeExpr,
+    pub span: Range<usize>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BridgeLetStmt {
     pub name: String,
+    pub mutable: bool,
     pub ty: Option<String>,
     pub init: Option<BridgeExpr>,
     pub span: Range<usize>,
@@ -67,7 +185,30 @@ pub struct BridgeLetStmt {
 pub enum BridgeExpr {
     Literal(BridgeLiteral),
     Variable(String),
+    Path(String),
     Call(BridgeCall),
+    MethodCall(BridgeMethodCall),
+    Field(BridgeFieldAccess),
+    Index(BridgeIndex),
+    StructLit(BridgeStructLit),
+    StructVariant(BridgeStructLit),
+    If(BridgeIf),
+    Block(BridgeBlock),
+    While(BridgeWhile),
+    Loop(BridgeLoop),
+    For(BridgeFor),
+    Range(BridgeRange),
+    List(BridgeList),
+    Break(BridgeBreak),
+    Continue,
+    Binary(BridgeBinary),
+    Unary(BridgeUnary),
+    Match(BridgeMatch),
+    Closure(BridgeClosure),
+    Spawn(BridgeSpawn),
+    SafeNav(BridgeSafeNav),
+    Try(BridgeTry),
+    Throw(BridgeThrow),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -77,9 +218,217 @@ pub struct BridgeCall {
     pub span: Range<usize>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeMethodCall {
+    pub receiver: Box<BridgeExpr>,
+    pub method: String,
+    pub args: Vec<BridgeExpr>,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeFieldAccess {
+    pub expr: Box<BridgeExpr>,
+    pub field: String,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeIndex {
+    pub expr: Box<BridgeExpr>,
+    pub index: Box<BridgeExpr>,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeStructLit {
+    pub name: String,
+    pub fields: Vec<BridgeFieldInit>,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeFieldInit {
+    pub name: String,
+    pub value: BridgeExpr,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeIf {
+    pub cond: Box<BridgeExpr>,
+    pub then_branch: BridgeBlock,
+    pub else_branch: Option<Box<BridgeExpr>>,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeWhile {
+    pub cond: Box<BridgeExpr>,
+    pub body: BridgeBlock,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeLoop {
+    pub body: BridgeBlock,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeFor {
+    pub var: String,
+    pub iter: Box<BridgeExpr>,
+    pub body: BridgeBlock,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeRange {
+    pub start: Box<BridgeExpr>,
+    pub end: Box<BridgeExpr>,
+    pub inclusive: bool,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeList {
+    pub items: Vec<BridgeExpr>,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeBreak {
+    pub value: Option<Box<BridgeExpr>>,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeBinary {
+    pub op: BridgeBinOp,
+    pub left: Box<BridgeExpr>,
+    pub right: Box<BridgeExpr>,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BridgeBinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    And,
+    Or,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Concat,
+    Coalesce,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeUnary {
+    pub op: BridgeUnOp,
+    pub expr: Box<BridgeExpr>,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BridgeUnOp {
+    Neg,
+    Not,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeMatch {
+    pub expr: Box<BridgeExpr>,
+    pub arms: Vec<BridgeArm>,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeArm {
+    pub pattern: BridgePattern,
+    pub guard: Option<BridgeExpr>,
+    pub body: BridgeExpr,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeClosure {
+    pub params: Vec<String>,
+    pub body: Box<BridgeExpr>,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeSafeNav {
+    pub expr: Box<BridgeExpr>,
+    pub access: BridgeSafeAccess,
+    pub nullable: bool,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum BridgeSafeAccess {
+    Field(String),
+    MethodCall(String, Vec<BridgeExpr>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeTry {
+    pub expr: Box<BridgeExpr>,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeThrow {
+    pub value: Box<BridgeExpr>,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeSpawn {
+    pub body: BridgeBlock,
+    pub is_move: bool,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum BridgePattern {
+    Wildcard,
+    Binding(String),
+    Literal(BridgeLiteral),
+    Range(BridgeRangePattern),
+    Path(String),
+    TupleVariant(String, Vec<BridgePattern>),
+    StructVariant(String, Vec<BridgeFieldPattern>),
+    Or(Vec<BridgePattern>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeRangePattern {
+    pub start: i64,
+    pub end: i64,
+    pub inclusive: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeFieldPattern {
+    pub name: String,
+    pub pattern: BridgePattern,
+    pub span: Range<usize>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum BridgeLiteral {
     Int(i64),
     String(String),
     Bool(bool),
+    Null,
+    Unit,
 }
@@ -2,6 +2,8 @@ use anyhow::{Context, Result};
 use bridge_ir::BridgeModule;
 use clap::Parser;
 use std::path::PathBuf;
+// Kept for the rustc-executor call the TODO in `run` describes
+#[allow(unused_imports)]
 use std::process::Command;
 
 #[derive(Parser)]
@@ -2,79 +2,212 @@
 // Nikaia AST definition matching Spec 0.0.4
 // Based on ADR-001 and Part I/II/III documents.
 
-use syn::Ident;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::Range;
+
+mod symbol;
+
+pub use symbol::Symbol;
+
+/// Ein Bezeichner: der internierte Name und seine Position in der Quelle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ident {
+    name: Symbol,
+    pub span: Range<usize>,
+}
+
+impl Ident {
+    pub fn new(name: &str, span: Range<usize>) -> Self {
+        Ident {
+            name: Symbol::intern(name),
+            span,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        self.name.as_str()
+    }
+}
+
+// Namen vergleichen sich ohne Rücksicht auf ihre Position
+impl PartialEq for Ident {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Eq for Ident {}
+
+impl<T: ?Sized + AsRef<str>> PartialEq<T> for Ident {
+    fn eq(&self, other: &T) -> bool {
+        self.as_str() == other.as_ref()
+    }
+}
+
+impl fmt::Display for Ident {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.name, f)
+    }
+}
+
+/// Kap 4.2: der anonyme Konstruktor `pub fn(...)` heißt in Rust `new`.
+pub const CONSTRUCTOR: &str = "new";
 
 /// Ein Nikaia-Programm ist eine Liste von Top-Level Items.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Program {
     pub items: Vec<Item>,
 }
 
+impl Program {
+    /// Alle Items, auch die der importierten Module (Kap 9.1), ohne die
+    /// `Item::Module`-Hüllen selbst.
+    pub fn all_items(&self) -> Vec<&Item> {
+        fn collect<'a>(items: &'a [Item], out: &mut Vec<&'a Item>) {
+            for item in items {
+                match item {
+                    Item::Module { items, .. } => collect(items, out),
+                    item => out.push(item),
+                }
+            }
+        }
+        let mut out = Vec::new();
+        collect(&self.items, &mut out);
+        out
+    }
+
+    pub fn all_items_mut(&mut self) -> Vec<&mut Item> {
+        fn collect<'a>(items: &'a mut [Item], out: &mut Vec<&'a mut Item>) {
+            for item in items {
+                match item {
+                    Item::Module { items, .. } => collect(items, out),
+                    item => out.push(item),
+                }
+            }
+        }
+        let mut out = Vec::new();
+        collect(&mut self.items, &mut out);
+        out
+    }
+}
+
 /// Top-Level Konstrukte (außerhalb von Funktionen)
-#[derive(Debug, Clone)]
+/// Alle `span`-Felder sind Byte-Bereiche in der Quelldatei des Moduls, in dem
+/// der Knoten steht.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Item {
     // Kap 5.1: fn add(a: i32) -> i32 { ... }
     Fn {
         name: Ident,
+        is_pub: bool,                // Kap 9.2: pub fn
         generics: Vec<GenericParam>, // Kap 4.5: [T]
+        receiver: Option<Receiver>,  // Kap 4.3: fn login(&self)
         args: Vec<FnArg>,
+        config: Vec<FnArg>, // Kap 5.4: Config-Argumente nach `;`
+        throws: Vec<Type>,  // Kap 7.1: throws IoError, NetworkError
         ret_type: Option<Type>,
         body: Block,
-        is_sync: bool, // Kap 12.1: sync keyword
+        is_sync: bool,     // Kap 12.1: sync keyword
+        docs: Vec<String>, // Inhalt der /// Zeilen vor dem Item
+        span: Range<usize>,
     },
 
     // Kap 4.1: struct User { ... }
     Struct {
         name: Ident,
+        is_pub: bool,
         generics: Vec<GenericParam>,
         fields: Vec<FieldDef>,
+        docs: Vec<String>,
+        span: Range<usize>,
     },
 
     // Kap 4.3: enum Message { ... }
     Enum {
         name: Ident,
+        is_pub: bool,
         generics: Vec<GenericParam>,
         variants: Vec<EnumVariant>,
+        docs: Vec<String>,
+        span: Range<usize>,
     },
 
-    // Kap 4.2: impl User { ... }
+    // Kap 4.2: impl User { ... }, mit anonymem Konstruktor pub fn(...) -> User
+    // Kap 4.7: impl Summarize for User { ... }
     Impl {
+        generics: Vec<GenericParam>, // impl[T] Stack[T]
+        trait_ref: Option<Type>,
         target: Type,
         methods: Vec<Item>, // Enthält Item::Fn
+        docs: Vec<String>,
+        span: Range<usize>,
+    },
+
+    // Kap 4.7: trait Summarize { fn summary(&self) -> String }
+    Trait {
+        name: Ident,
+        is_pub: bool,
+        generics: Vec<GenericParam>,
+        methods: Vec<TraitMethod>,
+        docs: Vec<String>,
+        span: Range<usize>,
     },
 
     // Part III, Kap 14.1: test "Name" { ... }
     Test {
         name: String,
         body: Block,
+        span: Range<usize>,
     },
 
     // Part III, Kap 13.4: bench "Name" { ... }
     Bench {
         name: String,
         body: Block,
+        span: Range<usize>,
     },
 
     // Part II, Kap 10.1: grammar ColorParser { ... }
     Grammar {
         name: Ident,
         content: String, // Simplified from TokenStream
+        span: Range<usize>,
     },
 
-    // Kap 9.2: use std::http
+    // Kap 9.2: use users::User, use std::http
     Import {
         path: String,
+        span: Range<usize>,
+    },
+
+    // Kap 9.1: jede .nika-Datei ist ein Modul. Der Loader hängt importierte
+    // Dateien als verschachtelte Module an das Programm (src/std/http.nika
+    // wird zu `std` { `http` }); ein Verzeichnis ohne eigene Datei hat keine
+    // Quelle.
+    Module {
+        name: Ident,
+        items: Vec<Item>,
+        source: SourceMap,
+    },
+
+    // Part II, Kap 10.6: Text, der sich nicht als Item parsen ließ, bis zum
+    // nächsten Synchronisationspunkt übersprungen
+    Error {
+        error: Diagnostic,
+        span: Range<usize>,
     },
 }
 
 /// Ein Block von Statements { ... }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Block {
     pub stmts: Vec<Stmt>,
+    pub span: Range<usize>, // von `{` bis `}`
 }
 
 /// Anweisungen innerhalb eines Blocks
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Stmt {
     // Kap 2.1: let mut x = 10
     Let {
@@ -82,27 +215,54 @@ pub enum Stmt {
         mutable: bool,
         ty: Option<Type>, // Type Inference macht dies optional
         value: Expr,
+        span: Range<usize>,
     },
 
-    // Kap 2.1: x = 20
+    // Kap 2.1: x = 20, obj.x = 1, scores["Player1"] = 100
     Assign {
         target: Expr,
         value: Expr,
+        span: Range<usize>,
     },
 
-    // Ein "nackter" Ausdruck (z.B. Funktionsaufruf oder Return-Value)
+    // Kap 3.3: count += 1
+    CompoundAssign {
+        target: Expr,
+        op: BinOp,
+        value: Expr,
+        span: Range<usize>,
+    },
+
+    // Ein "nackter" Ausdruck (z.B. Funktionsaufruf oder Return-Value);
+    // der Span ist der des Ausdrucks
     Expr(Expr),
 }
 
 /// Ausdrücke (Alles, was einen Wert zurückgibt)
-#[derive(Debug, Clone)]
-pub enum Expr {
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Expr {
+    pub kind: ExprKind,
+    pub span: Range<usize>,
+}
+
+impl Expr {
+    pub fn new(kind: ExprKind, span: Range<usize>) -> Self {
+        Expr { kind, span }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ExprKind {
     // Primitive
     LitInt(i64),
     LitStr(String),
     LitBool(bool),
+    LitNull, // Kap 2.3: null
     Variable(Ident),
 
+    // Kap 4.4: Message::Quit, HashMap::new
+    Path(Vec<Ident>),
+
     // Kap 3.1: Blöcke sind Expressions
     Block(Block),
 
@@ -113,13 +273,113 @@ pub enum Expr {
         else_branch: Option<Block>,
     },
 
+    // Kap 2: a + b, age >= 18, "User: " + name
+    Binary {
+        op: BinOp,
+        left: Box<Expr>,
+        right: Box<Expr>,
+    },
+
+    // Kap 2: !flag, -x
+    Unary {
+        op: UnOp,
+        expr: Box<Expr>,
+    },
+
+    // Kap 3.3: while count < 5 { ... }
+    While {
+        cond: Box<Expr>,
+        body: Block,
+    },
+
+    // Kap 3.3: loop { ... }, Wert über break value
+    Loop {
+        body: Block,
+    },
+
+    // Kap 3.3: for i in 0..5 { ... } / for n in numbers { ... }
+    For {
+        var: Ident,
+        iter: Box<Expr>,
+        body: Block,
+    },
+
+    // Kap 3.3: 0..5 und 0..=5
+    Range {
+        start: Box<Expr>,
+        end: Box<Expr>,
+        inclusive: bool,
+    },
+
+    // Kap 4.5: [1, 2, 3, 4]
+    List(Vec<Expr>),
+
+    Break(Option<Box<Expr>>),
+    Continue,
+
     // Kap 5.1: Funktionsaufruf add(1, 2)
+    // Kap 5.4: request(url; timeout: 10)
     Call {
         func: Box<Expr>,
         args: Vec<Expr>,
+        config: Vec<ConfigArg>,
     },
 
-    // Kap 8.2: spawn({ ... }) oder spawn(move { ... })
+    // Kap 4.2: User(username: name, email: email) – direkte Konstruktion mit
+    // benannten Feldern. User("Alice", "a@b") ist dagegen ein Call, den der
+    // checker auf den Konstruktor User::new umschreibt.
+    // `variant`: der Pfad nennt eine Struct-Variante, Message::Move(x: 1, y: 2)
+    // (setzt der checker).
+    StructLit {
+        path: Vec<Ident>,
+        fields: Vec<FieldInit>,
+        variant: bool,
+    },
+
+    // Kap 4.3: Methodenaufruf user.login()
+    MethodCall {
+        receiver: Box<Expr>,
+        method: Ident,
+        args: Vec<Expr>,
+        config: Vec<ConfigArg>,
+    },
+
+    // Kap 4.5: Indexzugriff scores["Player1"], numbers[0]
+    Index {
+        expr: Box<Expr>,
+        index: Box<Expr>,
+    },
+
+    // Kap 4.3: Feldzugriff self.username
+    Field {
+        expr: Box<Expr>,
+        field: Ident,
+    },
+
+    // Kap 3.5: user?.profile, user?.display_name()
+    // `nullable`: das Feld bzw. der Rückgabetyp ist selbst T? (setzt der checker).
+    SafeField {
+        expr: Box<Expr>,
+        field: Ident,
+        nullable: bool,
+    },
+    SafeMethodCall {
+        receiver: Box<Expr>,
+        method: Ident,
+        args: Vec<Expr>,
+        config: Vec<ConfigArg>,
+        nullable: bool,
+    },
+
+    // Kap 5.2: fn: a + b
+    // Implizite Parameter (a, b, c) trägt der checker anhand des Bodys ein.
+    Lambda {
+        params: Vec<Ident>,
+        implicit: bool,
+        body: Box<Expr>,
+    },
+
+    // Kap 8.2: spawn({ ... }), spawn(move { ... }), spawn fn: expr, spawn move fn { ... }
     // Auch Kap 5.2: Block Lambdas
     Spawn {
         body: Box<Expr>, // Meistens ein Expr::Block
@@ -139,59 +399,270 @@ pub enum Expr {
         code: String,              // Block 2 (Roher Text)
     },
 
-    // Kap 7.1: Error Handling ?{ ... }
+    // Kap 7.1: fetch_config() catch { ... }
     TryCatch {
         expr: Box<Expr>,
         handler: Block, // Der Block mit 'error' Variable
     },
 
+    // Kap 7.1: throw TimeoutError("Too slow!")
+    Throw {
+        value: Box<Expr>,
+        span: Range<usize>,
+    },
+
+    // Kap 7.1: Aufruf einer throws-Funktion, dessen Fehler weitergereicht wird.
+    // Setzt der checker ein; wird zu `?`.
+    Propagate(Box<Expr>),
+
+    // Kap 2.3: ein Wert vom Typ T an einer Stelle vom Typ T?, etwa
+    // `let nick: String? = name`. Setzt der checker ein; wird zu `Some(...)`.
+    Nullable(Box<Expr>),
+
     // Kap 3.4: match value { ... }
     Match {
         expr: Box<Expr>,
         arms: Vec<MatchArm>,
     },
+
+    // Part II, Kap 10.6: eine Anweisung, die sich nicht parsen ließ; der
+    // Span des Ausdrucks umfasst den übersprungenen Text
+    Error(Diagnostic),
 }
 
 // --- Helper Strukturen ---
 
-#[derive(Debug, Clone)]
+/// Ein Fehler im Quelltext, mit den Bytes, auf die er zeigt.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub message: String,
+    pub span: Range<usize>,
+    pub expected: Vec<String>, // was dort stattdessen hätte stehen können
+}
+
+/// Kap 7.1: Zeilenanfänge einer Quelldatei, um Byte-Spans als datei:zeile:spalte anzuzeigen.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SourceMap {
+    pub file: String,
+    pub line_starts: Vec<usize>,
+    // Byte-Offset und Länge jedes Zeichens, das mehr als ein Byte belegt
+    pub multibyte_chars: Vec<(usize, usize)>,
+}
+
+impl SourceMap {
+    pub fn new(file: &str, source: &str) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(source.match_indices('\n').map(|(i, _)| i + 1));
+        let multibyte_chars = source
+            .char_indices()
+            .filter(|(_, c)| c.len_utf8() > 1)
+            .map(|(i, c)| (i, c.len_utf8()))
+            .collect();
+        SourceMap {
+            file: file.to_string(),
+            line_starts,
+            multibyte_chars,
+        }
+    }
+
+    /// `main.nika:3:9` für einen Byte-Offset, Zeile und Spalte ab 1.
+    pub fn location(&self, offset: usize) -> String {
+        let (line, column) = self.line_column(offset);
+        format!("{}:{}:{}", self.file, line, column)
+    }
+
+    /// Zeile und Spalte ab 1; die Spalte zählt Zeichen, nicht Bytes.
+    pub fn line_column(&self, offset: usize) -> (usize, usize) {
+        let line = self
+            .line_starts
+            .partition_point(|&start| start <= offset)
+            .max(1);
+        let start = self.line_starts.get(line - 1).copied().unwrap_or(0);
+        let extra: usize = self
+            .multibyte_chars
+            .iter()
+            .filter(|(at, _)| (start..offset).contains(at))
+            .map(|(_, len)| len - 1)
+            .sum();
+        (line, offset - start - extra + 1)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Type {
     pub name: Ident,
     pub generics: Vec<Type>, // Recursive: Shared[Locked[T]]
+    pub nullable: bool,      // Kap 2.3: String?
+    pub span: Range<usize>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GenericParam {
     pub name: Ident,
-    // Constraints wie T: Drawable fehlen hier noch vereinfacht
+    pub bounds: Vec<Type>, // Kap 4.7: [T: Drawable + Clone]
+}
+
+// Kap 4.7: Methode eines Traits, mit optionaler Default-Implementierung
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraitMethod {
+    pub name: Ident,
+    pub receiver: Option<Receiver>,
+    pub args: Vec<FnArg>,
+    pub config: Vec<FnArg>,
+    pub throws: Vec<Type>,
+    pub ret_type: Option<Type>,
+    pub default: Option<Block>,
+    pub docs: Vec<String>,
+    pub span: Range<usize>,
+}
+
+// Kap 2: Binäre Operatoren, von schwach nach stark bindend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BinOp {
+    Or,
+    And,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Coalesce, // Kap 3.5: name ?? "Anonymous"
+    Add,
+    Concat, // Kap 2: "User: " + name; `+` mit String-Operand, setzt der checker ein
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+impl BinOp {
+    /// Binding strength used by the parser's precedence climbing.
+    pub fn precedence(self) -> u8 {
+        match self {
+            BinOp::Or => 1,
+            BinOp::And => 2,
+            BinOp::Eq | BinOp::Ne | BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge => 3,
+            BinOp::Coalesce => 4,
+            BinOp::Add | BinOp::Concat | BinOp::Sub => 5,
+            BinOp::Mul | BinOp::Div | BinOp::Rem => 6,
+        }
+    }
+
+    /// `a ?? b ?? c` falls back from right to left: `a ?? (b ?? c)`.
+    pub fn is_right_assoc(self) -> bool {
+        self == BinOp::Coalesce
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UnOp {
+    Neg, // -x
+    Not, // !flag
 }
 
-#[derive(Debug, Clone)]
+// Kap 4.3: self, &self, &mut self
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Receiver {
+    Value,
+    Ref,
+    RefMut,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FnArg {
     pub name: Ident,
     pub ty: Type,
+    pub default: Option<Expr>, // Kap 5.4: timeout: i32 = 30
+}
+
+// Kap 5.4: timeout: 10 hinter dem `;` eines Aufrufs
+// Der checker ordnet die Argumente nach der Deklaration und setzt `value: None`
+// für Parameter, die ihren Default behalten.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigArg {
+    pub name: Option<Ident>, // None: positionales Argument im Config-Bereich (Fehler)
+    pub value: Option<Expr>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FieldDef {
     pub name: Ident,
     pub ty: Type,
+    pub is_pub: bool, // Kap 9.2: pub name: String
+    pub docs: Vec<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EnumVariant {
     pub name: Ident,
-    pub data: Option<Vec<FieldDef>>, // Für: Variant { x: i32 }
+    pub data: VariantData,
+    pub docs: Vec<String>,
+    pub span: Range<usize>,
 }
 
-#[derive(Debug, Clone)]
+// Kap 4.4: Quit, Write(String), Move { x: i32, y: i32 }
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum VariantData {
+    Unit,
+    Tuple(Vec<Type>),
+    Struct(Vec<FieldDef>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MatchArm {
-    pub pattern: Expr, // Vereinfacht
+    pub pattern: Pattern,
+    pub guard: Option<Expr>, // n if n > 10 => ...
     pub body: Expr,
+    pub span: Range<usize>,
+}
+
+// Kap 3.4: Muster in match-Armen
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Pattern {
+    Wildcard,       // _
+    Binding(Ident), // n (oder eine Unit-Variante, siehe checker)
+    LitInt(i64),
+    LitStr(String),
+    LitBool(bool),
+    // 1..=5
+    Range {
+        start: i64,
+        end: i64,
+        inclusive: bool,
+    },
+    // Message::Quit, Write(text), Move { x, y }
+    Variant {
+        path: Vec<Ident>,
+        fields: PatternFields,
+    },
+    // 1 | 2
+    Or(Vec<Pattern>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PatternFields {
+    Unit,
+    Tuple(Vec<Pattern>),
+    Struct(Vec<FieldPattern>),
+}
+
+// Move { x, y: 0 }: ohne Muster bindet das Feld seinen eigenen Namen
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldPattern {
+    pub name: Ident,
+    pub pattern: Pattern,
+}
+
+// Kap 4.2: username: name
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldInit {
+    pub name: Ident,
+    pub value: Expr,
 }
 
 // Part III, Kap 16.1: $dst = out(reg) result
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AsmBinding {
     pub alias: Ident,      // $dst
     pub direction: String, // "out", "in", "inout"
@@ -0,0 +1,1421 @@
+// crates/nikaia/src/checker/mod.rs
+// Frontend checks on the Nikaia AST. They run before lowering so that
+// mistakes are reported in Nikaia terms instead of surfacing from rustc.
+// Name resolution that later stages rely on (qualified enum variants in
+// patterns, implicit lambda parameters, config argument order, nullability
+// of `?.` accesses, error propagation, constructor calls, string
+// concatenation) happens here as well, and so does privacy.
+use crate::ast::{
+    Block, ConfigArg, EnumVariant, Expr, ExprKind, FnArg, Ident, Item, Pattern, PatternFields,
+    Program, Stmt, TraitMethod, Type, VariantData, CONSTRUCTOR,
+};
+use crate::loader::{self, ModuleScope};
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+
+mod types;
+
+pub fn check_program(program: &mut Program) -> Result<()> {
+    let mut errors = Vec::new();
+    resolve_variant_patterns(program);
+    infer_lambda_params(program);
+    resolve_construction(program, &mut errors);
+    check_config_params(program, &mut errors);
+    resolve_config_args(program, &mut errors);
+    resolve_safe_access(program, &mut errors);
+    resolve_throws(program, &mut errors);
+    let uses = types::resolve_types(program);
+    check_privacy(program, &uses, &mut errors);
+    check_trait_impls(program, &mut errors);
+    check_match_exhaustiveness(program, &mut errors);
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!(errors.join("\n")))
+    }
+}
+
+/// Every `impl Trait for Type` must provide the trait's methods that have no default.
+fn check_trait_impls(program: &Program, errors: &mut Vec<String>) {
+    let traits: HashMap<String, &[TraitMethod]> = program
+        .all_items()
+        .into_iter()
+        .filter_map(|item| match item {
+            Item::Trait { name, methods, .. } => Some((name.to_string(), methods.as_slice())),
+            _ => None,
+        })
+        .collect();
+
+    for item in program.all_items() {
+        let Item::Impl {
+            trait_ref: Some(trait_ref),
+            target,
+            methods,
+            ..
+        } = item
+        else {
+            continue;
+        };
+        // Traits we don't know about (e.g. `Drop`) are left to rustc.
+        let Some(required) = traits.get(&trait_ref.name.to_string()) else {
+            continue;
+        };
+
+        for method in required.iter().filter(|m| m.default.is_none()) {
+            let provided = methods
+                .iter()
+                .any(|m| matches!(m, Item::Fn { name, .. } if *name == method.name));
+            if !provided {
+                errors.push(format!(
+                    "Missing trait method `{}` in `impl {} for {}`",
+                    method.name, trait_ref.name, target.name
+                ));
+            }
+        }
+    }
+}
+
+/// Enum name -> variants, for the enums declared in the program.
+fn collect_enums(program: &Program) -> HashMap<String, Vec<EnumVariant>> {
+    program
+        .all_items()
+        .into_iter()
+        .filter_map(|item| match item {
+            Item::Enum { name, variants, .. } => Some((name.to_string(), variants.clone())),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Qualifies variant patterns with their enum: `Move { x, y }` becomes
+/// `Message::Move { x, y }`, and a binding named like a unit variant (`Quit`)
+/// becomes that variant. Names declared by several enums stay as written.
+fn resolve_variant_patterns(program: &mut Program) {
+    let enums = collect_enums(program);
+    visit_exprs(program, &mut |expr| {
+        if let ExprKind::Match { arms, .. } = &mut expr.kind {
+            for arm in arms {
+                resolve_pattern(&mut arm.pattern, &enums);
+            }
+        }
+    });
+}
+
+fn resolve_pattern(pattern: &mut Pattern, enums: &HashMap<String, Vec<EnumVariant>>) {
+    match pattern {
+        Pattern::Binding(name) => {
+            if let Some(owner) = variant_owner(&name.to_string(), enums, true) {
+                let variant = name.clone();
+                *pattern = Pattern::Variant {
+                    path: vec![Ident::new(&owner, variant.span.clone()), variant],
+                    fields: PatternFields::Unit,
+                };
+            }
+        }
+        Pattern::Variant { path, fields } => {
+            if path.len() == 1 {
+                if let Some(owner) = variant_owner(&path[0].to_string(), enums, false) {
+                    path.insert(0, Ident::new(&owner, path[0].span.clone()));
+                }
+            }
+            match fields {
+                PatternFields::Unit => {}
+                PatternFields::Tuple(items) => {
+                    items.iter_mut().for_each(|p| resolve_pattern(p, enums));
+                }
+                PatternFields::Struct(fields) => fields
+                    .iter_mut()
+                    .for_each(|f| resolve_pattern(&mut f.pattern, enums)),
+            }
+        }
+        Pattern::Or(alternatives) => {
+            alternatives
+                .iter_mut()
+                .for_each(|p| resolve_pattern(p, enums));
+        }
+        _ => {}
+    }
+}
+
+/// The one enum declaring a variant with this name, if it is unambiguous.
+fn variant_owner(
+    variant: &str,
+    enums: &HashMap<String, Vec<EnumVariant>>,
+    unit_only: bool,
+) -> Option<String> {
+    let mut owners = enums.iter().filter(|(_, variants)| {
+        variants
+            .iter()
+            .any(|v| v.name == variant && (!unit_only || matches!(v.data, VariantData::Unit)))
+    });
+    match (owners.next(), owners.next()) {
+        (Some((owner, _)), None) => Some(owner.clone()),
+        _ => None,
+    }
+}
+
+/// A `match` over a known enum has to cover every variant, unless an
+/// unguarded `_` or binding arm catches the rest.
+fn check_match_exhaustiveness(program: &mut Program, errors: &mut Vec<String>) {
+    let enums = collect_enums(program);
+    visit_exprs(program, &mut |expr| {
+        let ExprKind::Match { arms, .. } = &mut expr.kind else {
+            return;
+        };
+        let Some((enum_name, variants)) = arms
+            .iter()
+            .find_map(|arm| matched_enum(&arm.pattern))
+            .and_then(|name| enums.get_key_value(&name))
+        else {
+            return;
+        };
+
+        let mut covered = Vec::new();
+        for arm in arms.iter().filter(|arm| arm.guard.is_none()) {
+            if !covers(&arm.pattern, &mut covered) {
+                return;
+            }
+        }
+        let missing: Vec<String> = variants
+            .iter()
+            .map(|v| v.name.to_string())
+            .filter(|name| !covered.contains(name))
+            .collect();
+        if !missing.is_empty() {
+            errors.push(format!(
+                "Non-exhaustive `match` on `{}`: missing {}",
+                enum_name,
+                missing
+                    .iter()
+                    .map(|name| format!("`{}`", name))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+    });
+}
+
+/// The enum a resolved pattern refers to (`Message` for `Message::Quit`).
+fn matched_enum(pattern: &Pattern) -> Option<String> {
+    match pattern {
+        Pattern::Variant { path, .. } if path.len() == 2 => Some(path[0].to_string()),
+        Pattern::Or(alternatives) => alternatives.iter().find_map(matched_enum),
+        _ => None,
+    }
+}
+
+/// Records the variants a pattern matches completely. Returns `false` for a
+/// catch-all, which makes the match exhaustive on its own.
+fn covers(pattern: &Pattern, covered: &mut Vec<String>) -> bool {
+    match pattern {
+        Pattern::Wildcard | Pattern::Binding(_) => false,
+        Pattern::Variant { path, fields } => {
+            let irrefutable = match fields {
+                PatternFields::Unit => true,
+                PatternFields::Tuple(items) => items.iter().all(is_irrefutable),
+                PatternFields::Struct(fields) => fields.iter().all(|f| is_irrefutable(&f.pattern)),
+            };
+            if let (true, Some(variant)) = (irrefutable, path.last()) {
+                covered.push(variant.to_string());
+            }
+            true
+        }
+        Pattern::Or(alternatives) => alternatives.iter().all(|p| covers(p, covered)),
+        _ => true,
+    }
+}
+
+fn is_irrefutable(pattern: &Pattern) -> bool {
+    matches!(pattern, Pattern::Wildcard | Pattern::Binding(_))
+}
+
+// --- Lambdas ---
+
+// Kap 5.2: the implicit arguments of `fn:`, in positional order
+const IMPLICIT_PARAMS: [&str; 3] = ["a", "b", "c"];
+
+/// `fn: a + b` takes `a` and `b`. Arguments are positional, so a body that
+/// only uses `b` still takes two; the unused first one becomes `_`.
+fn infer_lambda_params(program: &mut Program) {
+    visit_exprs(program, &mut |expr| {
+        // The implicit parameters point to the lambda
+        let span = expr.span.clone();
+        if let ExprKind::Lambda {
+            params,
+            implicit: true,
+            body,
+        } = &mut expr.kind
+        {
+            let mut used = [false; IMPLICIT_PARAMS.len()];
+            mark_implicit_uses(body, &mut used);
+            let arity = used.iter().rposition(|u| *u).map_or(0, |i| i + 1);
+            *params = (0..arity)
+                .map(|i| if used[i] { IMPLICIT_PARAMS[i] } else { "_" })
+                .map(|name| Ident::new(name, span.clone()))
+                .collect();
+        }
+    });
+}
+
+fn mark_implicit_uses(expr: &mut Expr, used: &mut [bool]) {
+    match &expr.kind {
+        ExprKind::Variable(name) => {
+            if let Some(i) = IMPLICIT_PARAMS.iter().position(|param| *name == *param) {
+                used[i] = true;
+            }
+        }
+        // A nested lambda has its own a, b and c
+        ExprKind::Lambda { .. } => {}
+        _ => for_each_child(expr, &mut |child| mark_implicit_uses(child, used)),
+    }
+}
+
+// --- Construction ---
+
+/// Kap 4.2: `User("Alice", "a@b")` calls the struct's anonymous constructor,
+/// which is `User::new` from here on. `User(username: name, ...)` builds the
+/// struct directly and has to name every field once. Like methods, structs
+/// are resolved by name.
+fn resolve_construction(program: &mut Program, errors: &mut Vec<String>) {
+    // Structs and functions by full path (`a::Item`), so that two modules
+    // may each declare an `Item`
+    let mut structs: HashMap<String, Vec<String>> = HashMap::new();
+    let mut functions = HashSet::new();
+    for (module, item) in module_items(&program.items, &mut Vec::new()) {
+        match item {
+            Item::Struct { name, fields, .. } => {
+                let fields = fields.iter().map(|f| f.name.to_string()).collect();
+                structs.insert(loader::item_path(&module, name.as_str()), fields);
+            }
+            Item::Fn { name, .. } => {
+                functions.insert(loader::item_path(&module, name.as_str()));
+            }
+            _ => {}
+        }
+    }
+    // Struct-like variants by `Enum::Variant`: Message::Move(x: 1, y: 2)
+    let mut variants: HashMap<String, Vec<String>> = HashMap::new();
+    for (name, declared) in collect_enums(program) {
+        for variant in declared {
+            if let VariantData::Struct(fields) = variant.data {
+                let fields = fields.iter().map(|f| f.name.to_string()).collect();
+                variants.insert(format!("{}::{}", name, variant.name), fields);
+            }
+        }
+    }
+    let mut constructors = Vec::new();
+    for_each_module_item(&mut program.items, &mut Vec::new(), &mut |scope, item| {
+        if let Item::Impl {
+            trait_ref: None,
+            target,
+            methods,
+            ..
+        } = item
+        {
+            let declares = methods.iter().any(
+                |m| matches!(m, Item::Fn { name, receiver: None, .. } if *name == CONSTRUCTOR),
+            );
+            let target = scope
+                .candidates(&[target.name.to_string()])
+                .into_iter()
+                .find(|candidate| structs.contains_key(candidate));
+            if let (true, Some(target)) = (declares, target) {
+                constructors.push(target);
+            }
+        }
+    });
+
+    visit_module_exprs(program, &mut |scope, expr| match &mut expr.kind {
+        ExprKind::Call { func, .. } => {
+            let path = match &func.kind {
+                ExprKind::Variable(name) => vec![name.clone()],
+                // users::User("Alice"); the variant Shape::Circle(1) names no struct
+                ExprKind::Path(path) => path.clone(),
+                _ => return,
+            };
+            let segments: Vec<String> = path.iter().map(|p| p.to_string()).collect();
+            // A function named like a struct is called as written
+            let Some(full) = scope
+                .candidates(&segments)
+                .into_iter()
+                .find(|c| structs.contains_key(c) || functions.contains(c))
+                .filter(|c| structs.contains_key(c))
+            else {
+                return;
+            };
+            let name = path[path.len() - 1].to_string();
+            if !constructors.contains(&full) {
+                errors.push(format!(
+                    "`{}` has no constructor; declare `pub fn(...)` in `impl {}` or name its fields: `{}(field: value)`",
+                    name, name, name
+                ));
+                return;
+            }
+            let mut path = path;
+            let span = path[path.len() - 1].span.clone();
+            path.push(Ident::new(CONSTRUCTOR, span));
+            func.kind = ExprKind::Path(path);
+        }
+        ExprKind::StructLit {
+            path,
+            fields,
+            variant,
+        } => {
+            let segments: Vec<String> = path.iter().map(|p| p.to_string()).collect();
+            let enum_variant = segments[segments.len().saturating_sub(2)..].join("::");
+            let (name, declared) = match find(&structs, scope, &segments) {
+                Some(declared) => (path[path.len() - 1].to_string(), declared),
+                None => match variants.get(&enum_variant) {
+                    Some(declared) => {
+                        *variant = true;
+                        (enum_variant, declared)
+                    }
+                    None => {
+                        errors.push(format!(
+                            "`{}` is not a struct; config arguments of a call go after `;`",
+                            path[path.len() - 1]
+                        ));
+                        return;
+                    }
+                },
+            };
+            let mut given: Vec<String> = Vec::new();
+            for field in fields.iter() {
+                let field = field.name.to_string();
+                if given.contains(&field) {
+                    errors.push(format!("Field `{}` of `{}` is set twice", field, name));
+                } else if !declared.contains(&field) {
+                    errors.push(format!("`{}` has no field `{}`", name, field));
+                }
+                given.push(field);
+            }
+            let missing: Vec<&str> = declared
+                .iter()
+                .filter(|f| !given.contains(f))
+                .map(String::as_str)
+                .collect();
+            if !missing.is_empty() {
+                errors.push(format!(
+                    "Missing fields in `{}(...)`: {}",
+                    name,
+                    missing.join(", ")
+                ));
+            }
+        }
+        _ => {}
+    });
+}
+
+// --- Config arguments ---
+
+/// The parameter names of a function or method, split at its `;`.
+#[derive(PartialEq)]
+struct Signature {
+    subject: Vec<String>,
+    config: Vec<String>,
+}
+
+impl Signature {
+    fn new(args: &[FnArg], config: &[FnArg]) -> Self {
+        Signature {
+            subject: args.iter().map(|a| a.name.to_string()).collect(),
+            config: config.iter().map(|a| a.name.to_string()).collect(),
+        }
+    }
+}
+
+/// Every function parameter list with its owner's name: free functions by
+/// their full path (`users::create`), impl methods and trait methods.
+fn param_lists(program: &Program) -> Vec<(String, &[FnArg], &[FnArg], bool)> {
+    let mut lists = Vec::new();
+    for (module, item) in module_items(&program.items, &mut Vec::new()) {
+        match item {
+            Item::Fn {
+                name, args, config, ..
+            } => lists.push((
+                loader::item_path(&module, name.as_str()),
+                args.as_slice(),
+                config.as_slice(),
+                false,
+            )),
+            Item::Impl { methods, .. } => {
+                for method in methods {
+                    if let Item::Fn {
+                        name, args, config, ..
+                    } = method
+                    {
+                        lists.push((name.to_string(), args.as_slice(), config.as_slice(), true));
+                    }
+                }
+            }
+            Item::Trait { methods, .. } => {
+                for method in methods {
+                    lists.push((
+                        method.name.to_string(),
+                        method.args.as_slice(),
+                        method.config.as_slice(),
+                        true,
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+    lists
+}
+
+/// Kap 5.4: subject parameters are always passed, config parameters after
+/// `;` are optional and therefore need a default.
+fn check_config_params(program: &Program, errors: &mut Vec<String>) {
+    for (name, args, config, _) in param_lists(program) {
+        for arg in args.iter().filter(|a| a.default.is_some()) {
+            errors.push(format!(
+                "Default value on subject parameter `{}` of `{}`; only config parameters after `;` have defaults",
+                arg.name, name
+            ));
+        }
+        for arg in config.iter().filter(|a| a.default.is_none()) {
+            errors.push(format!(
+                "Config parameter `{}` of `{}` needs a default value",
+                arg.name, name
+            ));
+        }
+    }
+}
+
+/// Matches the `name: value` arguments after a call's `;` to the callee's
+/// config parameters and puts them in declaration order. Parameters the call
+/// leaves out get `value: None`, which the backends fill with the default.
+/// Methods are resolved by name, so a method with config parameters must
+/// declare the same ones wherever it is defined.
+fn resolve_config_args(program: &mut Program, errors: &mut Vec<String>) {
+    let mut functions = HashMap::new();
+    let mut methods: HashMap<String, Vec<Signature>> = HashMap::new();
+    for (name, args, config, is_method) in param_lists(program) {
+        let signature = Signature::new(args, config);
+        if !is_method {
+            functions.insert(name, signature);
+            continue;
+        }
+        let known = methods.entry(name).or_default();
+        if !known.contains(&signature) {
+            known.push(signature);
+        }
+    }
+
+    visit_module_exprs(program, &mut |scope, expr| match &mut expr.kind {
+        ExprKind::Call { func, config, .. } => match &func.kind {
+            ExprKind::Variable(name) => {
+                let name = name.to_string();
+                let signature = find(&functions, scope, std::slice::from_ref(&name));
+                order_config_args(&name, signature, config, errors);
+            }
+            // Functions of another module: users::create(name; admin: true),
+            // and associated functions: User::create(name; admin: true)
+            ExprKind::Path(path) => {
+                let path: Vec<String> = path.iter().map(|p| p.to_string()).collect();
+                let callee = path.join("::");
+                match find(&functions, scope, &path) {
+                    Some(signature) => order_config_args(&callee, Some(signature), config, errors),
+                    None => {
+                        let signatures = path.last().and_then(|name| methods.get(name));
+                        order_method_config_args(&callee, signatures, config, errors)
+                    }
+                }
+            }
+            _ => order_config_args("this function", None, config, errors),
+        },
+        ExprKind::MethodCall { method, config, .. }
+        | ExprKind::SafeMethodCall { method, config, .. } => {
+            let callee = method.to_string();
+            order_method_config_args(&callee, methods.get(&callee), config, errors);
+        }
+        _ => {}
+    });
+}
+
+fn order_method_config_args(
+    callee: &str,
+    signatures: Option<&Vec<Signature>>,
+    config: &mut Vec<ConfigArg>,
+    errors: &mut Vec<String>,
+) {
+    match signatures.map(Vec::as_slice) {
+        Some([signature]) => order_config_args(callee, Some(signature), config, errors),
+        Some(signatures) if signatures.iter().any(|s| !s.config.is_empty()) => {
+            errors.push(format!(
+                "Cannot resolve the config arguments of `{}`: it is declared with different parameters",
+                callee
+            ));
+        }
+        _ => order_config_args(callee, None, config, errors),
+    }
+}
+
+fn order_config_args(
+    callee: &str,
+    signature: Option<&Signature>,
+    config: &mut Vec<ConfigArg>,
+    errors: &mut Vec<String>,
+) {
+    let Some(signature) = signature.filter(|s| !s.config.is_empty()) else {
+        if !config.is_empty() {
+            errors.push(format!("`{}` takes no config arguments", callee));
+        }
+        return;
+    };
+
+    let mut values: Vec<Option<ConfigArg>> = vec![None; signature.config.len()];
+    for arg in config.drain(..) {
+        let Some(name) = &arg.name else {
+            errors.push(format!(
+                "Positional argument after `;` in call to `{}`; config arguments are passed as `name: value`",
+                callee
+            ));
+            continue;
+        };
+        let name = name.to_string();
+        match signature.config.iter().position(|param| *param == name) {
+            Some(i) if values[i].is_some() => errors.push(format!(
+                "Config argument `{}` is passed twice in call to `{}`",
+                name, callee
+            )),
+            Some(i) => values[i] = Some(arg),
+            None if signature.subject.contains(&name) => errors.push(format!(
+                "`{}` is a subject argument of `{}` and goes before `;`",
+                name, callee
+            )),
+            None => errors.push(format!(
+                "Unknown config argument `{}` for `{}`",
+                name, callee
+            )),
+        }
+    }
+    *config = signature
+        .config
+        .iter()
+        .zip(values)
+        .map(|(name, arg)| {
+            // A parameter that keeps its default has no place in the call
+            arg.unwrap_or_else(|| ConfigArg {
+                name: Some(Ident::new(name, 0..0)),
+                value: None,
+            })
+        })
+        .collect();
+}
+
+// --- Nullable types ---
+
+/// Kap 3.5: `user?.profile` is `Profile?` whether `profile` is `Profile` or
+/// `Profile?`. The backends need to know which, so `?.` accesses are marked
+/// `nullable` when the field or the method's return type is nullable itself.
+/// Like config arguments, members are resolved by name.
+fn resolve_safe_access(program: &mut Program, errors: &mut Vec<String>) {
+    let mut fields: HashMap<String, Vec<bool>> = HashMap::new();
+    let mut methods: HashMap<String, Vec<bool>> = HashMap::new();
+    for item in program.all_items() {
+        match item {
+            Item::Struct { fields: defs, .. } => {
+                for field in defs {
+                    record(&mut fields, field.name.to_string(), field.ty.nullable);
+                }
+            }
+            Item::Impl { methods: defs, .. } => {
+                for method in defs {
+                    if let Item::Fn { name, ret_type, .. } = method {
+                        let nullable = ret_type.as_ref().is_some_and(|t| t.nullable);
+                        record(&mut methods, name.to_string(), nullable);
+                    }
+                }
+            }
+            Item::Trait { methods: defs, .. } => {
+                for method in defs {
+                    let nullable = method.ret_type.as_ref().is_some_and(|t| t.nullable);
+                    record(&mut methods, method.name.to_string(), nullable);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    visit_exprs(program, &mut |expr| {
+        let (name, nullable, declared) = match &mut expr.kind {
+            ExprKind::SafeField {
+                field, nullable, ..
+            } => (field.to_string(), nullable, &fields),
+            ExprKind::SafeMethodCall {
+                method, nullable, ..
+            } => (method.to_string(), nullable, &methods),
+            _ => return,
+        };
+        match declared.get(&name).map(Vec::as_slice) {
+            Some([flag]) => *nullable = *flag,
+            Some([_, _]) => errors.push(format!(
+                "Cannot tell whether `?.{}` is nullable: `{}` is declared both as `T` and as `T?`",
+                name, name
+            )),
+            _ => {}
+        }
+    });
+}
+
+/// Collects the distinct nullability flags a member name is declared with.
+fn record(declared: &mut HashMap<String, Vec<bool>>, name: String, nullable: bool) {
+    let flags = declared.entry(name).or_default();
+    if !flags.contains(&nullable) {
+        flags.push(nullable);
+    }
+}
+
+// --- Errors ---
+
+/// The error types callees declare in `throws`, by function path and by
+/// method name.
+struct Throwing {
+    functions: HashMap<String, Vec<String>>,
+    methods: HashMap<String, Vec<Vec<String>>>,
+}
+
+impl Throwing {
+    fn collect(program: &Program, errors: &mut Vec<String>) -> Self {
+        let mut throwing = Throwing {
+            functions: HashMap::new(),
+            methods: HashMap::new(),
+        };
+        let mut declare = |name: String, throws: &[Type], is_method: bool| {
+            let types: Vec<String> = throws.iter().map(|t| t.name.to_string()).collect();
+            for (i, ty) in types.iter().enumerate() {
+                if types[..i].contains(ty) {
+                    errors.push(format!(
+                        "`{}` is listed twice in the `throws` clause of `{}`",
+                        ty, name
+                    ));
+                }
+            }
+            if is_method {
+                let known = throwing.methods.entry(name).or_default();
+                if !known.contains(&types) {
+                    known.push(types);
+                }
+            } else {
+                if name == "main" && !types.is_empty() {
+                    errors.push(
+                        "`main` cannot declare `throws`; handle its errors with `catch`"
+                            .to_string(),
+                    );
+                }
+                throwing.functions.insert(name, types);
+            }
+        };
+
+        for (module, item) in module_items(&program.items, &mut Vec::new()) {
+            match item {
+                Item::Fn { name, throws, .. } => {
+                    declare(loader::item_path(&module, name.as_str()), throws, false)
+                }
+                Item::Impl { methods, .. } => {
+                    for method in methods {
+                        if let Item::Fn { name, throws, .. } = method {
+                            declare(name.to_string(), throws, true);
+                        }
+                    }
+                }
+                Item::Trait { methods, .. } => {
+                    for method in methods {
+                        declare(method.name.to_string(), &method.throws, true);
+                    }
+                }
+                _ => {}
+            }
+        }
+        throwing
+    }
+
+    /// The callee of a call that can throw, with the errors it declares.
+    /// `scope` is how the calling module names functions.
+    fn thrown_by(
+        &self,
+        expr: &Expr,
+        scope: &ModuleScope,
+        errors: &mut Vec<String>,
+    ) -> Option<(String, &[String])> {
+        let (callee, declared) = match &expr.kind {
+            ExprKind::Call { func, .. } => match &func.kind {
+                ExprKind::Variable(name) => {
+                    let name = name.to_string();
+                    let types = find(&self.functions, scope, std::slice::from_ref(&name))?;
+                    return (!types.is_empty()).then_some((name, types.as_slice()));
+                }
+                // Functions of another module: users::load(path), and
+                // associated functions: Config::load(path)
+                ExprKind::Path(path) => {
+                    let path: Vec<String> = path.iter().map(|p| p.to_string()).collect();
+                    let callee = path.join("::");
+                    if let Some(types) = find(&self.functions, scope, &path) {
+                        return (!types.is_empty()).then_some((callee, types.as_slice()));
+                    }
+                    (callee, self.methods.get(path.last()?)?)
+                }
+                _ => return None,
+            },
+            ExprKind::MethodCall { method, .. } | ExprKind::SafeMethodCall { method, .. } => {
+                let method = method.to_string();
+                let declared = self.methods.get(&method)?;
+                (method, declared)
+            }
+            _ => return None,
+        };
+
+        match declared.as_slice() {
+            [types] if types.is_empty() => None,
+            [_] if matches!(expr.kind, ExprKind::SafeMethodCall { .. }) => {
+                errors.push(format!(
+                    "`{}` throws and cannot be called with `?.`; check for null first",
+                    callee
+                ));
+                None
+            }
+            [types] => Some((callee, types.as_slice())),
+            _ => {
+                errors.push(format!(
+                    "Cannot tell whether `{}` throws: it is declared with different `throws` clauses",
+                    callee
+                ));
+                None
+            }
+        }
+    }
+}
+
+/// The function whose body is checked, with the errors it may let through.
+struct ThrowScope<'a> {
+    caller: String,
+    throws: Vec<String>,
+    callees: &'a Throwing,
+    module: &'a ModuleScope,
+}
+
+/// Kap 7.1: errors bubble up. A call to a function that `throws` is wrapped
+/// in `ExprKind::Propagate` unless a `catch` handles it, and every error type
+/// that can reach the caller has to be in the caller's own `throws` clause.
+/// Lambdas and spawned tasks run on their own, so calls in them need a
+/// `catch`. Like config arguments, methods are resolved by name.
+fn resolve_throws(program: &mut Program, errors: &mut Vec<String>) {
+    let callees = Throwing::collect(program, errors);
+    for_each_module_item(&mut program.items, &mut Vec::new(), &mut |module, item| {
+        let scope = |caller: String, throws: &[Type]| ThrowScope {
+            caller,
+            throws: throws.iter().map(|t| t.name.to_string()).collect(),
+            callees: &callees,
+            module,
+        };
+        match item {
+            Item::Fn {
+                name, throws, body, ..
+            } => propagate_in_block(body, &scope(name.to_string(), throws), errors),
+            Item::Impl { methods, .. } => {
+                for method in methods {
+                    if let Item::Fn {
+                        name, throws, body, ..
+                    } = method
+                    {
+                        propagate_in_block(body, &scope(name.to_string(), throws), errors);
+                    }
+                }
+            }
+            Item::Trait { methods, .. } => {
+                for method in methods {
+                    if let Some(body) = &mut method.default {
+                        let scope = scope(method.name.to_string(), &method.throws);
+                        propagate_in_block(body, &scope, errors);
+                    }
+                }
+            }
+            Item::Test { name, body, .. } | Item::Bench { name, body, .. } => {
+                propagate_in_block(body, &scope(format!("\"{}\"", name), &[]), errors);
+            }
+            _ => {}
+        }
+    });
+}
+
+fn propagate_in_block(block: &mut Block, scope: &ThrowScope, errors: &mut Vec<String>) {
+    for_each_block_expr(block, &mut |expr| {
+        propagate_errors(expr, scope, false, errors)
+    });
+}
+
+fn propagate_errors(
+    expr: &mut Expr,
+    scope: &ThrowScope,
+    in_lambda: bool,
+    errors: &mut Vec<String>,
+) {
+    match &mut expr.kind {
+        ExprKind::TryCatch {
+            expr: operand,
+            handler,
+        } => {
+            if scope
+                .callees
+                .thrown_by(operand, scope.module, errors)
+                .is_none()
+            {
+                errors.push(format!(
+                    "`catch` in `{}` needs a call to a function that throws",
+                    scope.caller
+                ));
+            }
+            // The caught call itself doesn't propagate, its arguments still do
+            for_each_child(operand, &mut |child| {
+                propagate_errors(child, scope, in_lambda, errors)
+            });
+            for_each_block_expr(handler, &mut |child| {
+                propagate_errors(child, scope, in_lambda, errors)
+            });
+            return;
+        }
+        ExprKind::Lambda { .. } | ExprKind::Spawn { .. } => {
+            for_each_child(expr, &mut |child| {
+                propagate_errors(child, scope, true, errors)
+            });
+            return;
+        }
+        ExprKind::Throw { .. } if in_lambda => errors.push(format!(
+            "`throw` inside a lambda in `{}`; errors cannot leave a lambda",
+            scope.caller
+        )),
+        ExprKind::Throw { .. } if scope.throws.is_empty() => errors.push(format!(
+            "`throw` in `{}`, which does not declare `throws`",
+            scope.caller
+        )),
+        _ => {}
+    }
+
+    for_each_child(expr, &mut |child| {
+        propagate_errors(child, scope, in_lambda, errors)
+    });
+    let Some((callee, thrown)) = scope.callees.thrown_by(expr, scope.module, errors) else {
+        return;
+    };
+    if in_lambda {
+        errors.push(format!(
+            "`{}` throws inside a lambda in `{}`; handle its errors with `catch`",
+            callee, scope.caller
+        ));
+        return;
+    }
+    for error in thrown.iter().filter(|e| !scope.throws.contains(e)) {
+        errors.push(format!(
+            "`{}` can throw `{}`, which `{}` does not declare; add it to `throws` or handle it with `catch`",
+            callee, error, scope.caller
+        ));
+    }
+    // The propagating call spans the same bytes as the call itself
+    let span = expr.span.clone();
+    let call = std::mem::replace(expr, Expr::new(ExprKind::Continue, span.clone()));
+    *expr = Expr::new(ExprKind::Propagate(Box::new(call)), span);
+}
+
+// --- Privacy ---
+
+/// What each module declares, for the privacy checks.
+#[derive(Default)]
+struct Declarations {
+    // Module -> item name -> is_pub
+    items: HashMap<String, HashMap<String, bool>>,
+    // Field name -> (struct, module, is_pub) of each declaration
+    fields: HashMap<String, Vec<(String, String, bool)>>,
+    // Method name -> (type or trait, module, is_pub) of each declaration
+    methods: HashMap<String, Vec<(String, String, bool)>>,
+    // Struct path (`users::User`) -> module and its private fields
+    private_fields: HashMap<String, (String, Vec<String>)>,
+    // Struct path -> module and whether its constructor is `pub`
+    constructors: HashMap<String, (String, bool)>,
+}
+
+impl Declarations {
+    fn collect(items: &[Item], module: &mut Vec<String>, errors: &mut Vec<String>) -> Self {
+        let mut declarations = Declarations::default();
+        declarations.declare(items, module, errors);
+        declarations
+    }
+
+    fn declare(&mut self, items: &[Item], module: &mut Vec<String>, errors: &mut Vec<String>) {
+        let module_name = loader::module_name(module);
+        for item in items {
+            match item {
+                Item::Fn { name, is_pub, .. } | Item::Enum { name, is_pub, .. } => {
+                    self.declare_item(&module_name, name, *is_pub);
+                }
+                Item::Trait {
+                    name,
+                    is_pub,
+                    methods,
+                    ..
+                } => {
+                    self.declare_item(&module_name, name, *is_pub);
+                    for method in methods {
+                        self.declare_method(&method.name, name, &module_name, true);
+                    }
+                }
+                Item::Struct {
+                    name,
+                    is_pub,
+                    fields,
+                    ..
+                } => {
+                    self.declare_item(&module_name, name, *is_pub);
+                    for field in fields {
+                        self.fields
+                            .entry(field.name.to_string())
+                            .or_default()
+                            .push((name.to_string(), module_name.clone(), field.is_pub));
+                    }
+                    let private = fields
+                        .iter()
+                        .filter(|f| !f.is_pub)
+                        .map(|f| f.name.to_string())
+                        .collect();
+                    self.private_fields.insert(
+                        loader::item_path(module, name.as_str()),
+                        (module_name.clone(), private),
+                    );
+                }
+                Item::Impl {
+                    trait_ref: None,
+                    target,
+                    methods,
+                    ..
+                } => {
+                    for method in methods {
+                        let Item::Fn {
+                            name,
+                            is_pub,
+                            receiver,
+                            ..
+                        } = method
+                        else {
+                            continue;
+                        };
+                        if *name == CONSTRUCTOR && receiver.is_none() {
+                            self.constructors.insert(
+                                loader::item_path(module, target.name.as_str()),
+                                (module_name.clone(), *is_pub),
+                            );
+                        } else {
+                            self.declare_method(name, &target.name, &module_name, *is_pub);
+                        }
+                    }
+                }
+                Item::Impl {
+                    trait_ref: Some(trait_ref),
+                    target,
+                    methods,
+                    ..
+                } => {
+                    for method in methods {
+                        let Item::Fn { name, is_pub, .. } = method else {
+                            continue;
+                        };
+                        if *is_pub {
+                            errors.push(format!(
+                                "`pub` on `{}` in `impl {} for {}`: trait methods take the trait's visibility",
+                                name, trait_ref.name, target.name
+                            ));
+                        }
+                        self.declare_method(name, &target.name, &module_name, true);
+                    }
+                }
+                Item::Module { name, items, .. } => {
+                    module.push(name.to_string());
+                    self.declare(items, module, errors);
+                    module.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Trait methods, also those of a trait impl, are declared `pub`: they
+    /// take the trait's visibility, which is checked as an item.
+    fn declare_method(&mut self, name: &Ident, owner: &Ident, module: &str, is_pub: bool) {
+        self.methods.entry(name.to_string()).or_default().push((
+            owner.to_string(),
+            module.to_string(),
+            is_pub,
+        ));
+    }
+
+    fn declare_item(&mut self, module: &str, name: &Ident, is_pub: bool) {
+        self.items
+            .entry(module.to_string())
+            .or_default()
+            .insert(name.to_string(), is_pub);
+    }
+
+    /// The declared item `path` names in `scope`, as a path from the crate
+    /// root: `http::get` in a module that imports `std::http` is
+    /// `std::http::get`.
+    fn resolve(&self, scope: &ModuleScope, path: &[String]) -> Option<Vec<String>> {
+        scope.candidates(path).into_iter().find_map(|candidate| {
+            let full: Vec<String> = candidate.split("::").map(str::to_string).collect();
+            let (name, module) = full.split_last()?;
+            let items = self.items.get(&loader::module_name(module))?;
+            items.contains_key(name).then_some(full)
+        })
+    }
+
+    /// `path` names an item from the crate root (`users::User`), as imports
+    /// do. Items of modules we don't know (`std::collections`) are left alone.
+    fn check_item(&self, path: &[String], from: &str, errors: &mut Vec<String>) {
+        let Some((name, module)) = path.split_last() else {
+            return;
+        };
+        let module = loader::module_name(module);
+        if module == from {
+            return;
+        }
+        let declared = self.items.get(&module).and_then(|items| items.get(name));
+        if declared == Some(&false) {
+            errors.push(format!(
+                "`{}` is private to module `{}`",
+                path.join("::"),
+                module
+            ));
+        }
+    }
+
+    /// Kap 4.2: only the declaring module may name the fields of a struct
+    /// with private fields; everyone else goes through its constructor.
+    fn check_struct_lit(&self, path: &[String], from: &str, errors: &mut Vec<String>) {
+        let (Some(name), Some((module, private))) =
+            (path.last(), self.private_fields.get(&path.join("::")))
+        else {
+            return;
+        };
+        if module != from && !private.is_empty() {
+            errors.push(format!(
+                "`{}` has private fields ({}) and cannot be built field by field outside module `{}`; call `{}(...)`",
+                name,
+                private.join(", "),
+                module,
+                name
+            ));
+        }
+    }
+
+    fn check_constructor(&self, target: &[String], from: &str, errors: &mut Vec<String>) {
+        let (Some(name), Some((module, is_pub))) =
+            (target.last(), self.constructors.get(&target.join("::")))
+        else {
+            return;
+        };
+        if module != from && !is_pub {
+            errors.push(format!(
+                "The constructor of `{}` is private to module `{}`",
+                name, module
+            ));
+        }
+    }
+
+    /// A field read or method call from another module must be `pub`. The
+    /// member is that of the receiver's type; when the type isn't known, the
+    /// use is only rejected if every member of that name is private to some
+    /// other module.
+    fn check_member(&self, used: &types::MemberUse, errors: &mut Vec<String>) {
+        let table = if used.is_method {
+            &self.methods
+        } else {
+            &self.fields
+        };
+        let Some(declared) = table.get(&used.name.to_string()) else {
+            return;
+        };
+        let candidates: Vec<&(String, String, bool)> = declared
+            .iter()
+            .filter(|(owner, ..)| used.owner.as_ref().is_none_or(|used| used == owner))
+            .collect();
+        let visible = candidates
+            .iter()
+            .any(|(_, module, is_pub)| *is_pub || *module == used.module);
+        if let (false, Some((owner, module, _))) = (visible, candidates.first()) {
+            let member = if used.is_method { "Method" } else { "Field" };
+            errors.push(format!(
+                "{} `{}` of `{}` is private to module `{}`",
+                member, used.name, owner, module
+            ));
+        }
+    }
+}
+
+/// Kap 9.2: items, fields and methods are private to the module (file) that
+/// declares them. Imports, calls through a module path (`users::create()`),
+/// field reads, method calls and constructor calls from another module need
+/// `pub`, and a struct
+/// with private fields can only be built field by field at home. Methods of a
+/// trait impl take the trait's visibility, so `pub` on one is a mistake.
+fn check_privacy(program: &mut Program, uses: &[types::MemberUse], errors: &mut Vec<String>) {
+    let declarations = Declarations::collect(&program.items, &mut Vec::new(), errors);
+    check_module_privacy(&mut program.items, &mut Vec::new(), &declarations, errors);
+    for used in uses {
+        declarations.check_member(used, errors);
+    }
+}
+
+fn check_module_privacy(
+    items: &mut [Item],
+    module: &mut Vec<String>,
+    declarations: &Declarations,
+    errors: &mut Vec<String>,
+) {
+    let module_name = loader::module_name(module);
+    let scope = ModuleScope::new(module, items);
+    for item in items {
+        match item {
+            Item::Import { path, .. } => {
+                let path: Vec<String> = path.split("::").map(str::to_string).collect();
+                declarations.check_item(&path, &module_name, errors);
+            }
+            Item::Module { name, items, .. } => {
+                module.push(name.to_string());
+                check_module_privacy(items, module, declarations, errors);
+                module.pop();
+            }
+            item => visit_item(item, &mut |expr| match &expr.kind {
+                ExprKind::Call { func, .. } => {
+                    if let ExprKind::Path(path) = &func.kind {
+                        if let [.., target, method] = path.as_slice() {
+                            if *method == CONSTRUCTOR {
+                                let struct_path: Vec<String> = path[..path.len() - 1]
+                                    .iter()
+                                    .map(|p| p.to_string())
+                                    .collect();
+                                if let Some(full) = declarations.resolve(&scope, &struct_path) {
+                                    declarations.check_constructor(&full, &module_name, errors);
+                                }
+                            } else {
+                                // User::create(): an associated function
+                                let used = types::MemberUse {
+                                    module: module_name.clone(),
+                                    name: method.clone(),
+                                    owner: Some(target.to_string()),
+                                    is_method: true,
+                                };
+                                declarations.check_member(&used, errors);
+                            }
+                        }
+                        let path: Vec<String> = path.iter().map(|p| p.to_string()).collect();
+                        if let Some(path) = declarations.resolve(&scope, &path) {
+                            declarations.check_item(&path, &module_name, errors);
+                        }
+                    }
+                }
+                ExprKind::StructLit { path, .. } => {
+                    let path: Vec<String> = path.iter().map(|p| p.to_string()).collect();
+                    if let Some(path) = declarations.resolve(&scope, &path) {
+                        declarations.check_struct_lit(&path, &module_name, errors);
+                        declarations.check_item(&path, &module_name, errors);
+                    }
+                }
+                _ => {}
+            }),
+        }
+    }
+}
+
+// --- AST traversal ---
+
+/// Calls `f` on every expression inside function bodies, outer expressions first.
+pub(crate) fn visit_exprs(program: &mut Program, f: &mut dyn FnMut(&mut Expr)) {
+    for item in program.all_items_mut() {
+        visit_item(item, f);
+    }
+}
+
+/// Like `visit_exprs`, with how the module of each expression names items.
+fn visit_module_exprs(program: &mut Program, f: &mut dyn FnMut(&ModuleScope, &mut Expr)) {
+    for_each_module_item(&mut program.items, &mut Vec::new(), &mut |scope, item| {
+        visit_item(item, &mut |expr| f(scope, expr))
+    });
+}
+
+/// Calls `f` on every item of `items` and their modules except the modules
+/// themselves, with how the item's module names items.
+fn for_each_module_item(
+    items: &mut [Item],
+    module: &mut Vec<String>,
+    f: &mut dyn FnMut(&ModuleScope, &mut Item),
+) {
+    let scope = ModuleScope::new(module, items);
+    for item in items {
+        match item {
+            Item::Module { name, items, .. } => {
+                module.push(name.to_string());
+                for_each_module_item(items, module, f);
+                module.pop();
+            }
+            item => f(&scope, item),
+        }
+    }
+}
+
+/// Every item of `items` and their modules except the modules themselves,
+/// with the path of its module.
+fn module_items<'p>(items: &'p [Item], module: &mut Vec<String>) -> Vec<(Vec<String>, &'p Item)> {
+    let mut out = Vec::new();
+    for item in items {
+        match item {
+            Item::Module { name, items, .. } => {
+                module.push(name.to_string());
+                out.extend(module_items(items, module));
+                module.pop();
+            }
+            item => out.push((module.clone(), item)),
+        }
+    }
+    out
+}
+
+/// The entry of `table`, keyed by full path, that `path` names in `scope`.
+fn find<'t, T>(
+    table: &'t HashMap<String, T>,
+    scope: &ModuleScope,
+    path: &[String],
+) -> Option<&'t T> {
+    scope
+        .candidates(path)
+        .iter()
+        .find_map(|candidate| table.get(candidate))
+}
+
+fn visit_item(item: &mut Item, f: &mut dyn FnMut(&mut Expr)) {
+    match item {
+        Item::Fn { body, .. } | Item::Test { body, .. } | Item::Bench { body, .. } => {
+            visit_block(body, f)
+        }
+        Item::Impl { methods, .. } => {
+            for method in methods {
+                visit_item(method, f);
+            }
+        }
+        Item::Trait { methods, .. } => {
+            for body in methods.iter_mut().filter_map(|m| m.default.as_mut()) {
+                visit_block(body, f);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn visit_block(block: &mut Block, f: &mut dyn FnMut(&mut Expr)) {
+    for_each_block_expr(block, &mut |expr| visit_expr(expr, f));
+}
+
+fn visit_expr(expr: &mut Expr, f: &mut dyn FnMut(&mut Expr)) {
+    f(expr);
+    for_each_child(expr, &mut |child| visit_expr(child, f));
+}
+
+/// Calls `f` on the top-level expressions of each statement in `block`.
+fn for_each_block_expr(block: &mut Block, f: &mut dyn FnMut(&mut Expr)) {
+    for stmt in &mut block.stmts {
+        match stmt {
+            Stmt::Let { value, .. } => f(value),
+            Stmt::Assign { target, value, .. } | Stmt::CompoundAssign { target, value, .. } => {
+                f(target);
+                f(value);
+            }
+            Stmt::Expr(expr) => f(expr),
+        }
+    }
+}
+
+/// Calls `f` on the direct subexpressions of `expr`.
+fn for_each_child(expr: &mut Expr, f: &mut dyn FnMut(&mut Expr)) {
+    match &mut expr.kind {
+        ExprKind::Block(block) | ExprKind::Loop { body: block } => for_each_block_expr(block, f),
+        ExprKind::If {
+            cond,
+            then_branch,
+            else_branch,
+        } => {
+            f(cond);
+            for_each_block_expr(then_branch, f);
+            if let Some(block) = else_branch {
+                for_each_block_expr(block, f);
+            }
+        }
+        ExprKind::While { cond, body }
+        | ExprKind::For {
+            iter: cond, body, ..
+        } => {
+            f(cond);
+            for_each_block_expr(body, f);
+        }
+        ExprKind::Binary { left, right, .. }
+        | ExprKind::Index {
+            expr: left,
+            index: right,
+        }
+        | ExprKind::Range {
+            start: left,
+            end: right,
+            ..
+        } => {
+            f(left);
+            f(right);
+        }
+        ExprKind::Unary { expr, .. }
+        | ExprKind::Field { expr, .. }
+        | ExprKind::SafeField { expr, .. }
+        | ExprKind::Spawn { body: expr, .. }
+        | ExprKind::Lambda { body: expr, .. }
+        | ExprKind::Throw { value: expr, .. }
+        | ExprKind::Propagate(expr)
+        | ExprKind::Nullable(expr)
+        | ExprKind::Break(Some(expr)) => f(expr),
+        ExprKind::List(items) => items.iter_mut().for_each(f),
+        ExprKind::StructLit { fields, .. } => {
+            fields.iter_mut().map(|field| &mut field.value).for_each(f)
+        }
+        ExprKind::Call {
+            func, args, config, ..
+        }
+        | ExprKind::MethodCall {
+            receiver: func,
+            args,
+            config,
+            ..
+        }
+        | ExprKind::SafeMethodCall {
+            receiver: func,
+            args,
+            config,
+            ..
+        } => {
+            f(func);
+            args.iter_mut().for_each(&mut *f);
+            config
+                .iter_mut()
+                .filter_map(|arg| arg.value.as_mut())
+                .for_each(f);
+        }
+        ExprKind::TryCatch { expr, handler } => {
+            f(expr);
+            for_each_block_expr(handler, f);
+        }
+        ExprKind::Match { expr, arms } => {
+            f(expr);
+            for arm in arms {
+                if let Some(guard) = &mut arm.guard {
+                    f(guard);
+                }
+                f(&mut arm.body);
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,621 @@
+// crates/nikaia/src/checker/types.rs
+// Kap 2: the types the backends cannot do without. Rust spells `+` on
+// strings differently from `+` on numbers, and the syntax alone only shows
+// which one is meant when a string literal is involved (`"Hi " + name`).
+// Likewise a value stored in a `T?` slot is `Some(value)` in Rust, which
+// nothing in `let nick: String? = name` spells out (Kap 2.3). This pass
+// follows the types of literals, variables, parameters, fields and return
+// values far enough to tell. Whatever it cannot tell is left as written;
+// rustc has the last word.
+use super::{find, for_each_module_item, module_items};
+use crate::ast::{
+    BinOp, Block, ConfigArg, Expr, ExprKind, FnArg, Ident, Item, Pattern, PatternFields, Program,
+    Stmt, Type, UnOp,
+};
+use crate::loader::{self, ModuleScope};
+use std::collections::HashMap;
+
+/// Methods of Rust's `String` that Nikaia code calls and that return one.
+const STRING_METHODS: &[&str] = &[
+    "to_string",
+    "to_uppercase",
+    "to_lowercase",
+    "repeat",
+    "replace",
+];
+
+/// The parameter and return types of a function or method.
+struct FnType {
+    args: Vec<Type>,
+    config: Vec<Type>,
+    ret: Option<Type>,
+}
+
+impl FnType {
+    fn new(args: &[FnArg], config: &[FnArg], ret: Option<Type>) -> Self {
+        FnType {
+            args: args.iter().map(|a| a.ty.clone()).collect(),
+            config: config.iter().map(|a| a.ty.clone()).collect(),
+            ret,
+        }
+    }
+}
+
+/// What the program declares: functions by full path, methods and fields by
+/// name together with the type that owns them.
+struct Declared {
+    functions: HashMap<String, FnType>,
+    methods: HashMap<String, Vec<(String, FnType)>>,
+    fields: HashMap<String, Vec<(String, Type)>>,
+    enums: Vec<String>,
+}
+
+impl Declared {
+    fn collect(program: &Program) -> Self {
+        let mut declared = Declared {
+            functions: HashMap::new(),
+            methods: HashMap::new(),
+            fields: HashMap::new(),
+            enums: Vec::new(),
+        };
+        for (module, item) in module_items(&program.items, &mut Vec::new()) {
+            match item {
+                Item::Fn {
+                    name,
+                    args,
+                    config,
+                    ret_type,
+                    ..
+                } => {
+                    let signature = FnType::new(args, config, ret_type.clone());
+                    let path = loader::item_path(&module, name.as_str());
+                    declared.functions.insert(path, signature);
+                }
+                Item::Struct { name, fields, .. } => {
+                    for field in fields {
+                        declared
+                            .fields
+                            .entry(field.name.to_string())
+                            .or_default()
+                            .push((name.to_string(), field.ty.clone()));
+                    }
+                }
+                Item::Enum { name, .. } => declared.enums.push(name.to_string()),
+                Item::Impl {
+                    target, methods, ..
+                } => {
+                    for method in methods {
+                        let Item::Fn {
+                            name,
+                            args,
+                            config,
+                            ret_type,
+                            ..
+                        } = method
+                        else {
+                            continue;
+                        };
+                        // `pub fn(...)` and `-> Self` return the impl's type
+                        let ret = match ret_type {
+                            Some(ty) if ty.name == "Self" => Some(target.clone()),
+                            None if *name == super::CONSTRUCTOR => Some(target.clone()),
+                            ret => ret.clone(),
+                        };
+                        declared
+                            .methods
+                            .entry(name.to_string())
+                            .or_default()
+                            .push((target.name.to_string(), FnType::new(args, config, ret)));
+                    }
+                }
+                Item::Trait { name, methods, .. } => {
+                    for method in methods {
+                        let signature =
+                            FnType::new(&method.args, &method.config, method.ret_type.clone());
+                        declared
+                            .methods
+                            .entry(method.name.to_string())
+                            .or_default()
+                            .push((name.to_string(), signature));
+                    }
+                }
+                _ => {}
+            }
+        }
+        declared
+    }
+
+    /// The method `name` of `owner`, or the only method of that name when the
+    /// owner is not known.
+    fn method(&self, owner: Option<&Type>, name: &str) -> Option<&FnType> {
+        member(self.methods.get(name)?, owner)
+    }
+
+    fn field(&self, owner: Option<&Type>, name: &str) -> Option<&Type> {
+        member(self.fields.get(name)?, owner)
+    }
+}
+
+fn member<'d, T>(declared: &'d [(String, T)], owner: Option<&Type>) -> Option<&'d T> {
+    match (owner, declared) {
+        (Some(owner), declared) => declared
+            .iter()
+            .find(|(name, _)| owner.name == *name)
+            .map(|(_, member)| member),
+        (None, [(_, member)]) => Some(member),
+        (None, _) => None,
+    }
+}
+
+/// A field read or method call, with the type it goes to when the pass
+/// knows that of the receiver. Privacy checks the member of that type
+/// (Kap 9.2).
+pub(super) struct MemberUse {
+    pub(super) module: String,
+    pub(super) name: Ident,
+    pub(super) owner: Option<String>,
+    pub(super) is_method: bool,
+}
+
+/// Kap 2: `+` with a `String` operand concatenates. The operator becomes
+/// `BinOp::Concat` wherever either side is known to be a string, not just a
+/// string literal.
+///
+/// Kap 2.3: a value of a known, non-nullable type that goes into a `T?` slot
+/// is wrapped in `ExprKind::Nullable`. The slots are annotated `let`s,
+/// assignments, arguments, struct fields and the value a function returns;
+/// `if`, `match` and blocks pass the slot on to the values they yield.
+///
+/// Returns the field reads and method calls of the program along the way.
+pub(super) fn resolve_types(program: &mut Program) -> Vec<MemberUse> {
+    let declared = Declared::collect(program);
+    let mut uses = Vec::new();
+    for_each_module_item(&mut program.items, &mut Vec::new(), &mut |module, item| {
+        let mut scope = TypeScope {
+            declared: &declared,
+            module,
+            vars: Vec::new(),
+            uses: &mut uses,
+        };
+        match item {
+            Item::Fn { .. } => scope.function(item, None),
+            Item::Impl {
+                target, methods, ..
+            } => {
+                for method in methods {
+                    scope.function(method, Some(target));
+                }
+            }
+            Item::Trait { methods, .. } => {
+                for method in methods {
+                    if let Some(body) = &mut method.default {
+                        scope.vars.push(params(&method.args, &method.config, None));
+                        scope.block(body, method.ret_type.as_ref());
+                        scope.vars.pop();
+                    }
+                }
+            }
+            Item::Test { body, .. } | Item::Bench { body, .. } => {
+                scope.block(body, None);
+            }
+            _ => {}
+        }
+    });
+    uses
+}
+
+/// The variables of a function body as it starts: `self` and the parameters.
+fn params(
+    args: &[FnArg],
+    config: &[FnArg],
+    receiver: Option<&Type>,
+) -> HashMap<String, Option<Type>> {
+    let mut vars: HashMap<String, Option<Type>> = args
+        .iter()
+        .chain(config)
+        .map(|arg| (arg.name.to_string(), Some(arg.ty.clone())))
+        .collect();
+    vars.insert("self".to_string(), receiver.cloned());
+    vars
+}
+
+/// The types known while walking one item: variables per block, innermost
+/// last. A variable of unknown type still shadows the outer ones.
+struct TypeScope<'a> {
+    declared: &'a Declared,
+    module: &'a ModuleScope,
+    vars: Vec<HashMap<String, Option<Type>>>,
+    uses: &'a mut Vec<MemberUse>,
+}
+
+impl<'a> TypeScope<'a> {
+    fn function(&mut self, item: &mut Item, receiver: Option<&Type>) {
+        if let Item::Fn {
+            args,
+            config,
+            ret_type,
+            body,
+            ..
+        } = item
+        {
+            self.vars.push(params(args, config, receiver));
+            self.block(body, ret_type.as_ref());
+            self.vars.pop();
+        }
+    }
+
+    fn declare(&mut self, name: &Ident, ty: Option<Type>) {
+        if let Some(vars) = self.vars.last_mut() {
+            vars.insert(name.to_string(), ty);
+        }
+    }
+
+    fn variable(&self, name: &Ident) -> Option<Option<Type>> {
+        self.vars
+            .iter()
+            .rev()
+            .find_map(|vars| vars.get(name.as_str()))
+            .cloned()
+    }
+
+    /// The type of the block's value: that of its last expression, which
+    /// goes into the block's slot.
+    fn block(&mut self, block: &mut Block, expected: Option<&Type>) -> Option<Type> {
+        self.vars.push(HashMap::new());
+        let mut ty = None;
+        let last = block.stmts.len().saturating_sub(1);
+        for (i, stmt) in block.stmts.iter_mut().enumerate() {
+            ty = match stmt {
+                Stmt::Expr(expr) if i == last => self.expr(expr, expected),
+                stmt => self.stmt(stmt),
+            };
+        }
+        self.vars.pop();
+        ty
+    }
+
+    fn stmt(&mut self, stmt: &mut Stmt) -> Option<Type> {
+        match stmt {
+            Stmt::Let {
+                name, ty, value, ..
+            } => {
+                let inferred = self.expr(value, ty.as_ref());
+                self.declare(name, ty.clone().or(inferred));
+                None
+            }
+            Stmt::Assign { target, value, .. } => {
+                let slot = self.expr(target, None);
+                self.expr(value, slot.as_ref());
+                None
+            }
+            Stmt::CompoundAssign {
+                target, op, value, ..
+            } => {
+                let target = self.expr(target, None);
+                let value = self.expr(value, None);
+                if *op == BinOp::Add && (is_string(&target) || is_string(&value)) {
+                    *op = BinOp::Concat;
+                }
+                None
+            }
+            Stmt::Expr(expr) => self.expr(expr, None),
+        }
+    }
+
+    /// The type of `expr`, which goes into a slot of type `expected`.
+    fn expr(&mut self, expr: &mut Expr, expected: Option<&Type>) -> Option<Type> {
+        let ty = self.infer(expr, expected)?;
+        if !expected.is_some_and(|slot| slot.nullable) || ty.nullable {
+            return Some(ty);
+        }
+        let span = expr.span.clone();
+        let value = std::mem::replace(expr, Expr::new(ExprKind::LitNull, span.clone()));
+        *expr = Expr::new(ExprKind::Nullable(Box::new(value)), span);
+        Some(Type {
+            nullable: true,
+            ..ty
+        })
+    }
+
+    /// The type of `expr`; `if`, `match` and blocks pass `expected` on.
+    fn infer(&mut self, expr: &mut Expr, expected: Option<&Type>) -> Option<Type> {
+        // `?.` yields null when the receiver is null
+        let safe = matches!(
+            expr.kind,
+            ExprKind::SafeField { .. } | ExprKind::SafeMethodCall { .. }
+        );
+        match &mut expr.kind {
+            ExprKind::LitInt(_) => Some(named("i32")),
+            ExprKind::LitStr(_) => Some(named("String")),
+            ExprKind::LitBool(_) => Some(named("bool")),
+            ExprKind::Variable(name) => self.variable(name).flatten(),
+            // Message::Quit
+            ExprKind::Path(path) => self.variant_of(path),
+            ExprKind::Block(block) => self.block(block, expected),
+            ExprKind::If {
+                cond,
+                then_branch,
+                else_branch,
+            } => {
+                self.expr(cond, None);
+                let then_ty = self.block(then_branch, expected);
+                let else_ty = else_branch
+                    .as_mut()
+                    .map(|block| self.block(block, expected))?;
+                then_ty.or(else_ty)
+            }
+            ExprKind::Binary { op, left, right } => {
+                let left = self.expr(left, None);
+                let right = self.expr(right, None);
+                match op {
+                    BinOp::Add if is_string(&left) || is_string(&right) => {
+                        *op = BinOp::Concat;
+                        Some(named("String"))
+                    }
+                    BinOp::Concat => Some(named("String")),
+                    BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div | BinOp::Rem => {
+                        left.or(right)
+                    }
+                    // The fallback decides whether `a ?? b` can still be null
+                    BinOp::Coalesce => right.or(left.map(|ty| Type {
+                        nullable: false,
+                        ..ty
+                    })),
+                    _ => Some(named("bool")),
+                }
+            }
+            ExprKind::Unary { op, expr } => {
+                let ty = self.expr(expr, None);
+                match op {
+                    UnOp::Neg => ty,
+                    UnOp::Not => Some(named("bool")),
+                }
+            }
+            ExprKind::While { cond, body } => {
+                self.expr(cond, None);
+                self.block(body, None);
+                None
+            }
+            ExprKind::Loop { body } => {
+                self.block(body, None);
+                None
+            }
+            ExprKind::For { var, iter, body } => {
+                // for i in 0..5, for user in users: List[User]
+                let iterated = self.expr(iter, None);
+                let item = match iter.kind {
+                    ExprKind::Range { .. } => Some(named("i32")),
+                    _ => iterated.and_then(|ty| ty.generics.first().cloned()),
+                };
+                self.vars.push(HashMap::from([(var.to_string(), item)]));
+                self.block(body, None);
+                self.vars.pop();
+                None
+            }
+            ExprKind::Range { start, end, .. } => {
+                self.expr(start, None);
+                self.expr(end, None);
+                None
+            }
+            ExprKind::List(items) => {
+                let slot = expected.and_then(|ty| ty.generics.first());
+                let mut item = None;
+                for expr in items {
+                    item = self.expr(expr, slot).or(item);
+                }
+                Some(Type {
+                    generics: item.into_iter().collect(),
+                    ..named("List")
+                })
+            }
+            ExprKind::Break(value) => {
+                if let Some(value) = value {
+                    self.expr(value, None);
+                }
+                None
+            }
+            ExprKind::Call { func, args, config } => {
+                let callee = self.callee(func);
+                self.args(args, config, callee);
+                match callee {
+                    Some(callee) => callee.ret.clone(),
+                    // Shape::Circle(1)
+                    None => match &func.kind {
+                        ExprKind::Path(path) => self.variant_of(path),
+                        _ => None,
+                    },
+                }
+            }
+            ExprKind::MethodCall {
+                receiver,
+                method,
+                args,
+                config,
+            }
+            | ExprKind::SafeMethodCall {
+                receiver,
+                method,
+                args,
+                config,
+                ..
+            } => {
+                let owner = self.expr(receiver, None);
+                self.used(method, owner.as_ref(), true);
+                let declared = self.declared;
+                let signature = declared.method(owner.as_ref(), method.as_str());
+                self.args(args, config, signature);
+                let ty = match signature {
+                    Some(signature) => signature.ret.clone(),
+                    None if STRING_METHODS.contains(&method.as_str()) => Some(named("String")),
+                    None => None,
+                };
+                ty.map(|ty| Type {
+                    nullable: ty.nullable || safe,
+                    ..ty
+                })
+            }
+            ExprKind::StructLit {
+                path,
+                fields,
+                variant,
+            } => {
+                // Message::Move(x: 1, y: 2) is a Message
+                let owner = if *variant {
+                    path.len() - 2
+                } else {
+                    path.len() - 1
+                };
+                let ty = path.get(owner).map(|name| named(name.as_str()));
+                let declared = self.declared;
+                for field in fields.iter_mut() {
+                    let slot = declared.field(ty.as_ref(), field.name.as_str());
+                    self.expr(&mut field.value, slot);
+                }
+                ty
+            }
+            // scores["Player1"]: the value type of Map[String, i32]
+            ExprKind::Index { expr, index } => {
+                self.expr(index, None);
+                self.expr(expr, None)
+                    .and_then(|ty| ty.generics.last().cloned())
+            }
+            ExprKind::Field { expr: owner, field }
+            | ExprKind::SafeField {
+                expr: owner, field, ..
+            } => {
+                let owner = self.expr(owner, None);
+                self.used(field, owner.as_ref(), false);
+                let ty = self.declared.field(owner.as_ref(), field.as_str()).cloned();
+                ty.map(|ty| Type {
+                    nullable: ty.nullable || safe,
+                    ..ty
+                })
+            }
+            ExprKind::Lambda { params, body, .. } => {
+                self.vars
+                    .push(params.iter().map(|p| (p.to_string(), None)).collect());
+                self.expr(body, None);
+                self.vars.pop();
+                None
+            }
+            ExprKind::Spawn { body, .. } => {
+                self.expr(body, None);
+                None
+            }
+            ExprKind::TryCatch { expr, handler } => {
+                let ty = self.expr(expr, None);
+                self.vars.push(HashMap::from([("error".to_string(), None)]));
+                self.block(handler, None);
+                self.vars.pop();
+                ty
+            }
+            ExprKind::Throw { value, .. } => {
+                self.expr(value, None);
+                None
+            }
+            ExprKind::Propagate(call) => self.expr(call, None),
+            ExprKind::Match { expr, arms } => {
+                self.expr(expr, None);
+                let mut ty = None;
+                for arm in arms {
+                    let mut bound = HashMap::new();
+                    bindings(&arm.pattern, &mut bound);
+                    self.vars.push(bound);
+                    if let Some(guard) = &mut arm.guard {
+                        self.expr(guard, None);
+                    }
+                    ty = ty.or(self.expr(&mut arm.body, expected));
+                    self.vars.pop();
+                }
+                ty
+            }
+            _ => None,
+        }
+    }
+
+    fn used(&mut self, name: &Ident, owner: Option<&Type>, is_method: bool) {
+        self.uses.push(MemberUse {
+            module: self.module.name(),
+            name: name.clone(),
+            owner: owner.map(|ty| ty.name.to_string()),
+            is_method,
+        });
+    }
+
+    /// Arguments go into the slots of their parameters, config arguments
+    /// into those of the config parameters, which are in order by now.
+    fn args(&mut self, args: &mut [Expr], config: &mut [ConfigArg], callee: Option<&FnType>) {
+        for (i, arg) in args.iter_mut().enumerate() {
+            self.expr(arg, callee.and_then(|callee| callee.args.get(i)));
+        }
+        for (i, arg) in config.iter_mut().enumerate() {
+            if let Some(value) = &mut arg.value {
+                self.expr(value, callee.and_then(|callee| callee.config.get(i)));
+            }
+        }
+    }
+
+    /// The function a call goes to, when it is declared in the program:
+    /// `create(...)`, `users::create(...)` or `User::create(...)`.
+    fn callee(&mut self, func: &mut Expr) -> Option<&'a FnType> {
+        let path: Vec<String> = match &func.kind {
+            ExprKind::Variable(name) if self.variable(name).is_none() => vec![name.to_string()],
+            ExprKind::Path(path) => path.iter().map(|p| p.to_string()).collect(),
+            _ => {
+                self.expr(func, None);
+                return None;
+            }
+        };
+        let declared = self.declared;
+        if let Some(function) = find(&declared.functions, self.module, &path) {
+            return Some(function);
+        }
+        let [.., owner, name] = path.as_slice() else {
+            return None;
+        };
+        declared.method(Some(&named(owner)), name)
+    }
+
+    /// The enum of `Shape::Circle`.
+    fn variant_of(&self, path: &[Ident]) -> Option<Type> {
+        let [.., owner, _] = path else {
+            return None;
+        };
+        self.declared
+            .enums
+            .iter()
+            .any(|name| owner == name)
+            .then(|| named(owner.as_str()))
+    }
+}
+
+/// The names a pattern binds; their types are not tracked.
+fn bindings(pattern: &Pattern, bound: &mut HashMap<String, Option<Type>>) {
+    match pattern {
+        Pattern::Binding(name) => {
+            bound.insert(name.to_string(), None);
+        }
+        Pattern::Variant { fields, .. } => match fields {
+            PatternFields::Unit => {}
+            PatternFields::Tuple(items) => items.iter().for_each(|p| bindings(p, bound)),
+            PatternFields::Struct(fields) => {
+                fields.iter().for_each(|f| bindings(&f.pattern, bound))
+            }
+        },
+        Pattern::Or(alternatives) => alternatives.iter().for_each(|p| bindings(p, bound)),
+        _ => {}
+    }
+}
+
+fn named(name: &str) -> Type {
+    Type {
+        name: Ident::new(name, 0..0),
+        generics: Vec::new(),
+        nullable: false,
+        span: 0..0,
+    }
+}
+
+fn is_string(ty: &Option<Type>) -> bool {
+    ty.as_ref().is_some_and(|ty| ty.name == "String")
+}
@@ -1,105 +1,1322 @@
 // crates/nikaia/src/interpreter/mod.rs
-use crate::ast::{Block, Expr, Item, Program, Stmt};
+use crate::ast::{
+    BinOp, Block, ConfigArg, EnumVariant, Expr, ExprKind, FnArg, Ident, Item, Pattern,
+    PatternFields, Program, Receiver, SourceMap, Stmt, TraitMethod, UnOp, VariantData,
+};
+use crate::loader::{self, ModuleScope};
+use std::collections::HashMap;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
 
-pub struct Interpreter;
+/// A runtime value produced by evaluating an expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Unit,
+    Int(i64),
+    Str(String),
+    Bool(bool),
+    List(Vec<Value>),
+    // Insertion-ordered; values aren't hashable
+    Map(Vec<(Value, Value)>),
+    // Half-open: 0..=5 is stored as 0..6
+    Range(i64, i64),
+    Struct {
+        name: String,
+        fields: Vec<(String, Value)>,
+    },
+    // Tuple variants name their fields "0", "1", ...
+    Enum {
+        name: String,
+        variant: String,
+        fields: Vec<(String, Value)>,
+    },
+    // Lambdas and named functions used as values
+    Function(Rc<Closure>),
+    // Kap 2.3: the value of a `T?` that holds nothing
+    Null,
+    // Kap 7.1: a thrown error, as `catch` binds it
+    Error(Box<Thrown>),
+}
+
+/// A thrown error with where it was thrown and the calls it propagated
+/// through, innermost first. Prints like the compiled backend's errors:
+///
+/// ```text
+/// error: IoError
+///   thrown at main.nika:3:24
+///   via read at main.nika:8:13
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Thrown {
+    error: Value,
+    trace: Vec<String>,
+}
+
+/// A runtime fault such as a division by zero. Like a panic of the compiled
+/// binary it ends the program: `catch` doesn't see it, and nothing after it
+/// runs.
+struct Fault(&'static str);
+
+/// Unwinds to `Interpreter::run`. `resume_unwind` skips the panic hook, so
+/// only the runtime's own message is printed.
+fn fault(message: &'static str) -> ! {
+    panic::resume_unwind(Box::new(Fault(message)))
+}
+
+/// A callable value. Lambdas capture the variables visible where they are
+/// created, by value.
+#[derive(Debug)]
+pub struct Closure {
+    params: Vec<String>,
+    body: Expr,
+    captured: Vec<(String, Value)>,
+    // The source of the module it was created in, whose names its body uses
+    source: usize,
+}
+
+// Functions compare by identity
+impl PartialEq for Closure {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+impl Value {
+    /// The Nikaia type name used to look up methods for this value.
+    fn type_name(&self) -> &str {
+        match self {
+            Value::Unit => "()",
+            Value::Int(_) => "i32",
+            Value::Str(_) => "String",
+            Value::Bool(_) => "bool",
+            Value::List(_) => "List",
+            Value::Map(_) => "HashMap",
+            Value::Range(..) => "Range",
+            Value::Struct { name, .. } | Value::Enum { name, .. } => name,
+            Value::Function(_) => "fn",
+            Value::Null => "null",
+            Value::Error(thrown) => thrown.error.type_name(),
+        }
+    }
+
+    fn field(&self, field: &str) -> Option<&Value> {
+        match self {
+            Value::Error(thrown) => thrown.error.field(field),
+            Value::Struct { fields, .. } | Value::Enum { fields, .. } => {
+                fields.iter().find(|(n, _)| n == field).map(|(_, v)| v)
+            }
+            _ => None,
+        }
+    }
+
+    fn field_mut(&mut self, field: &str) -> Option<&mut Value> {
+        match self {
+            Value::Struct { fields, .. } => {
+                fields.iter_mut().find(|(n, _)| n == field).map(|(_, v)| v)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Unit => write!(f, "()"),
+            Value::Null => write!(f, "null"),
+            Value::Int(i) => write!(f, "{}", i),
+            Value::Str(s) => write!(f, "{}", s),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::List(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            Value::Map(entries) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", key, value)?;
+                }
+                write!(f, "}}")
+            }
+            Value::Range(start, end) => write!(f, "{}..{}", start, end),
+            Value::Function(closure) => write!(f, "fn({})", closure.params.join(", ")),
+            Value::Error(thrown) => {
+                write!(f, "error: {}", thrown.error.type_name())?;
+                for frame in &thrown.trace {
+                    write!(f, "\n  {}", frame)?;
+                }
+                Ok(())
+            }
+            Value::Struct { name, fields } => {
+                write!(f, "{} {{ ", name)?;
+                for (i, (field, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", field, value)?;
+                }
+                write!(f, " }}")
+            }
+            Value::Enum {
+                variant, fields, ..
+            } => {
+                write!(f, "{}", variant)?;
+                if fields.is_empty() {
+                    return Ok(());
+                }
+                let is_tuple = fields[0].0 == "0";
+                write!(f, "{}", if is_tuple { "(" } else { " { " })?;
+                for (i, (field, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    if is_tuple {
+                        write!(f, "{}", value)?;
+                    } else {
+                        write!(f, "{}: {}", field, value)?;
+                    }
+                }
+                write!(f, "{}", if is_tuple { ")" } else { " }" })
+            }
+        }
+    }
+}
+
+/// A user-defined function or method, as registered from the program's items.
+#[derive(Debug, Clone)]
+struct Function {
+    receiver: Option<Receiver>,
+    args: Vec<FnArg>,
+    config: Vec<FnArg>,
+    body: Block,
+    // Index of the declaring module's source in `Interpreter::sources`
+    source: usize,
+}
+
+impl Function {
+    fn from_item(item: &Item, source: usize) -> Option<(String, Function)> {
+        match item {
+            Item::Fn {
+                name,
+                receiver,
+                args,
+                config,
+                body,
+                ..
+            } => Some((
+                name.to_string(),
+                Function {
+                    receiver: *receiver,
+                    args: args.clone(),
+                    config: config.clone(),
+                    body: body.clone(),
+                    source,
+                },
+            )),
+            _ => None,
+        }
+    }
+
+    /// The body with each config parameter bound to its default, for calls
+    /// that go through a function value and cannot pass config arguments.
+    fn body_with_defaults(&self) -> Block {
+        let mut stmts: Vec<Stmt> = self
+            .config
+            .iter()
+            .filter_map(|param| {
+                let value = param.default.clone()?;
+                Some(Stmt::Let {
+                    span: param.name.span.start..value.span.end,
+                    name: param.name.clone(),
+                    mutable: false,
+                    ty: None,
+                    value,
+                })
+            })
+            .collect();
+        stmts.extend(self.body.stmts.iter().cloned());
+        Block {
+            stmts,
+            span: self.body.span.clone(),
+        }
+    }
+
+    /// A trait's default method body, used when an impl doesn't override it.
+    fn from_default(method: &TraitMethod, source: usize) -> Option<(String, Function)> {
+        let body = method.default.clone()?;
+        Some((
+            method.name.to_string(),
+            Function {
+                receiver: method.receiver,
+                args: method.args.clone(),
+                config: method.config.clone(),
+                body,
+                source,
+            },
+        ))
+    }
+}
+
+/// A pending `break`/`continue` that unwinds blocks up to the innermost loop,
+/// or a thrown error that unwinds up to the nearest `catch` (Kap 7.1).
+#[derive(Debug)]
+enum Control {
+    Break(Value),
+    Continue,
+    // `from_call`: the error just left a call, whose `Propagate` adds a frame
+    Throw { error: Value, from_call: bool },
+}
+
+/// Lexically nested variable scopes of one function activation.
+#[derive(Debug, Default)]
+struct Env {
+    scopes: Vec<HashMap<String, Value>>,
+    control: Option<Control>,
+    // The running function's source, for trace locations
+    source: usize,
+}
+
+impl Env {
+    fn new() -> Self {
+        Self {
+            scopes: vec![HashMap::new()],
+            control: None,
+            source: 0,
+        }
+    }
+
+    fn push(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    fn pop(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn define(&mut self, name: &str, value: Value) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), value);
+        }
+    }
+
+    /// Takes a pending `break`/`continue` for the innermost loop. A thrown
+    /// error stays pending and ends the loop like a `break`.
+    fn take_loop_control(&mut self) -> Option<Control> {
+        match self.control.take() {
+            Some(thrown @ Control::Throw { .. }) => {
+                self.control = Some(thrown);
+                Some(Control::Break(Value::Unit))
+            }
+            control => control,
+        }
+    }
+
+    fn throw(&mut self, error: Value) -> Value {
+        self.control = Some(Control::Throw {
+            error,
+            from_call: false,
+        });
+        Value::Unit
+    }
+
+    /// Passes on an error that a called function threw.
+    fn throw_from_call(&mut self, error: Value) -> Value {
+        self.control = Some(Control::Throw {
+            error,
+            from_call: true,
+        });
+        Value::Unit
+    }
+
+    fn get(&self, name: &str) -> Option<&Value> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    /// Every visible variable, inner scopes last so they win when redefined.
+    fn snapshot(&self) -> Vec<(String, Value)> {
+        self.scopes
+            .iter()
+            .flat_map(|scope| scope.iter().map(|(k, v)| (k.clone(), v.clone())))
+            .collect()
+    }
+
+    fn get_mut(&mut self, name: &str) -> Option<&mut Value> {
+        self.scopes
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.get_mut(name))
+    }
+}
+
+pub struct Interpreter {
+    // Full path (`users::create`) -> function
+    functions: HashMap<String, Function>,
+    // Type name -> method name -> method
+    methods: HashMap<String, HashMap<String, Function>>,
+    enums: HashMap<String, Vec<EnumVariant>>,
+    // Locations for the traces of thrown errors: the entry file, then the
+    // files of imported modules (Kap 9.1)
+    sources: Vec<SourceMap>,
+    // How the code of each of these sources names functions (Kap 9.2)
+    scopes: Vec<ModuleScope>,
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl Interpreter {
     pub fn new() -> Self {
-        Self
+        Self {
+            functions: HashMap::new(),
+            methods: HashMap::new(),
+            enums: HashMap::new(),
+            sources: vec![SourceMap::default()],
+            scopes: vec![ModuleScope::new(&[], &[])],
+        }
+    }
+
+    /// The entry file the program was parsed from, for error traces. Imported
+    /// modules carry their own.
+    pub fn with_source(mut self, source: SourceMap) -> Self {
+        self.sources[0] = source;
+        self
     }
 
-    pub fn run(&self, program: &Program) {
+    pub fn run(&mut self, program: &Program) {
         println!("[Nikaia Kernel] Interpreter Init...");
+        self.register(program);
+
         // Entry point lookup: find 'main' function
-        for item in &program.items {
-            if let Item::Fn { name, body, .. } = item {
-                if name.to_string() == "main" {
-                    println!("[Nikaia Kernel] Executing 'main'...");
-                    self.eval_block(body);
-                    return;
-                }
+        if let Some(main) = self.functions.get("main").cloned() {
+            println!("[Nikaia Kernel] Executing 'main'...");
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                self.call_function(&main, None, Vec::new(), Vec::new())
+            }));
+            match result {
+                Ok(Ok(_)) => {}
+                Ok(Err(error)) => println!("[Nikaia Runtime] Uncaught error: {}", error),
+                Err(payload) => match payload.downcast::<Fault>() {
+                    Ok(fault) => println!("[Nikaia Runtime] Panic: {}", fault.0),
+                    Err(payload) => panic::resume_unwind(payload),
+                },
             }
+            return;
         }
         println!("[Nikaia Kernel] No main function found.");
     }
 
-    fn eval_block(&self, block: &Block) {
+    fn register(&mut self, program: &Program) {
+        let mut items = Vec::new();
+        self.scopes[0] = ModuleScope::new(&[], &program.items);
+        self.module_items(&program.items, 0, &mut Vec::new(), &mut items);
+        let traits: HashMap<String, (&[TraitMethod], usize)> = items
+            .iter()
+            .filter_map(|(item, source, _)| match item {
+                Item::Trait { name, methods, .. } => {
+                    Some((name.to_string(), (methods.as_slice(), *source)))
+                }
+                _ => None,
+            })
+            .collect();
+
+        for (item, source, module) in items {
+            match item {
+                Item::Fn { .. } => {
+                    if let Some((name, function)) = Function::from_item(item, source) {
+                        self.functions
+                            .insert(loader::item_path(&module, &name), function);
+                    }
+                }
+                Item::Enum { name, variants, .. } => {
+                    self.enums.insert(name.to_string(), variants.clone());
+                }
+                Item::Impl {
+                    trait_ref,
+                    target,
+                    methods,
+                    ..
+                } => {
+                    let table = self.methods.entry(target.name.to_string()).or_default();
+                    for method in methods {
+                        if let Some((name, function)) = Function::from_item(method, source) {
+                            table.insert(name, function);
+                        }
+                    }
+                    let (defaults, trait_source) = trait_ref
+                        .as_ref()
+                        .and_then(|t| traits.get(&t.name.to_string()))
+                        .copied()
+                        .unwrap_or_default();
+                    let defaults = defaults
+                        .iter()
+                        .filter_map(|method| Function::from_default(method, trait_source));
+                    for (name, function) in defaults {
+                        table.entry(name).or_insert(function);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Every item of `items` and their modules, with the index of the source
+    /// it was parsed from and the path of its module; registers the sources
+    /// of the modules on the way.
+    fn module_items<'p>(
+        &mut self,
+        items: &'p [Item],
+        source: usize,
+        module: &mut Vec<String>,
+        out: &mut Vec<(&'p Item, usize, Vec<String>)>,
+    ) {
+        for item in items {
+            match item {
+                Item::Module {
+                    name,
+                    items,
+                    source: module_source,
+                } => {
+                    module.push(name.to_string());
+                    self.sources.push(module_source.clone());
+                    self.scopes.push(ModuleScope::new(module, items));
+                    self.module_items(items, self.sources.len() - 1, module, out);
+                    module.pop();
+                }
+                item => out.push((item, source, module.clone())),
+            }
+        }
+    }
+
+    /// The function `path` names in the code of `source` (Kap 9.2).
+    fn function(&self, path: &[String], source: usize) -> Option<&Function> {
+        self.scopes[source]
+            .candidates(path)
+            .iter()
+            .find_map(|candidate| self.functions.get(candidate))
+    }
+
+    /// Runs a function body in a fresh scope. Returns the result and, for
+    /// methods, the receiver as it is after the call, or the error the body
+    /// threw. `config` holds one entry per config parameter, in the order the
+    /// checker put them; `None` (or a missing entry) evaluates the parameter's
+    /// default in the callee.
+    fn call_function(
+        &self,
+        function: &Function,
+        receiver: Option<Value>,
+        args: Vec<Value>,
+        config: Vec<Option<Value>>,
+    ) -> Result<(Value, Option<Value>), Value> {
+        let mut env = Env::new();
+        env.source = function.source;
+        if let Some(receiver) = receiver {
+            env.define("self", receiver);
+        }
+        for (param, value) in function.args.iter().zip(args) {
+            env.define(&param.name.to_string(), value);
+        }
+        let mut config = config.into_iter();
+        for param in &function.config {
+            let value = match (config.next().flatten(), &param.default) {
+                (Some(value), _) => value,
+                (None, Some(default)) => self.eval_expr(default, &mut env),
+                (None, None) => Value::Unit,
+            };
+            env.define(&param.name.to_string(), value);
+        }
+        let result = self.eval_block(&function.body, &mut env);
+        match env.control.take() {
+            Some(Control::Throw { error, .. }) => Err(error),
+            _ => Ok((result, env.get("self").cloned())),
+        }
+    }
+
+    fn call_closure(&self, closure: &Closure, args: Vec<Value>) -> Value {
+        let mut env = Env::new();
+        env.source = closure.source;
+        for (name, value) in &closure.captured {
+            env.define(name, value.clone());
+        }
+        env.push();
+        for (param, value) in closure.params.iter().zip(args) {
+            env.define(param, value);
+        }
+        self.eval_expr(&closure.body, &mut env)
+    }
+
+    fn eval_block(&self, block: &Block, env: &mut Env) -> Value {
+        env.push();
+        let mut last = Value::Unit;
         for stmt in &block.stmts {
-            self.eval_stmt(stmt);
+            last = self.eval_stmt(stmt, env);
+            if env.control.is_some() {
+                break;
+            }
         }
+        env.pop();
+        last
     }
 
-    fn eval_stmt(&self, stmt: &Stmt) {
+    fn eval_stmt(&self, stmt: &Stmt, env: &mut Env) -> Value {
         match stmt {
             Stmt::Let { name, value, .. } => {
-                // In a real implementation, we would store the result in a scope map.
-                // For now, we just print the binding.
-                println!("[Nikaia Runtime] Bind: {} = <evaluated>", name);
-                self.eval_expr(value);
+                let value = self.eval_expr(value, env);
+                env.define(&name.to_string(), value);
+                Value::Unit
             }
-            Stmt::Expr(expr) => {
-                self.eval_expr(expr);
+            Stmt::CompoundAssign {
+                target, op, value, ..
+            } => {
+                let current = self.eval_expr(target, env);
+                let value = self.eval_expr(value, env);
+                let updated = binary_op(*op, current, value);
+                self.assign(target, updated, env);
+                Value::Unit
+            }
+            Stmt::Expr(expr) => self.eval_expr(expr, env),
+            Stmt::Assign { target, value, .. } => {
+                let value = self.eval_expr(value, env);
+                self.assign(target, value, env);
+                Value::Unit
+            }
+        }
+    }
+
+    /// Stores `value` in a variable, field or index.
+    fn assign(&self, target: &Expr, value: Value, env: &mut Env) {
+        let ExprKind::Index { expr, index } = &target.kind else {
+            match place_mut(target, env) {
+                Some(place) => *place = value,
+                None => println!("[Nikaia Runtime] Cannot assign to {:?}", target),
             }
-            Stmt::Assign { .. } => {
-                println!("[Nikaia Runtime] Assignment (Skipped)");
+            return;
+        };
+        let key = self.eval_expr(index, env);
+        match place_mut(expr, env) {
+            Some(Value::Map(entries)) => match entries.iter_mut().find(|(k, _)| *k == key) {
+                Some(entry) => entry.1 = value,
+                None => entries.push((key, value)),
+            },
+            Some(Value::List(items)) => {
+                let slot = match key {
+                    Value::Int(i) => usize::try_from(i).ok().and_then(|i| items.get_mut(i)),
+                    _ => None,
+                };
+                match slot {
+                    Some(slot) => *slot = value,
+                    None => println!("[Nikaia Runtime] Index {} out of bounds", key),
+                }
             }
+            Some(other) => println!("[Nikaia Runtime] Cannot index '{}'", other.type_name()),
+            None => println!("[Nikaia Runtime] Cannot assign to {:?}", target),
         }
     }
 
-    fn eval_expr(&self, expr: &Expr) {
-        match expr {
-            Expr::Call { func, args } => {
+    fn eval_expr(&self, expr: &Expr, env: &mut Env) -> Value {
+        match &expr.kind {
+            ExprKind::LitInt(i) => Value::Int(*i),
+            ExprKind::LitStr(s) => Value::Str(s.clone()),
+            ExprKind::LitBool(b) => Value::Bool(*b),
+            ExprKind::LitNull => Value::Null,
+            ExprKind::Variable(name) => match env.get(&name.to_string()) {
+                Some(value) => value.clone(),
+                // A named function used as a value: numbers.map(double)
+                None => match self.function(&[name.to_string()], env.source) {
+                    Some(function) => Value::Function(Rc::new(Closure {
+                        params: function.args.iter().map(|a| a.name.to_string()).collect(),
+                        body: Expr::new(
+                            ExprKind::Block(function.body_with_defaults()),
+                            function.body.span.clone(),
+                        ),
+                        captured: Vec::new(),
+                        source: function.source,
+                    })),
+                    None => {
+                        println!("[Nikaia Runtime] Unknown variable '{}'", name);
+                        Value::Unit
+                    }
+                },
+            },
+            ExprKind::Lambda { params, body, .. } => Value::Function(Rc::new(Closure {
+                params: params.iter().map(|p| p.to_string()).collect(),
+                body: (**body).clone(),
+                captured: env.snapshot(),
+                source: env.source,
+            })),
+            ExprKind::Call {
+                func, args, config, ..
+            } => {
                 // Simplified function resolution
-                if let Expr::Variable(name) = &**func {
+                if let ExprKind::Variable(name) = &func.kind {
                     let name_str = name.to_string();
+                    let args: Vec<Value> = args.iter().map(|a| self.eval_expr(a, env)).collect();
                     if name_str == "println" {
-                        self.builtin_println(args);
-                        return;
+                        self.builtin_println(&args);
+                        return Value::Unit;
                     }
                     if name_str == "log" {
-                        self.builtin_log(args);
-                        return;
+                        self.builtin_log(&args);
+                        return Value::Unit;
+                    }
+                    // Variables holding a function shadow functions of the same name
+                    if let Some(Value::Function(closure)) = env.get(&name_str) {
+                        let closure = Rc::clone(closure);
+                        return self.call_closure(&closure, args);
+                    }
+                    if let Some(function) = self.function(&[name_str], env.source) {
+                        return self.call_named(function, args, config, env);
+                    }
+                } else if !matches!(func.kind, ExprKind::Path(_)) {
+                    // Anything else that evaluates to a function
+                    if let Value::Function(closure) = self.eval_expr(func, env) {
+                        let args: Vec<Value> =
+                            args.iter().map(|a| self.eval_expr(a, env)).collect();
+                        return self.call_closure(&closure, args);
+                    }
+                }
+                // Tuple variant constructor: Message::Write("hi")
+                if let ExprKind::Path(path) = &func.kind {
+                    let args: Vec<Value> = args.iter().map(|a| self.eval_expr(a, env)).collect();
+                    match join_path(path).as_str() {
+                        "HashMap::new" => return Value::Map(Vec::new()),
+                        "Vec::new" => return Value::List(Vec::new()),
+                        _ => {}
+                    }
+                    // An associated function: User::new("Alice"), the
+                    // constructor User("Alice") resolves to (Kap 4.2)
+                    if let [.., target, name] = path.as_slice() {
+                        let associated = self
+                            .methods
+                            .get(&target.to_string())
+                            .and_then(|methods| methods.get(&name.to_string()));
+                        if let Some(function) = associated {
+                            return self.call_named(function, args, config, env);
+                        }
+                    }
+                    // A function of another module: users::create("Alice")
+                    let segments: Vec<String> = path.iter().map(|p| p.to_string()).collect();
+                    if let Some(function) = self.function(&segments, env.source) {
+                        return self.call_named(function, args, config, env);
+                    }
+                    if let Some(value) = self.enum_value(path, args) {
+                        return value;
                     }
                 }
                 println!("[Nikaia Runtime] Call to unknown function");
+                Value::Unit
+            }
+            ExprKind::Path(path) => match self.enum_value(path, Vec::new()) {
+                Some(value) => value,
+                None => {
+                    println!("[Nikaia Runtime] Unknown path '{}'", join_path(path));
+                    Value::Unit
+                }
+            },
+            ExprKind::Match { expr, arms } => {
+                let value = self.eval_expr(expr, env);
+                for arm in arms {
+                    let mut bindings = Vec::new();
+                    if !match_pattern(&arm.pattern, &value, &mut bindings) {
+                        continue;
+                    }
+                    env.push();
+                    for (name, bound) in bindings {
+                        env.define(&name, bound);
+                    }
+                    let guard_passed = match &arm.guard {
+                        Some(guard) => self.eval_expr(guard, env) == Value::Bool(true),
+                        None => true,
+                    };
+                    let result = guard_passed.then(|| self.eval_expr(&arm.body, env));
+                    env.pop();
+                    if let Some(result) = result {
+                        return result;
+                    }
+                }
+                println!("[Nikaia Runtime] No match arm for '{}'", value);
+                Value::Unit
+            }
+            ExprKind::MethodCall {
+                receiver,
+                method,
+                args,
+                config,
+                ..
+            } => self.eval_method_call(receiver, &method.to_string(), args, config, env),
+            ExprKind::Index { expr, index } => {
+                let base = self.eval_expr(expr, env);
+                let key = self.eval_expr(index, env);
+                let found = match (&base, &key) {
+                    (Value::List(items), Value::Int(i)) => {
+                        usize::try_from(*i).ok().and_then(|i| items.get(i))
+                    }
+                    (Value::Map(entries), _) => {
+                        entries.iter().find(|(k, _)| *k == key).map(|(_, v)| v)
+                    }
+                    _ => None,
+                };
+                match found {
+                    Some(value) => value.clone(),
+                    None => {
+                        println!(
+                            "[Nikaia Runtime] No entry {} in '{}'",
+                            key,
+                            base.type_name()
+                        );
+                        Value::Unit
+                    }
+                }
+            }
+            ExprKind::StructLit {
+                path,
+                fields,
+                variant,
+            } => {
+                let fields = fields
+                    .iter()
+                    .map(|field| (field.name.to_string(), self.eval_expr(&field.value, env)))
+                    .collect();
+                match (variant, path.as_slice()) {
+                    // Message::Move(x: 1, y: 2)
+                    (true, [.., name, variant]) => Value::Enum {
+                        name: name.to_string(),
+                        variant: variant.to_string(),
+                        fields,
+                    },
+                    _ => Value::Struct {
+                        name: path[path.len() - 1].to_string(),
+                        fields,
+                    },
+                }
             }
-            Expr::Spawn { body, .. } => {
+            ExprKind::Field { expr, field } => read_field(&self.eval_expr(expr, env), field),
+            // Kap 3.5: null?.field and null?.method() are null
+            ExprKind::SafeField { expr, field, .. } => match self.eval_expr(expr, env) {
+                Value::Null => Value::Null,
+                base => read_field(&base, field),
+            },
+            ExprKind::SafeMethodCall {
+                receiver,
+                method,
+                args,
+                config,
+                ..
+            } => match self.eval_expr(receiver, env) {
+                Value::Null => Value::Null,
+                target => {
+                    self.call_method(target, receiver, &method.to_string(), args, config, env)
+                }
+            },
+            ExprKind::Spawn { body, .. } => {
                 println!("[Nikaia Runtime] Spawning Task (Async -> Sync Simulation)...");
                 // In Stage 1, this will use Tokio. For now, we execute inline.
-                // The body is usually an Expr::Block because of `spawn({ ... })` syntax.
-                if let Expr::Block(block) = &**body {
-                    self.eval_block(block);
+                // The body is usually an ExprKind::Block because of `spawn({ ... })` syntax.
+                if let ExprKind::Block(block) = &body.kind {
+                    self.eval_block(block, env);
                 } else {
                     // Fallback for single expression spawn(expr)
-                    self.eval_expr(body);
+                    self.eval_expr(body, env);
+                }
+                Value::Unit
+            }
+            ExprKind::Block(b) => self.eval_block(b, env),
+            ExprKind::If {
+                cond,
+                then_branch,
+                else_branch,
+            } => match self.eval_expr(cond, env) {
+                Value::Bool(true) => self.eval_block(then_branch, env),
+                Value::Bool(false) => match else_branch {
+                    Some(block) => self.eval_block(block, env),
+                    None => Value::Unit,
+                },
+                other => {
+                    println!(
+                        "[Nikaia Runtime] Condition must be 'bool', found '{}'",
+                        other.type_name()
+                    );
+                    Value::Unit
+                }
+            },
+            ExprKind::Binary {
+                op: BinOp::And,
+                left,
+                right,
+            } => match self.eval_expr(left, env) {
+                Value::Bool(false) => Value::Bool(false),
+                left => binary_op(BinOp::And, left, self.eval_expr(right, env)),
+            },
+            ExprKind::Binary {
+                op: BinOp::Or,
+                left,
+                right,
+            } => match self.eval_expr(left, env) {
+                Value::Bool(true) => Value::Bool(true),
+                left => binary_op(BinOp::Or, left, self.eval_expr(right, env)),
+            },
+            ExprKind::Binary {
+                op: BinOp::Coalesce,
+                left,
+                right,
+            } => match self.eval_expr(left, env) {
+                Value::Null => self.eval_expr(right, env),
+                left => left,
+            },
+            ExprKind::Binary { op, left, right } => {
+                let left = self.eval_expr(left, env);
+                let right = self.eval_expr(right, env);
+                binary_op(*op, left, right)
+            }
+            ExprKind::Unary { op, expr } => match (op, self.eval_expr(expr, env)) {
+                (UnOp::Neg, Value::Int(i)) => checked(i.checked_neg()),
+                (UnOp::Not, Value::Bool(b)) => Value::Bool(!b),
+                (op, value) => {
+                    println!(
+                        "[Nikaia Runtime] Unsupported operand for {:?}: '{}'",
+                        op,
+                        value.type_name()
+                    );
+                    Value::Unit
+                }
+            },
+            ExprKind::While { cond, body } => {
+                loop {
+                    match self.eval_expr(cond, env) {
+                        Value::Bool(true) => {}
+                        Value::Bool(false) => break,
+                        other => {
+                            println!(
+                                "[Nikaia Runtime] Condition must be 'bool', found '{}'",
+                                other.type_name()
+                            );
+                            break;
+                        }
+                    }
+                    self.eval_block(body, env);
+                    if let Some(Control::Break(_)) = env.take_loop_control() {
+                        break;
+                    }
+                }
+                Value::Unit
+            }
+            ExprKind::Loop { body } => loop {
+                self.eval_block(body, env);
+                if let Some(Control::Break(value)) = env.take_loop_control() {
+                    break value;
+                }
+            },
+            ExprKind::For { var, iter, body } => {
+                let items = match self.eval_expr(iter, env) {
+                    Value::List(items) => items,
+                    Value::Range(start, end) => (start..end).map(Value::Int).collect(),
+                    other => {
+                        println!(
+                            "[Nikaia Runtime] Cannot iterate over '{}'",
+                            other.type_name()
+                        );
+                        Vec::new()
+                    }
+                };
+                for item in items {
+                    env.push();
+                    env.define(&var.to_string(), item);
+                    self.eval_block(body, env);
+                    env.pop();
+                    if let Some(Control::Break(_)) = env.take_loop_control() {
+                        break;
+                    }
+                }
+                Value::Unit
+            }
+            ExprKind::Range {
+                start,
+                end,
+                inclusive,
+            } => match (self.eval_expr(start, env), self.eval_expr(end, env)) {
+                (Value::Int(start), Value::Int(end)) => {
+                    Value::Range(start, if *inclusive { end + 1 } else { end })
+                }
+                (start, end) => {
+                    println!(
+                        "[Nikaia Runtime] Range bounds must be integers, found '{}' and '{}'",
+                        start.type_name(),
+                        end.type_name()
+                    );
+                    Value::Unit
+                }
+            },
+            ExprKind::List(items) => {
+                Value::List(items.iter().map(|i| self.eval_expr(i, env)).collect())
+            }
+            ExprKind::Break(value) => {
+                let value = match value {
+                    Some(value) => self.eval_expr(value, env),
+                    None => Value::Unit,
+                };
+                env.control = Some(Control::Break(value));
+                Value::Unit
+            }
+            ExprKind::Continue => {
+                env.control = Some(Control::Continue);
+                Value::Unit
+            }
+            ExprKind::Throw { value, span } => {
+                let error = match self.eval_expr(value, env) {
+                    Value::Error(thrown) => thrown.error,
+                    error => error,
+                };
+                if env.control.is_some() {
+                    return Value::Unit;
                 }
+                let source = &self.sources[env.source];
+                let trace = vec![format!("thrown at {}", source.location(span.start))];
+                env.throw(Value::Error(Box::new(Thrown { error, trace })))
             }
-            Expr::LitStr(_) => {
-                // Literals evaluate to themselves.
+            // A thrown error propagates through `env.control` by itself; leaving
+            // the call adds a frame to its trace
+            ExprKind::Propagate(call) => {
+                let value = self.eval_expr(call, env);
+                if let Some(Control::Throw {
+                    error: Value::Error(thrown),
+                    from_call,
+                }) = &mut env.control
+                {
+                    if std::mem::take(from_call) {
+                        let (callee, offset) = call_site(call);
+                        thrown.trace.push(format!(
+                            "via {} at {}",
+                            callee,
+                            self.sources[env.source].location(offset)
+                        ));
+                    }
+                }
+                value
+            }
+            // Values carry no static type; `T` and `T?` look alike at runtime
+            ExprKind::Nullable(value) => self.eval_expr(value, env),
+            ExprKind::TryCatch { expr, handler } => {
+                let value = self.eval_expr(expr, env);
+                match env.control.take() {
+                    Some(Control::Throw { error, .. }) => {
+                        env.push();
+                        env.define("error", error);
+                        let value = self.eval_block(handler, env);
+                        env.pop();
+                        value
+                    }
+                    control => {
+                        env.control = control;
+                        value
+                    }
+                }
             }
-            Expr::Block(b) => self.eval_block(b),
-            Expr::Dsl { target, .. } => {
+            ExprKind::Dsl { target, .. } => {
                 println!("[Nikaia Runtime] DSL Block '{}' (Skipped)", target);
+                Value::Unit
+            }
+            _ => {
+                println!("[Nikaia Runtime] Eval: {:?}", expr);
+                Value::Unit
             }
-            _ => println!("[Nikaia Runtime] Eval: {:?}", expr),
         }
     }
 
-    fn builtin_println(&self, args: &[Expr]) {
-        for arg in args {
-            if let Expr::LitStr(s) = arg {
-                println!("{}", s);
-            } else {
-                println!("<expression>");
+    /// Calls a user-defined function by name with evaluated arguments.
+    fn call_named(
+        &self,
+        function: &Function,
+        args: Vec<Value>,
+        config: &[ConfigArg],
+        env: &mut Env,
+    ) -> Value {
+        let config = self.eval_config(config, env);
+        // An argument threw, so the call doesn't happen
+        if env.control.is_some() {
+            return Value::Unit;
+        }
+        match self.call_function(function, None, args, config) {
+            Ok((result, _)) => result,
+            Err(error) => env.throw_from_call(error),
+        }
+    }
+
+    fn eval_method_call(
+        &self,
+        receiver: &Expr,
+        method: &str,
+        args: &[Expr],
+        config: &[ConfigArg],
+        env: &mut Env,
+    ) -> Value {
+        let target = self.eval_expr(receiver, env);
+        self.call_method(target, receiver, method, args, config, env)
+    }
+
+    /// Calls `method` on `target`, the already evaluated `receiver`.
+    fn call_method(
+        &self,
+        target: Value,
+        receiver: &Expr,
+        method: &str,
+        args: &[Expr],
+        config: &[ConfigArg],
+        env: &mut Env,
+    ) -> Value {
+        let Some(function) = self
+            .methods
+            .get(target.type_name())
+            .and_then(|table| table.get(method))
+        else {
+            println!(
+                "[Nikaia Runtime] No method '{}' on '{}'",
+                method,
+                target.type_name()
+            );
+            return Value::Unit;
+        };
+
+        let args: Vec<Value> = args.iter().map(|a| self.eval_expr(a, env)).collect();
+        let config = self.eval_config(config, env);
+        if env.control.is_some() {
+            return Value::Unit;
+        }
+        // Methods called on a caught error see the error itself
+        let target = match target {
+            Value::Error(thrown) => thrown.error,
+            target => target,
+        };
+        let (result, receiver_after) =
+            match self.call_function(function, Some(target), args, config) {
+                Ok(returned) => returned,
+                Err(error) => return env.throw_from_call(error),
+            };
+
+        // `&mut self` methods write their receiver back to where it came from.
+        if function.receiver == Some(Receiver::RefMut) {
+            if let (Some(updated), Some(place)) = (receiver_after, place_mut(receiver, env)) {
+                *place = updated;
             }
         }
+        result
+    }
+
+    fn eval_config(&self, config: &[ConfigArg], env: &mut Env) -> Vec<Option<Value>> {
+        config
+            .iter()
+            .map(|arg| arg.value.as_ref().map(|value| self.eval_expr(value, env)))
+            .collect()
+    }
+
+    /// Builds `Enum::Variant` or `Enum::Variant(args)` for a declared enum.
+    fn enum_value(&self, path: &[Ident], args: Vec<Value>) -> Option<Value> {
+        let [name, variant] = path else {
+            return None;
+        };
+        let declared = self
+            .enums
+            .get(&name.to_string())?
+            .iter()
+            .find(|v| v.name == *variant)?;
+        let arity = match &declared.data {
+            VariantData::Unit => 0,
+            VariantData::Tuple(types) => types.len(),
+            VariantData::Struct(_) => return None,
+        };
+        if arity != args.len() {
+            return None;
+        }
+        Some(Value::Enum {
+            name: name.to_string(),
+            variant: variant.to_string(),
+            fields: args
+                .into_iter()
+                .enumerate()
+                .map(|(i, value)| (i.to_string(), value))
+                .collect(),
+        })
+    }
+
+    fn builtin_println(&self, args: &[Value]) {
+        for arg in args {
+            println!("{}", arg);
+        }
     }
 
-    fn builtin_log(&self, args: &[Expr]) {
+    fn builtin_log(&self, args: &[Value]) {
         for arg in args {
-            if let Expr::LitStr(s) = arg {
-                println!("[LOG] {}", s);
+            println!("[LOG] {}", arg);
+        }
+    }
+}
+
+fn join_path(path: &[Ident]) -> String {
+    path.iter()
+        .map(|segment| segment.to_string())
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+/// Tests `value` against `pattern`, collecting the bindings it introduces.
+fn match_pattern(pattern: &Pattern, value: &Value, bindings: &mut Vec<(String, Value)>) -> bool {
+    match (pattern, value) {
+        (Pattern::Wildcard, _) => true,
+        (Pattern::Binding(name), _) => {
+            bindings.push((name.to_string(), value.clone()));
+            true
+        }
+        (Pattern::LitInt(expected), Value::Int(i)) => expected == i,
+        (Pattern::LitStr(expected), Value::Str(s)) => expected == s,
+        (Pattern::LitBool(expected), Value::Bool(b)) => expected == b,
+        (
+            Pattern::Range {
+                start,
+                end,
+                inclusive,
+            },
+            Value::Int(i),
+        ) => *i >= *start && (if *inclusive { *i <= *end } else { *i < *end }),
+        (
+            Pattern::Variant { path, fields },
+            Value::Enum {
+                name,
+                variant,
+                fields: values,
+            },
+        ) => {
+            let same_variant = match path.as_slice() {
+                [v] => v == variant,
+                [e, v] => e == name && v == variant,
+                _ => false,
+            };
+            same_variant
+                && match fields {
+                    PatternFields::Unit => true,
+                    PatternFields::Tuple(items) => {
+                        items.len() == values.len()
+                            && items
+                                .iter()
+                                .zip(values)
+                                .all(|(p, (_, v))| match_pattern(p, v, bindings))
+                    }
+                    PatternFields::Struct(field_patterns) => field_patterns.iter().all(|fp| {
+                        values
+                            .iter()
+                            .find(|(n, _)| fp.name == n)
+                            .is_some_and(|(_, v)| match_pattern(&fp.pattern, v, bindings))
+                    }),
+                }
+        }
+        (Pattern::Or(alternatives), _) => alternatives.iter().any(|p| {
+            let mark = bindings.len();
+            let matched = match_pattern(p, value, bindings);
+            if !matched {
+                bindings.truncate(mark);
             }
+            matched
+        }),
+        _ => false,
+    }
+}
+
+/// The callee of a call and where it is called, for the frames of error traces.
+fn call_site(call: &Expr) -> (String, usize) {
+    match &call.kind {
+        ExprKind::Call { func, .. } => {
+            let callee = match &func.kind {
+                ExprKind::Variable(name) => name.to_string(),
+                ExprKind::Path(path) => join_path(path),
+                _ => "fn".to_string(),
+            };
+            (callee, func.span.start)
         }
+        ExprKind::MethodCall { method, .. } | ExprKind::SafeMethodCall { method, .. } => {
+            (method.to_string(), method.span.start)
+        }
+        _ => (String::new(), 0),
+    }
+}
+
+fn read_field(base: &Value, field: &Ident) -> Value {
+    match base.field(&field.to_string()) {
+        Some(value) => value.clone(),
+        None => {
+            println!(
+                "[Nikaia Runtime] '{}' has no field '{}'",
+                base.type_name(),
+                field
+            );
+            Value::Unit
+        }
+    }
+}
+
+/// Applies a binary operator to two evaluated operands. `&&`/`||` only get
+/// here once the left side did not decide the result.
+fn binary_op(op: BinOp, left: Value, right: Value) -> Value {
+    let result = match (op, &left, &right) {
+        (BinOp::Concat, _, _) | (BinOp::Add, Value::Str(_), _) | (BinOp::Add, _, Value::Str(_)) => {
+            Some(Value::Str(format!("{}{}", left, right)))
+        }
+        (BinOp::Div | BinOp::Rem, Value::Int(_), Value::Int(0)) => fault("division by zero"),
+        (BinOp::Add, Value::Int(a), Value::Int(b)) => Some(checked(a.checked_add(*b))),
+        (BinOp::Sub, Value::Int(a), Value::Int(b)) => Some(checked(a.checked_sub(*b))),
+        (BinOp::Mul, Value::Int(a), Value::Int(b)) => Some(checked(a.checked_mul(*b))),
+        (BinOp::Div, Value::Int(a), Value::Int(b)) => Some(checked(a.checked_div(*b))),
+        (BinOp::Rem, Value::Int(a), Value::Int(b)) => Some(checked(a.checked_rem(*b))),
+        (BinOp::And | BinOp::Or, Value::Bool(_), Value::Bool(b)) => Some(Value::Bool(*b)),
+        (BinOp::Eq, _, _) => Some(Value::Bool(left == right)),
+        (BinOp::Ne, _, _) => Some(Value::Bool(left != right)),
+        (BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge, _, _) => {
+            let ordering = match (&left, &right) {
+                (Value::Int(a), Value::Int(b)) => Some(a.cmp(b)),
+                (Value::Str(a), Value::Str(b)) => Some(a.cmp(b)),
+                _ => None,
+            };
+            ordering.map(|ordering| {
+                Value::Bool(match op {
+                    BinOp::Lt => ordering.is_lt(),
+                    BinOp::Le => ordering.is_le(),
+                    BinOp::Gt => ordering.is_gt(),
+                    _ => ordering.is_ge(),
+                })
+            })
+        }
+        _ => None,
+    };
+    result.unwrap_or_else(|| {
+        println!(
+            "[Nikaia Runtime] Cannot apply {:?} to '{}' and '{}'",
+            op,
+            left.type_name(),
+            right.type_name()
+        );
+        Value::Unit
+    })
+}
+
+/// The result of checked integer arithmetic; `None` is an overflow.
+fn checked(result: Option<i64>) -> Value {
+    Value::Int(result.unwrap_or_else(|| fault("overflow")))
+}
+
+/// Resolves an assignable expression (`x`, `x.field`) to its storage.
+fn place_mut<'e>(expr: &Expr, env: &'e mut Env) -> Option<&'e mut Value> {
+    match &expr.kind {
+        ExprKind::Variable(name) => env.get_mut(&name.to_string()),
+        ExprKind::Field { expr, field } => place_mut(expr, env)?.field_mut(&field.to_string()),
+        _ => None,
     }
 }
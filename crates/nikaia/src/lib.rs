@@ -0,0 +1,11 @@
+// crates/nikaia/src/lib.rs
+// Der Nikaia-Treiber als Bibliothek: Parser, Checker, Loader und Interpreter.
+//
+// The binary in main.rs is a thin command line around these modules; the
+// integration tests in tests/ use them directly.
+
+pub mod ast;
+pub mod checker;
+pub mod interpreter;
+pub mod loader;
+pub mod parser;
@@ -0,0 +1,380 @@
+// crates/nikaia/src/loader/mod.rs
+// Kap 9.1: every `.nika` file is a module. Starting from the entry file, the
+// loader resolves `use` items against the directory the entry file lives in
+// (the project's `src/`), parses each imported file once and attaches it to
+// the program as a nested `Item::Module`. The imports form the module graph,
+// which must not have cycles.
+use crate::ast::{Ident, Item, Program, SourceMap};
+use crate::parser::{parse_to_ast, Report};
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+/// The module a program's entry file is, as named in errors.
+pub const ROOT_MODULE: &str = "main";
+
+// The modules of Rust's standard library; `use std::...` that is not a project
+// file has to name one of them
+const STD_MODULES: &[&str] = &[
+    "alloc",
+    "any",
+    "array",
+    "borrow",
+    "boxed",
+    "cell",
+    "char",
+    "cmp",
+    "collections",
+    "convert",
+    "default",
+    "env",
+    "error",
+    "f32",
+    "f64",
+    "ffi",
+    "fmt",
+    "fs",
+    "future",
+    "hash",
+    "hint",
+    "i8",
+    "i16",
+    "i32",
+    "i64",
+    "i128",
+    "io",
+    "isize",
+    "iter",
+    "marker",
+    "mem",
+    "net",
+    "num",
+    "ops",
+    "option",
+    "os",
+    "panic",
+    "path",
+    "pin",
+    "prelude",
+    "primitive",
+    "process",
+    "ptr",
+    "rc",
+    "result",
+    "slice",
+    "str",
+    "string",
+    "sync",
+    "task",
+    "thread",
+    "time",
+    "u8",
+    "u16",
+    "u32",
+    "u64",
+    "u128",
+    "usize",
+    "vec",
+];
+
+/// Parses `source`, the entry file at `entry`, and every module it imports,
+/// directly or through other modules.
+pub fn load_program(entry: &Path, source: &str) -> Result<(Program, SourceMap)> {
+    let source_map = SourceMap::new(&entry.display().to_string(), source);
+    let mut program = parse_file(source, &source_map)?;
+
+    let mut loader = Loader {
+        root: entry.parent().unwrap_or(Path::new("")).to_path_buf(),
+        entry: entry.to_path_buf(),
+        loaded: Vec::new(),
+        loading: vec![Vec::new()],
+        errors: Vec::new(),
+        report: Report::default(),
+    };
+    loader.load_imports(&program.items, &source_map);
+    // A module that does not parse is not loaded, so nothing else is
+    // reported about it
+    if !loader.report.messages.is_empty() {
+        return Err(loader.report.into());
+    }
+    if !loader.errors.is_empty() {
+        return Err(anyhow::anyhow!(loader.errors.join("\n")));
+    }
+
+    for module in loader.loaded {
+        attach(
+            &mut program.items,
+            &module.path,
+            module.program.items,
+            module.source,
+        );
+    }
+    Ok((program, source_map))
+}
+
+struct Module {
+    path: Vec<String>,
+    program: Program,
+    source: SourceMap,
+}
+
+struct Loader {
+    root: PathBuf,
+    entry: PathBuf,
+    loaded: Vec<Module>,
+    // The chain of modules whose imports are being loaded, the entry file
+    // (module path `[]`) first; an import of one of them closes a cycle
+    loading: Vec<Vec<String>>,
+    errors: Vec<String>,
+    // Syntax errors in imported modules
+    report: Report,
+}
+
+impl Loader {
+    fn load_imports(&mut self, items: &[Item], source: &SourceMap) {
+        for item in items {
+            let Item::Import { path, .. } = item else {
+                continue;
+            };
+            let segments: Vec<String> = path.split("::").map(str::to_string).collect();
+            let Some((module, rest)) = self.resolve(&segments) else {
+                // Not a project file: `use std::collections::HashMap` is Rust's
+                match segments.as_slice() {
+                    [std, module, ..] if std == "std" && STD_MODULES.contains(&module.as_str()) => {
+                    }
+                    [std, module, ..] if std == "std" => self.errors.push(format!(
+                        "Unresolved import `{}` in {}: no module file {} and no module `std::{}` in Rust",
+                        path,
+                        source.file,
+                        self.candidates(&segments).join(" or "),
+                        module
+                    )),
+                    _ => self.errors.push(format!(
+                        "Unresolved import `{}` in {}: no module file {}",
+                        path,
+                        source.file,
+                        self.candidates(&segments).join(" or ")
+                    )),
+                }
+                continue;
+            };
+            if !self.load(&module) {
+                continue;
+            }
+            let Some(name) = rest.first() else {
+                continue;
+            };
+            if rest.len() > 1 || !self.declares(&module, name) {
+                self.errors.push(format!(
+                    "Unresolved import `{}` in {}: module `{}` has no item `{}`",
+                    path,
+                    source.file,
+                    module_name(&module),
+                    rest.join("::")
+                ));
+            }
+        }
+    }
+
+    /// The module an import names and the item path inside it. The longest
+    /// prefix of the import that is a file wins: `use std::http` is
+    /// `src/std/http.nika` and `use users::User` is `User` in `src/users.nika`.
+    fn resolve<'a>(&self, segments: &'a [String]) -> Option<(Vec<String>, &'a [String])> {
+        (1..=segments.len()).rev().find_map(|len| {
+            let file = self.file(&segments[..len]);
+            if file == self.entry {
+                return Some((Vec::new(), &segments[len..]));
+            }
+            file.is_file()
+                .then(|| (segments[..len].to_vec(), &segments[len..]))
+        })
+    }
+
+    fn file(&self, module: &[String]) -> PathBuf {
+        let mut file = self.root.join(module.join("/"));
+        file.set_extension("nika");
+        file
+    }
+
+    /// The files an import could have named, for the unresolved import error.
+    fn candidates(&self, segments: &[String]) -> Vec<String> {
+        (1..=segments.len())
+            .rev()
+            .map(|len| self.file(&segments[..len]).display().to_string())
+            .collect()
+    }
+
+    /// Loads `module` and its imports unless it is loaded already. Returns
+    /// false if it could not be loaded.
+    fn load(&mut self, module: &[String]) -> bool {
+        if let Some(start) = self.loading.iter().position(|m| m == module) {
+            let mut cycle: Vec<String> = self.loading[start..]
+                .iter()
+                .map(|m| module_name(m))
+                .collect();
+            cycle.push(module_name(module));
+            self.errors
+                .push(format!("Import cycle: {}", cycle.join(" -> ")));
+            return false;
+        }
+        if self.loaded.iter().any(|m| m.path == module) {
+            return true;
+        }
+
+        let file = self.file(module);
+        let source = match std::fs::read_to_string(&file) {
+            Ok(source) => source,
+            Err(e) => {
+                self.errors
+                    .push(format!("Cannot read {}: {}", file.display(), e));
+                return false;
+            }
+        };
+        let source_map = SourceMap::new(&file.display().to_string(), &source);
+        let program = match parse_file(&source, &source_map) {
+            Ok(program) => program,
+            Err(report) => {
+                self.report.messages.extend(report.messages);
+                return false;
+            }
+        };
+
+        self.loading.push(module.to_vec());
+        self.load_imports(&program.items, &source_map);
+        self.loading.pop();
+        self.loaded.push(Module {
+            path: module.to_vec(),
+            program,
+            source: source_map,
+        });
+        true
+    }
+
+    /// Whether `module` declares an item called `name`, `pub` or not; privacy
+    /// is the checker's business.
+    fn declares(&self, module: &[String], name: &str) -> bool {
+        let items = match self.loaded.iter().find(|m| m.path == module) {
+            Some(loaded) => &loaded.program.items,
+            // The entry file, which is never imported without a cycle
+            None => return true,
+        };
+        items.iter().any(|item| match item {
+            Item::Fn { name: n, .. }
+            | Item::Struct { name: n, .. }
+            | Item::Enum { name: n, .. }
+            | Item::Trait { name: n, .. } => n == name,
+            _ => false,
+        })
+    }
+}
+
+fn parse_file(source: &str, source_map: &SourceMap) -> Result<Program, Report> {
+    parse_to_ast(source).map_err(|errors| errors.report(source_map, source))
+}
+
+/// `users`, `std::http`, or `main` for the entry file.
+pub fn module_name(module: &[String]) -> String {
+    match module {
+        [] => ROOT_MODULE.to_string(),
+        _ => module.join("::"),
+    }
+}
+
+/// The full path of the item `name` in `module`: `users::create`, or just
+/// `create` in the entry file.
+pub fn item_path(module: &[String], name: &str) -> String {
+    match module {
+        [] => name.to_string(),
+        _ => format!("{}::{}", module.join("::"), name),
+    }
+}
+
+/// Kap 9.2: how the code of one module names items. `create()` is an item
+/// of the module itself or one it imports (`use users::create`),
+/// `http::get()` goes through an imported module (`use std::http`), and
+/// `users::create()` is a path from the crate root.
+pub struct ModuleScope {
+    module: Vec<String>,
+    imports: Vec<Vec<String>>,
+}
+
+impl ModuleScope {
+    /// The scope of `module`, whose items are `items`.
+    pub fn new(module: &[String], items: &[Item]) -> Self {
+        let imports = items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Import { path, .. } => Some(path.split("::").map(str::to_string).collect()),
+                _ => None,
+            })
+            .collect();
+        ModuleScope {
+            module: module.to_vec(),
+            imports,
+        }
+    }
+
+    /// The module's name, as `module_name` spells it.
+    pub fn name(&self) -> String {
+        module_name(&self.module)
+    }
+
+    /// The full path of the item `name` declared in this module.
+    pub fn item_path(&self, name: &str) -> String {
+        item_path(&self.module, name)
+    }
+
+    /// The full paths `path` may name, most local first; the caller takes the
+    /// first one it knows.
+    pub fn candidates(&self, path: &[String]) -> Vec<String> {
+        let Some((first, rest)) = path.split_first() else {
+            return Vec::new();
+        };
+        let mut candidates = vec![item_path(&self.module, &path.join("::"))];
+        for import in &self.imports {
+            if import.last() == Some(first) {
+                let mut full = import.clone();
+                full.extend(rest.iter().cloned());
+                candidates.push(full.join("::"));
+            }
+        }
+        let root = path.join("::");
+        if !candidates.contains(&root) {
+            candidates.push(root);
+        }
+        candidates
+    }
+}
+
+/// Puts a loaded file's items into the module at `path`, creating the
+/// modules on the way for directories without a file of their own.
+fn attach(items: &mut Vec<Item>, path: &[String], module_items: Vec<Item>, source: SourceMap) {
+    let Some((name, rest)) = path.split_first() else {
+        return;
+    };
+    let position = items
+        .iter()
+        .position(|item| matches!(item, Item::Module { name: n, .. } if n == name));
+    let index = position.unwrap_or_else(|| {
+        // A module is named by its file, so its name has no place in the source
+        items.push(Item::Module {
+            name: Ident::new(name, 0..0),
+            items: Vec::new(),
+            source: SourceMap::default(),
+        });
+        items.len() - 1
+    });
+    let Item::Module {
+        items: children,
+        source: module_source,
+        ..
+    } = &mut items[index]
+    else {
+        return;
+    };
+    if rest.is_empty() {
+        children.extend(module_items);
+        *module_source = source;
+    } else {
+        attach(children, rest, module_items, source);
+    }
+}
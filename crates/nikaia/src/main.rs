@@ -1,15 +1,16 @@
 // crates/nikaia/src/main.rs
+#![feature(rustc_private)]
+
+// The executor links the compiler; the binary has to say so as well
+extern crate rustc_driver;
+
 use anyhow::Result;
 use bridge_ir::BridgeModule;
-use bridge_orchestrator::{LanguageFrontend, Orchestrator};
-use clap::Parser;
+use bridge_orchestrator::LanguageFrontend;
+use clap::{Parser, ValueEnum};
+use nikaia_driver::{checker, interpreter, loader, parser};
 use std::path::PathBuf;
 
-// Modules
-mod ast;
-mod interpreter;
-mod parser;
-
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 pub struct Cli {
@@ -18,33 +19,82 @@ pub struct Cli {
 
     #[arg(long, default_value = "bridge")]
     pub backend: String, // "interpreter", "bridge", "cranelift", "llvm"
+
+    /// Print the AST of the program and its modules instead of running it
+    #[arg(long, value_enum)]
+    pub dump_ast: Option<AstFormat>,
+
+    /// How syntax errors are printed: for people, or one JSON object per line
+    #[arg(long, value_enum, default_value = "human")]
+    pub message_format: MessageFormat,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum AstFormat {
+    Json,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum MessageFormat {
+    Human,
+    Json,
 }
 
 struct NikaiaFrontend {
-    backend: String,
+    entry: PathBuf, // Kap 9.1: imported modules are found next to it
 }
 
 impl LanguageFrontend for NikaiaFrontend {
     fn parse(&self, source: &str) -> Result<BridgeModule> {
-        parser::parse_to_bridge(source)
+        parser::parse_to_bridge(source, &self.entry)
     }
 }
 
 pub fn main() -> Result<()> {
     let args = Cli::parse();
+    let result = run(&args);
 
+    // Syntax errors are printed in the format asked for; other errors end
+    // up in the usual `Error: ...`
+    if let Some(report) = result
+        .as_ref()
+        .err()
+        .and_then(|e| e.downcast_ref::<parser::Report>())
+    {
+        match args.message_format {
+            MessageFormat::Human => eprintln!("{}", report),
+            MessageFormat::Json => {
+                for message in &report.messages {
+                    println!("{}", serde_json::to_string(message)?);
+                }
+            }
+        }
+        std::process::exit(1);
+    }
+    result
+}
+
+fn run(args: &Cli) -> Result<()> {
     let source = std::fs::read_to_string(&args.input)?;
 
+    // The AST as parsed, before the checker resolves and rewrites it
+    if let Some(AstFormat::Json) = args.dump_ast {
+        let (program, _) = loader::load_program(&args.input, &source)?;
+        println!("{}", serde_json::to_string_pretty(&program)?);
+        return Ok(());
+    }
+
     if args.backend == "interpreter" {
         // For the interpreter, we need to parse to AST, not BridgeIR.
-        let program = parser::parse_to_ast(&source)?;
-        let interpreter = interpreter::Interpreter::new();
+        let (mut program, source_map) = loader::load_program(&args.input, &source)?;
+        checker::check_program(&mut program)?;
+        let mut interpreter = interpreter::Interpreter::new().with_source(source_map);
         interpreter.run(&program);
         Ok(())
     } else {
         // For compilation backends (bridge, llvm, etc.), we use the orchestrator flow (or similar)
         let frontend = NikaiaFrontend {
-            backend: args.backend.clone(),
+            entry: args.input.clone(),
         };
         let bridge_module = frontend.parse(&source)?;
 
@@ -0,0 +1,139 @@
+// crates/nikaia/src/parser/expected.rs
+// Erwartete Tokens: wie weit der Parser kam und was er dort erwartet hätte.
+//
+// Every token parser reports the offset it looked at and, if the token there
+// was not the one it wanted, what it wanted. Only the furthest offset is kept:
+// that is where the alternatives gave up, so a syntax error is placed there
+// and lists what was expected there (recovery.rs).
+//
+// Rules that start with many different tokens open with a label instead. A
+// label names the rule ("expression", "type") and hides the tokens the rule
+// can start with, so `let x = ;` expects an expression rather than an
+// identifier, an integer, `if`, `match` and a dozen more. Tokens wanted after
+// the rule gave up, like the `}` of `{ let x = 1`, are still listed.
+
+use super::trivia;
+use std::cell::RefCell;
+use std::mem;
+use winnow::stream::{AsChar, Location, Stream, StreamIsPartial};
+use winnow::ModalResult;
+
+/// The furthest offset the parser got to and what it expected there, in the
+/// order the alternatives were tried.
+#[derive(Debug, Default)]
+pub(super) struct Furthest {
+    pub(super) offset: usize,
+    pub(super) expected: Vec<String>,
+    // Tokens that a label given at `offset` stands for
+    hidden: Vec<&'static str>,
+}
+
+thread_local! {
+    static FURTHEST: RefCell<Furthest> = RefCell::default();
+}
+
+macro_rules! labels {
+    ($($parser:ident $text:literal [$($first:literal),* $(,)?],)*) => {
+        $(
+            #[doc = concat!("Label: ", $text, ". Matches nothing and never fails.")]
+            pub(crate) fn $parser<I>(input: &mut I) -> ModalResult<()>
+            where
+                I: Stream + StreamIsPartial + Location,
+                <I as Stream>::Token: AsChar + Clone,
+            {
+                label(input, $text, &[$($first),*])
+            }
+        )*
+    };
+}
+
+// Each label with the tokens its rule starts with, as the token parsers name them
+labels! {
+    label_expression "expression" [
+        "identifier", "integer", "string literal", "`-`", "`!`", "`(`", "`[`", "`{`", "`if`",
+        "`while`", "`loop`", "`for`", "`break`", "`continue`", "`throw`", "`match`",
+        "`spawn`", "`fn`", "`true`", "`false`", "`null`", "`self`",
+    ],
+    label_type "type" ["identifier"],
+    label_pattern "pattern" [
+        "`-`", "integer", "string literal", "`true`", "`false`", "identifier",
+    ],
+}
+
+/// Returns how far the parser got since the last call and starts over.
+pub(super) fn take_furthest() -> Furthest {
+    FURTHEST.with(|furthest| mem::take(&mut *furthest.borrow_mut()))
+}
+
+/// Puts back what `take_furthest` returned, for a caller that turned out not
+/// to need it.
+pub(super) fn restore(taken: Furthest) {
+    reach(taken.offset);
+    FURTHEST.with(|furthest| {
+        let mut furthest = furthest.borrow_mut();
+        let furthest = &mut *furthest;
+        if furthest.offset == taken.offset {
+            furthest.hidden.extend(taken.hidden);
+            for expected in taken.expected {
+                if !furthest.expected.contains(&expected) {
+                    furthest.expected.push(expected);
+                }
+            }
+            let hidden = &furthest.hidden;
+            furthest
+                .expected
+                .retain(|token| !hidden.contains(&token.as_str()));
+        }
+    });
+}
+
+/// The parser looked at `offset`.
+pub(super) fn reach(offset: usize) {
+    FURTHEST.with(|furthest| {
+        let mut furthest = furthest.borrow_mut();
+        if offset > furthest.offset {
+            *furthest = Furthest {
+                offset,
+                ..Furthest::default()
+            };
+        }
+    });
+}
+
+/// A token parser wanted `token` at `offset`.
+pub(super) fn token(offset: usize, token: String) {
+    record(offset, token, &[]);
+}
+
+fn label<I>(input: &mut I, text: &str, first: &'static [&'static str]) -> ModalResult<()>
+where
+    I: Stream + StreamIsPartial + Location,
+    <I as Stream>::Token: AsChar + Clone,
+{
+    let start = input.checkpoint();
+    trivia::skip_trivia(input)?;
+    let offset = input.location();
+    input.reset(&start);
+    record(offset, text.to_string(), first);
+    Ok(())
+}
+
+/// Adds `expected` at `offset`, unless a label there stands for it. The
+/// tokens in `hides` are dropped at `offset`, whether they came before or
+/// come after.
+fn record(offset: usize, expected: String, hides: &'static [&'static str]) {
+    reach(offset);
+    FURTHEST.with(|furthest| {
+        let mut furthest = furthest.borrow_mut();
+        if furthest.offset != offset || furthest.hidden.contains(&expected.as_str()) {
+            return;
+        }
+        furthest.hidden.extend(hides);
+        furthest
+            .expected
+            .retain(|token| !hides.contains(&token.as_str()));
+        if !furthest.expected.contains(&expected) {
+            furthest.expected.push(expected);
+        }
+    });
+}
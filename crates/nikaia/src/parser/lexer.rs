@@ -0,0 +1,438 @@
+// crates/nikaia/src/parser/lexer.rs
+// Tokens: Schlüsselwörter, Namen, Literale, Satzzeichen und Trivia.
+//
+// winnow-grammar parses text, so the grammar cannot run on a token vector.
+// Its terminals call the token parsers at the end of this file instead: each
+// skips trivia, lexes exactly one token with `lex` and checks its kind. This is
+// the only place where keywords and names are told apart, so `identifier`
+// never matches `let` and `kw_let` never matches the start of `letter`, and
+// `punct_lt` never matches the start of `<=`. `tokenize` lexes a whole file
+// the same way.
+
+use super::expected;
+use super::trivia::{self, eat, Comment};
+use crate::ast::Ident;
+use std::fmt;
+use std::ops::Range;
+use winnow::error::{AddContext, ContextError, ErrMode, StrContext, StrContextValue};
+use winnow::stream::{AsChar, LocatingSlice, Location, Stream, StreamIsPartial};
+use winnow::ModalResult;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
+    Keyword(Keyword),
+    Ident(String),
+    Int(i64),
+    /// The string's value, escapes resolved
+    Str(String),
+    Punct(&'static str),
+    Trivia(Trivia),
+    /// A character no token starts with, an unterminated string or an
+    /// integer too large for `i64`
+    Invalid,
+}
+
+// A line break is a token of its own: statements end with the line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trivia {
+    Whitespace,
+    Newline,
+    Comment,
+    DocComment,
+}
+
+macro_rules! keywords {
+    ($($variant:ident $text:literal $parser:ident,)*) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum Keyword {
+            $($variant,)*
+        }
+
+        impl Keyword {
+            pub fn as_str(self) -> &'static str {
+                match self {
+                    $(Keyword::$variant => $text,)*
+                }
+            }
+
+            fn from_word(word: &str) -> Option<Self> {
+                match word {
+                    $($text => Some(Keyword::$variant),)*
+                    _ => None,
+                }
+            }
+        }
+
+        $(
+            #[doc = concat!("The `", $text, "` keyword; returns its span.")]
+            pub(crate) fn $parser<I>(input: &mut I) -> ModalResult<Range<usize>>
+            where
+                I: Stream + StreamIsPartial + Location,
+                <I as Stream>::Token: AsChar + Clone,
+            {
+                keyword(input, Keyword::$variant)
+            }
+        )*
+    };
+}
+
+// Reserved words never lex as names. `self` is one of them; `var_expr` reads
+// it as a variable.
+keywords! {
+    Break "break" kw_break,
+    Catch "catch" kw_catch,
+    Continue "continue" kw_continue,
+    Else "else" kw_else,
+    Enum "enum" kw_enum,
+    False "false" kw_false,
+    Fn "fn" kw_fn,
+    For "for" kw_for,
+    If "if" kw_if,
+    Impl "impl" kw_impl,
+    In "in" kw_in,
+    Let "let" kw_let,
+    Loop "loop" kw_loop,
+    Match "match" kw_match,
+    Move "move" kw_move,
+    Mut "mut" kw_mut,
+    Null "null" kw_null,
+    Pub "pub" kw_pub,
+    SelfValue "self" kw_self,
+    Spawn "spawn" kw_spawn,
+    Struct "struct" kw_struct,
+    Sync "sync" kw_sync,
+    Throw "throw" kw_throw,
+    Throws "throws" kw_throws,
+    Trait "trait" kw_trait,
+    True "true" kw_true,
+    Use "use" kw_use,
+    While "while" kw_while,
+}
+
+macro_rules! punctuation {
+    ($($text:literal $parser:ident,)*) => {
+        const PUNCTUATION: &[&str] = &[$($text,)*];
+
+        $(
+            #[doc = concat!("The `", $text, "` token; returns its span.")]
+            pub(crate) fn $parser<I>(input: &mut I) -> ModalResult<Range<usize>>
+            where
+                I: Stream + StreamIsPartial + Location,
+                <I as Stream>::Token: AsChar + Clone,
+            {
+                punctuation(input, $text)
+            }
+        )*
+    };
+}
+
+// Longest first, so `..=` is not read as `..` and `=`
+punctuation! {
+    "..=" punct_dot_dot_eq,
+    "::" punct_path_sep,
+    "->" punct_arrow,
+    "=>" punct_fat_arrow,
+    "?." punct_safe_dot,
+    "??" punct_coalesce,
+    ".." punct_dot_dot,
+    "==" punct_eq_eq,
+    "!=" punct_ne,
+    "<=" punct_le,
+    ">=" punct_ge,
+    "&&" punct_and_and,
+    "||" punct_or_or,
+    "+=" punct_plus_eq,
+    "-=" punct_minus_eq,
+    "*=" punct_star_eq,
+    "/=" punct_slash_eq,
+    "%=" punct_percent_eq,
+    "(" punct_open_paren,
+    ")" punct_close_paren,
+    "{" punct_open_brace,
+    "}" punct_close_brace,
+    "[" punct_open_bracket,
+    "]" punct_close_bracket,
+    "," punct_comma,
+    ";" punct_semi,
+    ":" punct_colon,
+    "." punct_dot,
+    "?" punct_question,
+    "=" punct_eq,
+    "<" punct_lt,
+    ">" punct_gt,
+    "+" punct_plus,
+    "-" punct_minus,
+    "*" punct_star,
+    "/" punct_slash,
+    "%" punct_percent,
+    "!" punct_bang,
+    "&" punct_amp,
+    "|" punct_pipe,
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenKind::Keyword(keyword) => write!(f, "keyword `{}`", keyword.as_str()),
+            TokenKind::Ident(name) => write!(f, "identifier `{}`", name),
+            TokenKind::Int(value) => write!(f, "integer `{}`", value),
+            TokenKind::Str(_) => f.write_str("string literal"),
+            TokenKind::Punct(punct) => write!(f, "`{}`", punct),
+            TokenKind::Trivia(Trivia::Whitespace) => f.write_str("whitespace"),
+            TokenKind::Trivia(Trivia::Newline) => f.write_str("line break"),
+            TokenKind::Trivia(_) => f.write_str("comment"),
+            TokenKind::Invalid => f.write_str("invalid token"),
+        }
+    }
+}
+
+// --- Lexing ---
+
+/// All of `source` as tokens, trivia included. Lexing never fails; text that
+/// starts no token becomes `Invalid`.
+pub fn tokenize(source: &str) -> Vec<Token> {
+    let mut input = LocatingSlice::new(source);
+    std::iter::from_fn(|| lex(&mut input)).collect()
+}
+
+/// The first token that is not trivia and ends after `offset`, for naming
+/// what a parse error found.
+pub fn token_after(source: &str, offset: usize) -> Option<Token> {
+    tokenize(source)
+        .into_iter()
+        .find(|token| token.span.end > offset && !matches!(token.kind, TokenKind::Trivia(_)))
+}
+
+/// The token at the start of `input`, or `None` at its end.
+pub(super) fn lex<I>(input: &mut I) -> Option<Token>
+where
+    I: Stream + Location,
+    <I as Stream>::Token: AsChar,
+{
+    let start = input.location();
+    let checkpoint = input.checkpoint();
+    let kind = match input.next_token()?.as_char() {
+        '\n' => TokenKind::Trivia(Trivia::Newline),
+        c if c.is_whitespace() => {
+            take_while(input, |c| c.is_whitespace() && c != '\n');
+            TokenKind::Trivia(Trivia::Whitespace)
+        }
+        c if c.is_alphabetic() || c == '_' => {
+            let mut word = c.to_string();
+            word.push_str(&take_while(input, |c| c.is_alphanumeric() || c == '_'));
+            match Keyword::from_word(&word) {
+                Some(keyword) => TokenKind::Keyword(keyword),
+                None => TokenKind::Ident(word),
+            }
+        }
+        c if c.is_ascii_digit() => {
+            let mut digits = c.to_string();
+            digits.push_str(&take_while(input, |c| c.is_ascii_digit()));
+            digits.parse().map_or(TokenKind::Invalid, TokenKind::Int)
+        }
+        '"' => string(input),
+        _ => {
+            input.reset(&checkpoint);
+            match trivia::comment(input) {
+                Some(Comment::Doc) => TokenKind::Trivia(Trivia::DocComment),
+                Some(_) => TokenKind::Trivia(Trivia::Comment),
+                None => punct(input),
+            }
+        }
+    };
+    Some(Token {
+        kind,
+        span: start..input.location(),
+    })
+}
+
+fn take_while<I>(input: &mut I, accept: impl Fn(char) -> bool) -> String
+where
+    I: Stream,
+    <I as Stream>::Token: AsChar,
+{
+    let mut text = String::new();
+    loop {
+        let start = input.checkpoint();
+        match input.next_token().map(AsChar::as_char) {
+            Some(c) if accept(c) => text.push(c),
+            _ => {
+                input.reset(&start);
+                return text;
+            }
+        }
+    }
+}
+
+// After the opening quote. \n, \t, \r, \0, \\ and \" are escapes; a backslash
+// before anything else stays as it is.
+fn string<I>(input: &mut I) -> TokenKind
+where
+    I: Stream,
+    <I as Stream>::Token: AsChar,
+{
+    let mut value = String::new();
+    loop {
+        match input.next_token().map(AsChar::as_char) {
+            Some('"') => return TokenKind::Str(value),
+            Some('\\') => match input.next_token().map(AsChar::as_char) {
+                Some('n') => value.push('\n'),
+                Some('t') => value.push('\t'),
+                Some('r') => value.push('\r'),
+                Some('0') => value.push('\0'),
+                Some(c @ ('\\' | '"')) => value.push(c),
+                Some(c) => {
+                    value.push('\\');
+                    value.push(c);
+                }
+                None => return TokenKind::Invalid,
+            },
+            Some(c) => value.push(c),
+            None => return TokenKind::Invalid,
+        }
+    }
+}
+
+fn punct<I>(input: &mut I) -> TokenKind
+where
+    I: Stream,
+    <I as Stream>::Token: AsChar,
+{
+    let start = input.checkpoint();
+    for &punct in PUNCTUATION {
+        if punct.chars().all(|c| eat(input, c)) {
+            return TokenKind::Punct(punct);
+        }
+        input.reset(&start);
+    }
+    input.next_token();
+    TokenKind::Invalid
+}
+
+// --- Token parsers for the grammar ---
+
+/// A name that is not a keyword.
+pub(crate) fn identifier<I>(input: &mut I) -> ModalResult<Ident>
+where
+    I: Stream + StreamIsPartial + Location,
+    <I as Stream>::Token: AsChar + Clone,
+{
+    expect(
+        input,
+        StrContextValue::Description("identifier"),
+        |token| match token.kind {
+            TokenKind::Ident(name) => Some(Ident::new(&name, token.span)),
+            _ => None,
+        },
+    )
+}
+
+pub(crate) fn int_literal<I>(input: &mut I) -> ModalResult<i64>
+where
+    I: Stream + StreamIsPartial + Location,
+    <I as Stream>::Token: AsChar + Clone,
+{
+    expect(
+        input,
+        StrContextValue::Description("integer"),
+        |token| match token.kind {
+            TokenKind::Int(value) => Some(value),
+            _ => None,
+        },
+    )
+}
+
+pub(crate) fn str_literal<I>(input: &mut I) -> ModalResult<String>
+where
+    I: Stream + StreamIsPartial + Location,
+    <I as Stream>::Token: AsChar + Clone,
+{
+    expect(
+        input,
+        StrContextValue::Description("string literal"),
+        |token| match token.kind {
+            TokenKind::Str(value) => Some(value),
+            _ => None,
+        },
+    )
+}
+
+/// Succeeds without consuming anything when no line break comes before the
+/// next token. Statements end with their line, so a value or suffix that
+/// has to continue the current one checks this first: `x` followed by `= 1`
+/// on the next line is two statements, not an assignment.
+pub(crate) fn same_line<I>(input: &mut I) -> ModalResult<()>
+where
+    I: Stream + StreamIsPartial + Location,
+    <I as Stream>::Token: AsChar + Clone,
+{
+    let start = input.checkpoint();
+    let result = loop {
+        match lex(input).map(|token| token.kind) {
+            Some(TokenKind::Trivia(Trivia::Newline)) => {
+                break Err(ErrMode::Backtrack(ContextError::new()));
+            }
+            Some(TokenKind::Trivia(_)) => {}
+            _ => break Ok(()),
+        }
+    };
+    input.reset(&start);
+    result
+}
+
+fn keyword<I>(input: &mut I, keyword: Keyword) -> ModalResult<Range<usize>>
+where
+    I: Stream + StreamIsPartial + Location,
+    <I as Stream>::Token: AsChar + Clone,
+{
+    expect(
+        input,
+        StrContextValue::StringLiteral(keyword.as_str()),
+        |token| (token.kind == TokenKind::Keyword(keyword)).then_some(token.span),
+    )
+}
+
+fn punctuation<I>(input: &mut I, punct: &'static str) -> ModalResult<Range<usize>>
+where
+    I: Stream + StreamIsPartial + Location,
+    <I as Stream>::Token: AsChar + Clone,
+{
+    expect(input, StrContextValue::StringLiteral(punct), |token| {
+        (token.kind == TokenKind::Punct(punct)).then_some(token.span)
+    })
+}
+
+/// Skips trivia and lexes one token, which `accept` turns into the result.
+/// If it refuses, nothing past the trivia is consumed and the error names
+/// `expected`, which is also reported to expected.rs.
+fn expect<I, T>(
+    input: &mut I,
+    expected: StrContextValue,
+    accept: impl FnOnce(Token) -> Option<T>,
+) -> ModalResult<T>
+where
+    I: Stream + StreamIsPartial + Location,
+    <I as Stream>::Token: AsChar + Clone,
+{
+    trivia::skip_trivia(input)?;
+    let start = input.checkpoint();
+    let offset = input.location();
+    if let Some(value) = lex(input).and_then(accept) {
+        expected::reach(input.location());
+        return Ok(value);
+    }
+    input.reset(&start);
+    let token = match expected {
+        StrContextValue::StringLiteral(text) => format!("`{}`", text),
+        ref other => other.to_string(),
+    };
+    expected::token(offset, token);
+    let error = ContextError::new().add_context(input, &start, StrContext::Expected(expected));
+    Err(ErrMode::Backtrack(error))
+}
@@ -1,31 +1,143 @@
 // crates/nikaia/src/parser/mod.rs
 use crate::ast;
+use crate::checker;
+use crate::loader;
 use anyhow::Result;
 use bridge_ir::{
-    BridgeBlock, BridgeCall, BridgeExpr, BridgeFunction, BridgeItem, BridgeLetStmt, BridgeLiteral,
-    BridgeModule, BridgeStmt,
+    BridgeArg, BridgeArm, BridgeAssign, BridgeBinOp, BridgeBinary, BridgeBlock, BridgeBreak,
+    BridgeCall, BridgeClosure, BridgeCompoundAssign, BridgeEnum, BridgeExpr, BridgeField,
+    BridgeFieldAccess, BridgeFieldInit, BridgeFieldPattern, BridgeFor, BridgeFunction,
+    BridgeGenericParam, BridgeIf, BridgeImpl, BridgeIndex, BridgeItem, BridgeLetStmt, BridgeList,
+    BridgeLiteral, BridgeLoop, BridgeMatch, BridgeMethodCall, BridgeModule, BridgePattern,
+    BridgeRange, BridgeRangePattern, BridgeReceiver, BridgeSafeAccess, BridgeSafeNav,
+    BridgeSourceMap, BridgeSpawn, BridgeStmt, BridgeStruct, BridgeStructLit, BridgeThrow,
+    BridgeTrait, BridgeTraitMethod, BridgeTry, BridgeUnOp, BridgeUnary, BridgeUse, BridgeVariant,
+    BridgeVariantData, BridgeWhile,
 };
+use std::iter::Peekable;
+use std::ops::Range;
+use std::path::Path;
 use winnow_grammar::grammar;
 
+mod expected;
+pub mod lexer;
+mod recovery;
+mod report;
+mod trivia;
+
+pub use report::{Message, Report};
+
 // --- Public API ---
 
-pub fn parse_to_bridge(input: &str) -> Result<BridgeModule> {
-    let program = parse_to_ast(input)?;
-    lower_program(program)
+/// `input` is the project's entry file at `entry`; the modules it imports are
+/// loaded from the files next to it (Kap 9.1).
+pub fn parse_to_bridge(input: &str, entry: &Path) -> Result<BridgeModule> {
+    let (mut program, source_map) = loader::load_program(entry, input)?;
+    checker::check_program(&mut program)?;
+    lower_program(program, source_map)
+}
+
+pub fn parse_to_ast(source: &str) -> std::result::Result<ast::Program, SyntaxErrors> {
+    let (program, errors) = parse_with_diagnostics(source);
+    if errors.is_empty() {
+        Ok(program)
+    } else {
+        Err(SyntaxErrors(errors))
+    }
+}
+
+/// Every syntax error in a file, in source order.
+#[derive(Debug)]
+pub struct SyntaxErrors(pub Vec<ast::Diagnostic>);
+
+impl SyntaxErrors {
+    /// The errors placed in the file `source_map` describes.
+    pub fn report(&self, source_map: &ast::SourceMap, source: &str) -> Report {
+        Report {
+            messages: self
+                .0
+                .iter()
+                .map(|error| Message::new(error, source_map, source))
+                .collect(),
+        }
+    }
+}
+
+// Without a source map the errors can only point at byte offsets
+impl std::fmt::Display for SyntaxErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, error) in self.0.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            let summary = report::summary(&error.message, &error.expected);
+            write!(f, "byte {}: {}", error.span.start, summary)?;
+        }
+        Ok(())
+    }
 }
 
-pub fn parse_to_ast(input: &str) -> Result<ast::Program> {
+impl std::error::Error for SyntaxErrors {}
+
+/// Parses `source` past syntax errors (Part II, Kap 10.6): a statement or item
+/// that does not parse becomes an error node, and parsing resumes at the next
+/// one. Returns the program with its error nodes and every error in it.
+pub fn parse_with_diagnostics(source: &str) -> (ast::Program, Vec<ast::Diagnostic>) {
     use winnow::stream::LocatingSlice;
     use winnow::Parser;
 
+    expected::take_furthest();
     // Wrap input in LocatingSlice to provide Location trait required by the grammar
-    let input = LocatingSlice::new(input);
+    let input = LocatingSlice::new(source);
 
     // The macro generates a module `CompilerGrammar`
     // The rule `program` becomes `parse_program`
-    CompilerGrammar::parse_program
-        .parse(input)
-        .map_err(|e| anyhow::anyhow!("Parse error:\n{}", e))
+    match CompilerGrammar::parse_program.parse(input) {
+        Ok(mut program) => {
+            let errors = syntax_errors(&mut program);
+            (program, errors)
+        }
+        // Recovery skips whatever no item matches, so this only happens if
+        // the grammar itself gives up
+        Err(e) => {
+            let mut error = match lexer::token_after(source, e.offset()) {
+                Some(token) => ast::Diagnostic {
+                    message: format!("unexpected {}", token.kind),
+                    span: token.span,
+                    expected: Vec::new(),
+                },
+                None => ast::Diagnostic {
+                    message: "unexpected end of input".to_string(),
+                    span: source.len()..source.len(),
+                    expected: Vec::new(),
+                },
+            };
+            let furthest = expected::take_furthest();
+            if error.span.start == furthest.offset {
+                error.expected = furthest.expected;
+            }
+            (ast::Program { items: Vec::new() }, vec![error])
+        }
+    }
+}
+
+/// The error nodes' diagnostics, in source order.
+fn syntax_errors(program: &mut ast::Program) -> Vec<ast::Diagnostic> {
+    let mut errors: Vec<ast::Diagnostic> = program
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            ast::Item::Error { error, .. } => Some(error.clone()),
+            _ => None,
+        })
+        .collect();
+    checker::visit_exprs(program, &mut |expr| {
+        if let ast::ExprKind::Error(error) = &expr.kind {
+            errors.push(error.clone());
+        }
+    });
+    errors.sort_by_key(|error| error.span.start);
+    errors
 }
 
 // --- Grammar Definition ---
@@ -33,56 +145,306 @@ pub fn parse_to_ast(input: &str) -> Result<ast::Program> {
 grammar! {
     grammar CompilerGrammar {
         use crate::ast::*;
-        use syn::Ident;
-        use proc_macro2::Span;
-        use winnow::ascii::{multispace0, digit1};
+        use crate::parser::{block_expr, climb_binary, item_at};
+        use crate::parser::lexer::{identifier, int_literal, same_line, str_literal};
+        use crate::parser::lexer::{kw_break, kw_catch, kw_continue, kw_else, kw_enum, kw_false, kw_fn, kw_for, kw_if, kw_impl, kw_in, kw_let, kw_loop, kw_match, kw_move, kw_mut, kw_null, kw_pub, kw_self, kw_spawn, kw_struct, kw_sync, kw_throw, kw_throws, kw_trait, kw_true, kw_use, kw_while};
+        use crate::parser::lexer::{punct_amp, punct_and_and, punct_arrow, punct_bang, punct_close_brace, punct_close_bracket, punct_close_paren, punct_coalesce, punct_colon, punct_comma, punct_dot, punct_dot_dot, punct_dot_dot_eq, punct_eq, punct_eq_eq, punct_fat_arrow, punct_ge, punct_gt, punct_le, punct_lt, punct_minus, punct_minus_eq, punct_ne, punct_open_brace, punct_open_bracket, punct_open_paren, punct_or_or, punct_path_sep, punct_percent, punct_percent_eq, punct_pipe, punct_plus, punct_plus_eq, punct_question, punct_safe_dot, punct_semi, punct_slash, punct_slash_eq, punct_star, punct_star_eq};
+        use crate::parser::expected::{label_expression, label_pattern, label_type};
+        use crate::parser::recovery::{skip_item, skip_statement};
+        use crate::parser::trivia::{adjacent_dot, adjacent_safe_dot, doc_comment, skip_trivia, skip_trivia_keep_docs};
 
         // --- Entry Point ---
         // Rule 'program' -> generates 'parse_program'
         pub rule program -> Program =
-            _start:skip_ws
+            _start:skip_to_docs
             items:item*
             _end:skip_ws
             -> {
                 Program { items }
             }
 
-        rule skip_ws -> () = multispace0 -> { () }
+        // Whitespace and comments, see parser/trivia.rs. Token parsers skip
+        // them on their own; rules only need this in front of a recorded span
+        // and at the end of the file.
+        rule skip_ws -> () = skip_trivia -> { () }
+
+        // Stops in front of `///`, so the next item or field can collect its docs
+        rule skip_to_docs -> () = skip_trivia_keep_docs -> { () }
 
         // --- Top-Level Items ---
+        // An item spans from its docs to its last token; item_at fills it in
+        // Part II, Kap 10.6: an item that does not parse is skipped up to the next one
         rule item -> Item =
+            i:item_kind @ span _sp:skip_to_docs -> { item_at(i, span) }
+          | e:skip_item _sp:skip_to_docs -> { e }
+
+        rule item_kind -> Item =
             i:fn_item -> { i }
+          | s:struct_item -> { s }
+          | e:enum_item -> { e }
+          | i:impl_item -> { i }
+          | t:trait_item -> { t }
+          | u:use_item -> { u }
 
-        rule kw_sync -> () = "sync" -> { () }
+        // Kap 9.2: use users::User, use std::http
+        // The loader resolves the path against the project's src/ tree.
+        rule use_item -> Item =
+            _use:kw_use
+            head:identifier
+            tail:path_tail*
+            -> {
+                let mut path = vec![head.to_string()];
+                path.extend(tail);
+                Item::Import { path: path.join("::"), span: 0..0 }
+            }
 
         rule fn_item -> Item =
-            "fn"
-            _sp:skip_ws
-            name:ident
-            _sp2:skip_ws
+            docs:doc_comment*
+            is_pub:kw_pub?
+            _fn:kw_fn
+            name:identifier
             generics:generic_list?
-            args:fn_arg_list
-            _sp3:skip_ws
+            params:fn_params
             is_sync:kw_sync?
-            _sp4:skip_ws
+            throws:throws_clause?
             ret:return_type_arrow?
-            _sp5:skip_ws
             body:block
             -> {
                 Item::Fn {
-                    name: Ident::new(&name, Span::call_site()),
+                    name,
+                    is_pub: is_pub.is_some(),
                     generics: generics.unwrap_or_default(),
-                    args,
+                    receiver: params.0,
+                    args: params.1,
+                    config: params.2,
+                    throws: throws.unwrap_or_default(),
                     ret_type: ret,
                     body,
-                    is_sync: is_sync.is_some()
+                    is_sync: is_sync.is_some(),
+                    docs,
+                    span: 0..0,
                 }
             }
 
+        rule impl_item -> Item =
+            docs:doc_comment*
+            _impl:kw_impl
+            generics:generic_list?
+            first:type_ref
+            for_target:impl_for?
+            _open:punct_open_brace
+            _sp:skip_to_docs
+            methods:impl_method*
+            _close:punct_close_brace
+            -> {
+                // `impl Trait for Type` names the trait first
+                let generics = generics.unwrap_or_default();
+                match for_target {
+                    Some(target) => Item::Impl { generics, trait_ref: Some(first), target, methods, docs, span: 0..0 },
+                    None => Item::Impl { generics, trait_ref: None, target: first, methods, docs, span: 0..0 },
+                }
+            }
+
+        rule impl_for -> Type =
+            _for:kw_for t:type_ref -> { t }
+
+        rule trait_item -> Item =
+            docs:doc_comment*
+            is_pub:kw_pub?
+            _trait:kw_trait
+            name:identifier
+            generics:generic_list?
+            _open:punct_open_brace
+            _sp:skip_to_docs
+            methods:trait_method_at*
+            _close:punct_close_brace
+            -> {
+                Item::Trait {
+                    name,
+                    is_pub: is_pub.is_some(),
+                    generics: generics.unwrap_or_default(),
+                    methods,
+                    docs,
+                    span: 0..0,
+                }
+            }
+
+        rule trait_method_at -> TraitMethod =
+            m:trait_method @ span _sp:skip_to_docs -> { TraitMethod { span, ..m } }
+
+        // Required methods end after their signature, provided ones carry a body
+        rule trait_method -> TraitMethod =
+            docs:doc_comment*
+            _fn:kw_fn
+            name:identifier
+            params:fn_params
+            throws:throws_clause?
+            ret:return_type_arrow?
+            default:block?
+            _semi:punct_semi?
+            -> {
+                TraitMethod {
+                    name,
+                    receiver: params.0,
+                    args: params.1,
+                    config: params.2,
+                    throws: throws.unwrap_or_default(),
+                    ret_type: ret,
+                    default,
+                    docs,
+                    span: 0..0,
+                }
+            }
+
+        rule impl_method -> Item =
+            f:fn_item @ span _sp:skip_to_docs -> { item_at(f, span) }
+          | c:constructor_item @ span _sp:skip_to_docs -> { item_at(c, span) }
+
+        // Kap 4.2: pub fn(username: String) -> User { ... } makes User("Alice") work.
+        // It has no name in Nikaia and becomes `new` in Rust.
+        rule constructor_item -> Item =
+            docs:doc_comment*
+            is_pub:kw_pub?
+            kw:kw_fn
+            params:fn_params
+            throws:throws_clause?
+            ret:return_type_arrow?
+            body:block
+            -> {
+                Item::Fn {
+                    name: Ident::new(CONSTRUCTOR, kw),
+                    is_pub: is_pub.is_some(),
+                    generics: Vec::new(),
+                    receiver: params.0,
+                    args: params.1,
+                    config: params.2,
+                    throws: throws.unwrap_or_default(),
+                    ret_type: ret,
+                    body,
+                    is_sync: false,
+                    docs,
+                    span: 0..0,
+                }
+            }
+
+        rule struct_item -> Item =
+            docs:doc_comment*
+            is_pub:kw_pub?
+            _struct:kw_struct
+            name:identifier
+            generics:generic_list?
+            _open:punct_open_brace
+            _sp:skip_to_docs
+            fields:field_defs?
+            _close:punct_close_brace
+            -> {
+                Item::Struct {
+                    name,
+                    is_pub: is_pub.is_some(),
+                    generics: generics.unwrap_or_default(),
+                    fields: fields.unwrap_or_default(),
+                    docs,
+                    span: 0..0,
+                }
+            }
+
+        rule field_defs -> Vec<FieldDef> =
+            head:field_def tail:field_def_tail* _comma:punct_comma? -> {
+                let mut fields = vec![head];
+                fields.extend(tail);
+                fields
+            }
+
+        rule field_def_tail -> FieldDef =
+            _comma:punct_comma _sp:skip_to_docs f:field_def -> { f }
+
+        // Kap 9.2: items and fields are private unless marked `pub`
+        rule field_def -> FieldDef =
+            docs:doc_comment* is_pub:kw_pub? name:identifier _colon:punct_colon ty:type_ref -> {
+                FieldDef {
+                    name,
+                    ty,
+                    is_pub: is_pub.is_some(),
+                    docs,
+                }
+            }
+
+        rule enum_item -> Item =
+            docs:doc_comment*
+            is_pub:kw_pub?
+            _enum:kw_enum
+            name:identifier
+            generics:generic_list?
+            _open:punct_open_brace
+            _sp:skip_to_docs
+            variants:enum_variants?
+            _close:punct_close_brace
+            -> {
+                Item::Enum {
+                    name,
+                    is_pub: is_pub.is_some(),
+                    generics: generics.unwrap_or_default(),
+                    variants: variants.unwrap_or_default(),
+                    docs,
+                    span: 0..0,
+                }
+            }
+
+        rule enum_variants -> Vec<EnumVariant> =
+            head:enum_variant tail:enum_variant_tail* _comma:punct_comma? -> {
+                let mut variants = vec![head];
+                variants.extend(tail);
+                variants
+            }
+
+        rule enum_variant_tail -> EnumVariant =
+            _comma:punct_comma _sp:skip_to_docs v:enum_variant -> { v }
+
+        rule enum_variant -> EnumVariant =
+            docs:doc_comment* name:identifier data:variant_data_end? -> {
+                // Without data the variant ends with its name
+                let (data, end) = data.unwrap_or((VariantData::Unit, name.span.end));
+                EnumVariant {
+                    span: name.span.start..end,
+                    name,
+                    data,
+                    docs,
+                }
+            }
+
+        rule variant_data_end -> (VariantData, usize) =
+            d:variant_data @ span -> { (d, span.end) }
+
+        rule variant_data -> VariantData =
+            _open:punct_open_brace _sp:skip_to_docs fields:field_defs? _close:punct_close_brace -> {
+                VariantData::Struct(fields.unwrap_or_default())
+            }
+          | _open:punct_open_paren types:type_refs? _close:punct_close_paren -> {
+                VariantData::Tuple(types.unwrap_or_default())
+            }
+
         // --- Argumente & Typen ---
 
-        rule fn_arg_list -> Vec<FnArg> =
-            "(" _sp:skip_ws args:fn_arg_defs? _sp2:skip_ws ")" -> { args.unwrap_or_default() }
+        // Methods open their parameter list with a receiver: (&self, name: String)
+        // Kap 5.4: config parameters follow a `;`: (url: String; timeout: i32 = 30)
+        rule fn_params -> (Option<Receiver>, Vec<FnArg>, Vec<FnArg>) =
+            _open:punct_open_paren r:receiver rest:receiver_rest? config:config_params? _close:punct_close_paren -> {
+                (Some(r), rest.unwrap_or_default(), config.unwrap_or_default())
+            }
+          | _open:punct_open_paren args:fn_arg_defs? config:config_params? _close:punct_close_paren -> {
+                (None, args.unwrap_or_default(), config.unwrap_or_default())
+            }
+
+        rule config_params -> Vec<FnArg> =
+            _semi:punct_semi args:fn_arg_defs -> { args }
+
+        rule receiver_rest -> Vec<FnArg> =
+            _comma:punct_comma args:fn_arg_defs -> { args }
+
+        rule receiver -> Receiver =
+            _amp:punct_amp _mut:kw_mut _self:kw_self -> { Receiver::RefMut }
+          | _amp:punct_amp _self:kw_self -> { Receiver::Ref }
+          | _self:kw_self -> { Receiver::Value }
 
         rule fn_arg_defs -> Vec<FnArg> =
             head:fn_arg_def tail:fn_arg_def_tail* -> {
@@ -92,19 +454,25 @@ grammar! {
             }
 
         rule fn_arg_def_tail -> FnArg =
-            _sp:skip_ws "," _sp2:skip_ws arg:fn_arg_def -> { arg }
+            _comma:punct_comma arg:fn_arg_def -> { arg }
 
         rule fn_arg_def -> FnArg =
-            name:ident _sp:skip_ws ":" _sp2:skip_ws ty:type_ref -> {
-                FnArg { name: Ident::new(&name, Span::call_site()), ty }
+            name:identifier _colon:punct_colon ty:type_ref default:arg_default? -> {
+                FnArg { name, ty, default }
             }
 
+        rule arg_default -> Expr =
+            _eq:punct_eq e:expr -> { e }
+
         rule return_type_arrow -> Type =
-            "->" _sp:skip_ws ty:type_ref -> { ty }
+            _arrow:punct_arrow ty:type_ref -> { ty }
+
+        // Kap 7.1: fn fetch_config() throws IoError, NetworkError -> String
+        rule throws_clause -> Vec<Type> =
+            _throws:kw_throws types:type_refs -> { types }
 
-        // USING [ ] SYNTAX directly for testing
         rule generic_list -> Vec<GenericParam> =
-            [ _sp:skip_ws params:generic_params? _sp2:skip_ws ] -> { params.unwrap_or_default() }
+            _open:punct_open_bracket params:generic_params? _close:punct_close_bracket -> { params.unwrap_or_default() }
 
         rule generic_params -> Vec<GenericParam> =
             head:generic_param tail:generic_param_tail* -> {
@@ -114,22 +482,48 @@ grammar! {
             }
 
         rule generic_param_tail -> GenericParam =
-            _sp:skip_ws "," _sp2:skip_ws p:generic_param -> { p }
+            _comma:punct_comma p:generic_param -> { p }
 
         rule generic_param -> GenericParam =
-            name:ident
-            -> { GenericParam { name: Ident::new(&name, Span::call_site()) } }
+            name:identifier bounds:generic_bounds?
+            -> {
+                GenericParam {
+                    name,
+                    bounds: bounds.unwrap_or_default(),
+                }
+            }
+
+        rule generic_bounds -> Vec<Type> =
+            _colon:punct_colon head:type_ref tail:generic_bound_tail* -> {
+                let mut bounds = vec![head];
+                bounds.extend(tail);
+                bounds
+            }
+
+        rule generic_bound_tail -> Type =
+            _plus:punct_plus t:type_ref -> { t }
 
         rule type_ref -> Type =
-            name:ident
+            _l:label_type _sp:skip_ws t:type_parts @ span -> { Type { span, ..t } }
+
+        rule type_parts -> Type =
+            name:identifier
             generics:generic_type_args?
+            nullable:nullable_mark?
             -> {
-                Type { name: Ident::new(&name, Span::call_site()), generics: generics.unwrap_or_default() }
+                Type {
+                    name,
+                    generics: generics.unwrap_or_default(),
+                    nullable: nullable.is_some(),
+                    span: 0..0,
+                }
             }
 
-        // USING [ ] SYNTAX directly for testing
+        // Kap 2.3: String?
+        rule nullable_mark -> () = _mark:punct_question -> { () }
+
         rule generic_type_args -> Vec<Type> =
-            [ _sp:skip_ws args:type_refs? _sp2:skip_ws ] -> { args.unwrap_or_default() }
+            _open:punct_open_bracket args:type_refs? _close:punct_close_bracket -> { args.unwrap_or_default() }
 
         rule type_refs -> Vec<Type> =
             head:type_ref tail:type_ref_tail* -> {
@@ -139,67 +533,590 @@ grammar! {
             }
 
         rule type_ref_tail -> Type =
-            _sp:skip_ws "," _sp2:skip_ws t:type_ref -> { t }
+            _comma:punct_comma t:type_ref -> { t }
 
         // --- Statements & Blocks ---
 
+        // Spans start at the first token, so the rules that record one skip
+        // the trivia in front of it themselves
         rule block -> Block =
-            "{" _sp:skip_ws stmts:stmt_list _sp2:skip_ws "}" -> { Block { stmts } }
+            _sp:skip_ws b:block_parts @ span -> { Block { span, ..b } }
+
+        rule block_parts -> Block =
+            _open:punct_open_brace stmts:stmt_list _close:punct_close_brace -> { Block { stmts, span: 0..0 } }
 
         rule stmt_list -> Vec<Stmt> =
             stmts:stmt* -> { stmts }
 
+        // A statement that does not parse is skipped to its end, see parser/recovery.rs
         rule stmt -> Stmt =
             l:let_stmt -> { l }
           | e:expr_stmt -> { e }
+          | e:skip_statement -> { Stmt::Expr(e) }
 
-        rule kw_mut -> () = "mut" -> { () }
-
+        // A statement's span ends with its value, before any `;`
         rule let_stmt -> Stmt =
-            "let"
-            _sp:skip_ws
+            kw:kw_let
             mutable:kw_mut?
-            _sp2:skip_ws
-            name:ident
-            _sp3:skip_ws
+            name:identifier
             ty:type_annotation?
-            _sp4:skip_ws
-            "="
-            _sp5:skip_ws
+            _eq:punct_eq
             val:expr
-            _sp6:skip_ws
-            ";"?
-            _sp7:skip_ws
+            _semi:punct_semi?
             -> {
                 Stmt::Let {
-                    name: Ident::new(&name, Span::call_site()),
+                    name,
                     mutable: mutable.is_some(),
                     ty,
-                    value: val
+                    span: kw.start..val.span.end,
+                    value: val,
                 }
             }
 
         rule type_annotation -> Type =
-            ":" _sp:skip_ws ty:type_ref -> { ty }
+            _colon:punct_colon ty:type_ref -> { ty }
 
+        // x = 20, obj.x = 1, count += 1
         rule expr_stmt -> Stmt =
-            e:expr _sp:skip_ws ";"? _sp2:skip_ws -> { Stmt::Expr(e) }
+            e:expr assign:assign_tail? _semi:punct_semi? -> {
+                match assign {
+                    Some((Some(op), value)) => {
+                        let span = e.span.start..value.span.end;
+                        Stmt::CompoundAssign { target: e, op, value, span }
+                    }
+                    Some((None, value)) => {
+                        let span = e.span.start..value.span.end;
+                        Stmt::Assign { target: e, value, span }
+                    }
+                    None => Stmt::Expr(e),
+                }
+            }
+
+        // The `=` has to stay on the line of its target
+        rule assign_tail -> (Option<BinOp>, Expr) =
+            _nl:same_line op:assign_op value:expr -> { (Some(op), value) }
+          | _nl:same_line _eq:punct_eq value:expr -> { (None, value) }
+
+        rule assign_op -> BinOp =
+            _op:punct_plus_eq -> { BinOp::Add }
+          | _op:punct_minus_eq -> { BinOp::Sub }
+          | _op:punct_star_eq -> { BinOp::Mul }
+          | _op:punct_slash_eq -> { BinOp::Div }
+          | _op:punct_percent_eq -> { BinOp::Rem }
 
         // --- Expressions ---
 
+        // Ranges bind loosest: 0..count, 0..=5
+        // Kap 7.1: fetch_config() catch { ... }
         rule expr -> Expr =
-            c:call_expr -> { c }
-          | s:str_lit -> { s }
-          | i:int_lit -> { i }
-          | v:var_expr -> { v }
+            _l:label_expression start:binary_expr range:range_tail? handler:catch_tail? -> {
+                let expr = match range {
+                    Some((inclusive, end)) => {
+                        let span = start.span.start..end.span.end;
+                        let kind = ExprKind::Range {
+                            start: Box::new(start),
+                            end: Box::new(end),
+                            inclusive,
+                        };
+                        Expr::new(kind, span)
+                    }
+                    None => start,
+                };
+                match handler {
+                    Some(handler) => {
+                        let span = expr.span.start..handler.span.end;
+                        Expr::new(ExprKind::TryCatch { expr: Box::new(expr), handler }, span)
+                    }
+                    None => expr,
+                }
+            }
 
-        rule call_expr -> Expr =
-            func:ident _sp:skip_ws "(" _sp2:skip_ws args:call_args? _sp3:skip_ws ")" -> {
-                Expr::Call {
-                    func: Box::new(Expr::Variable(Ident::new(&func, Span::call_site()))),
-                    args: args.unwrap_or_default(),
+        // `catch` has to stay on the line of the call it guards
+        rule catch_tail -> Block =
+            _nl:same_line _catch:kw_catch handler:block -> { handler }
+
+        rule range_tail -> (bool, Expr) =
+            _range:punct_dot_dot_eq end:binary_expr -> { (true, end) }
+          | _range:punct_dot_dot end:binary_expr -> { (false, end) }
+
+        // Operands and operators are collected flat; climb_binary applies precedence.
+        // An operator has to stay on the line of its left operand.
+        rule binary_expr -> Expr =
+            head:unary_expr tail:binary_tail* -> { climb_binary(head, tail) }
+
+        rule binary_tail -> (BinOp, Expr) =
+            _nl:same_line op:bin_op rhs:unary_expr -> { (op, rhs) }
+
+        rule bin_op -> BinOp =
+            _op:punct_coalesce -> { BinOp::Coalesce }
+          | _op:punct_or_or -> { BinOp::Or }
+          | _op:punct_and_and -> { BinOp::And }
+          | _op:punct_eq_eq -> { BinOp::Eq }
+          | _op:punct_ne -> { BinOp::Ne }
+          | _op:punct_le -> { BinOp::Le }
+          | _op:punct_ge -> { BinOp::Ge }
+          | _op:punct_lt -> { BinOp::Lt }
+          | _op:punct_gt -> { BinOp::Gt }
+          | _op:punct_plus -> { BinOp::Add }
+          | _op:punct_minus -> { BinOp::Sub }
+          | _op:punct_star -> { BinOp::Mul }
+          | _op:punct_slash -> { BinOp::Div }
+          | _op:punct_percent -> { BinOp::Rem }
+
+        rule unary_op -> UnOp =
+            _op:punct_minus -> { UnOp::Neg }
+          | _op:punct_bang -> { UnOp::Not }
+
+        rule unary_expr -> Expr =
+            _sp:skip_ws op:unary_op @ op_span e:unary_expr -> {
+                let span = op_span.start..e.span.end;
+                Expr::new(ExprKind::Unary { op, expr: Box::new(e) }, span)
+            }
+          | _sp:skip_ws p:postfix_expr -> { p }
+
+        // Left-recursive so that chains like user.profile().name associate to the left.
+        // A call's own span is the method name, where a propagated error's trace points to.
+        rule postfix_expr -> Expr =
+            recv:postfix_expr _dot:punct_dot method:identifier call:call_tail @ call_span -> {
+                let span = recv.span.start..call_span.end;
+                let kind = ExprKind::MethodCall {
+                    receiver: Box::new(recv),
+                    method,
+                    args: call.0,
+                    config: call.1,
+                };
+                Expr::new(kind, span)
+            }
+          | base:postfix_expr _nl:same_line index:index_suffix @ index_span -> {
+                let span = base.span.start..index_span.end;
+                Expr::new(ExprKind::Index { expr: Box::new(base), index: Box::new(index) }, span)
+            }
+          | base:postfix_expr _dot:punct_dot field:identifier -> {
+                let span = base.span.start..field.span.end;
+                Expr::new(ExprKind::Field { expr: Box::new(base), field }, span)
+            }
+          // Kap 3.5: user?.display_name(), user?.profile
+          | recv:postfix_expr _dot:punct_safe_dot method:identifier call:call_tail @ call_span -> {
+                let span = recv.span.start..call_span.end;
+                let kind = ExprKind::SafeMethodCall {
+                    receiver: Box::new(recv),
+                    method,
+                    args: call.0,
+                    config: call.1,
+                    nullable: false,
+                };
+                Expr::new(kind, span)
+            }
+          | base:postfix_expr _dot:punct_safe_dot field:identifier -> {
+                let span = base.span.start..field.span.end;
+                Expr::new(ExprKind::SafeField { expr: Box::new(base), field, nullable: false }, span)
+            }
+          | p:primary_expr -> { p }
+
+        rule index_suffix -> Expr =
+            _open:punct_open_bracket index:expr _close:punct_close_bracket -> { index }
+
+        // Rules that start with a single token return the bare kind and get
+        // their span here; unary_expr has skipped the trivia in front of it.
+        // Rules whose span starts elsewhere work it out themselves.
+        rule primary_expr -> Expr =
+            f:if_expr @ span -> { Expr::new(f, span) }
+          | w:while_expr @ span -> { Expr::new(w, span) }
+          | l:loop_expr @ span -> { Expr::new(l, span) }
+          | f:for_expr @ span -> { Expr::new(f, span) }
+          | b:break_expr @ span -> { Expr::new(b, span) }
+          | c:continue_expr @ span -> { Expr::new(c, span) }
+          | t:throw_expr @ span -> { Expr::new(t, span) }
+          | m:match_expr @ span -> { Expr::new(m, span) }
+          | l:list_lit @ span -> { Expr::new(l, span) }
+          | s:spawn_expr @ span -> { Expr::new(s, span) }
+          | l:lambda_expr @ span -> { Expr::new(l, span) }
+          | p:paren_expr @ span -> { Expr::new(p, span) }
+          | b:block -> { block_expr(b) }
+          | s:struct_lit -> { s }
+          | p:path_call_expr -> { p }
+          | p:path_expr @ span -> { Expr::new(p, span) }
+          | c:call_expr -> { c }
+          | s:str_lit @ span -> { Expr::new(s, span) }
+          | i:int_lit @ span -> { Expr::new(i, span) }
+          | b:bool_lit @ span -> { Expr::new(b, span) }
+          | n:null_lit @ span -> { Expr::new(n, span) }
+          | v:var_expr @ span -> { Expr::new(v, span) }
+
+        // (a + b) * c: the operand keeps its own tree, so no node is needed
+        rule paren_expr -> ExprKind =
+            _open:punct_open_paren e:expr _close:punct_close_paren -> { e.kind }
+
+        // `if` is an expression: let status = if adult { "Adult" } else { "Minor" }
+        rule if_expr -> ExprKind =
+            _if:kw_if
+            cond:expr
+            then_branch:block
+            else_branch:else_branch?
+            -> {
+                ExprKind::If {
+                    cond: Box::new(cond),
+                    then_branch,
+                    else_branch,
+                }
+            }
+
+        // `else if` nests the following conditional as the only statement of the else block
+        rule else_branch -> Block =
+            _else:kw_else _sp:skip_ws nested:if_expr @ span -> {
+                Block { stmts: vec![Stmt::Expr(Expr::new(nested, span.clone()))], span }
+            }
+          | _else:kw_else b:block -> { b }
+
+        rule while_expr -> ExprKind =
+            _while:kw_while cond:expr body:block -> {
+                ExprKind::While { cond: Box::new(cond), body }
+            }
+
+        rule loop_expr -> ExprKind =
+            _loop:kw_loop body:block -> { ExprKind::Loop { body } }
+
+        rule for_expr -> ExprKind =
+            _for:kw_for
+            var:identifier
+            _in:kw_in
+            iter:expr
+            body:block
+            -> {
+                ExprKind::For {
+                    var,
+                    iter: Box::new(iter),
+                    body,
+                }
+            }
+
+        // The value of `break value` has to start on the same line
+        rule break_expr -> ExprKind =
+            _break:kw_break value:break_value? -> { ExprKind::Break(value.map(Box::new)) }
+
+        rule break_value -> Expr =
+            _nl:same_line e:expr -> { e }
+
+        rule continue_expr -> ExprKind =
+            _continue:kw_continue -> { ExprKind::Continue }
+
+        // Kap 7.1: throw TimeoutError("Too slow!")
+        // The span covers the thrown value; it is the first frame of the error's trace.
+        rule throw_expr -> ExprKind =
+            _throw:kw_throw _nl:same_line value:expr -> {
+                ExprKind::Throw { span: value.span.clone(), value: Box::new(value) }
+            }
+
+        // Kap 5.2: fn: a.id, fn: a + b
+        // Kap 5.3: fn { ... } and fn(user) { ... }
+        rule lambda_expr -> ExprKind =
+            _fn:kw_fn _colon:punct_colon body:lambda_body -> {
+                ExprKind::Lambda { params: Vec::new(), implicit: true, body: Box::new(body) }
+            }
+          | _fn:kw_fn _open:punct_open_paren params:ident_list? _close:punct_close_paren body:block -> {
+                ExprKind::Lambda {
+                    params: params.unwrap_or_default(),
+                    implicit: false,
+                    body: Box::new(block_expr(body)),
                 }
             }
+          | _fn:kw_fn body:block -> {
+                ExprKind::Lambda { params: Vec::new(), implicit: true, body: Box::new(block_expr(body)) }
+            }
+
+        rule ident_list -> Vec<Ident> =
+            head:identifier tail:ident_list_tail* _comma:punct_comma? -> {
+                let mut names = vec![head];
+                names.extend(tail);
+                names
+            }
+
+        rule ident_list_tail -> Ident =
+            _comma:punct_comma n:identifier -> { n }
+
+        // The body of `fn:` is an expression whose `.` chains have to stay tight:
+        // in `users.map fn: a.id .filter fn: a > 1` the `.` after the space ends
+        // the first lambda and continues the outer chain.
+        rule lambda_body -> Expr =
+            head:lambda_unary_expr tail:lambda_binary_tail* -> { climb_binary(head, tail) }
+
+        rule lambda_binary_tail -> (BinOp, Expr) =
+            _nl:same_line op:bin_op rhs:lambda_unary_expr -> { (op, rhs) }
+
+        rule lambda_unary_expr -> Expr =
+            _sp:skip_ws op:unary_op @ op_span e:lambda_unary_expr -> {
+                let span = op_span.start..e.span.end;
+                Expr::new(ExprKind::Unary { op, expr: Box::new(e) }, span)
+            }
+          | _sp:skip_ws p:lambda_postfix_expr -> { p }
+
+        rule lambda_postfix_expr -> Expr =
+            recv:lambda_postfix_expr _dot:adjacent_dot method:identifier call:call_tail @ call_span -> {
+                let span = recv.span.start..call_span.end;
+                let kind = ExprKind::MethodCall {
+                    receiver: Box::new(recv),
+                    method,
+                    args: call.0,
+                    config: call.1,
+                };
+                Expr::new(kind, span)
+            }
+          | base:lambda_postfix_expr _nl:same_line index:index_suffix @ index_span -> {
+                let span = base.span.start..index_span.end;
+                Expr::new(ExprKind::Index { expr: Box::new(base), index: Box::new(index) }, span)
+            }
+          | base:lambda_postfix_expr _dot:adjacent_dot field:identifier -> {
+                let span = base.span.start..field.span.end;
+                Expr::new(ExprKind::Field { expr: Box::new(base), field }, span)
+            }
+          | recv:lambda_postfix_expr _dot:adjacent_safe_dot method:identifier call:call_tail @ call_span -> {
+                let span = recv.span.start..call_span.end;
+                let kind = ExprKind::SafeMethodCall {
+                    receiver: Box::new(recv),
+                    method,
+                    args: call.0,
+                    config: call.1,
+                    nullable: false,
+                };
+                Expr::new(kind, span)
+            }
+          | base:lambda_postfix_expr _dot:adjacent_safe_dot field:identifier -> {
+                let span = base.span.start..field.span.end;
+                Expr::new(ExprKind::SafeField { expr: Box::new(base), field, nullable: false }, span)
+            }
+          | p:primary_expr -> { p }
+
+        // Kap 8.2: spawn({ ... }), spawn(move { ... }), spawn fn: expr, spawn move fn { ... }
+        rule spawn_expr -> ExprKind =
+            _spawn:kw_spawn _open:punct_open_paren is_move:kw_move? body:block _close:punct_close_paren -> {
+                ExprKind::Spawn { body: Box::new(block_expr(body)), is_move: is_move.is_some() }
+            }
+          | _spawn:kw_spawn is_move:kw_move? _fn:kw_fn body:spawn_body -> {
+                ExprKind::Spawn { body: Box::new(body), is_move: is_move.is_some() }
+            }
+
+        rule spawn_body -> Expr =
+            _colon:punct_colon e:expr -> { e }
+          | b:block -> { block_expr(b) }
+
+        rule match_expr -> ExprKind =
+            _match:kw_match
+            scrutinee:expr
+            _open:punct_open_brace
+            arms:match_arm*
+            _close:punct_close_brace
+            -> {
+                ExprKind::Match { expr: Box::new(scrutinee), arms }
+            }
+
+        // An arm spans from its pattern to the end of its body
+        rule match_arm -> MatchArm =
+            _sp:skip_ws
+            pattern:pattern @ pattern_span
+            guard:match_guard?
+            _arrow:punct_fat_arrow
+            body:expr
+            _comma:punct_comma?
+            -> {
+                MatchArm { span: pattern_span.start..body.span.end, pattern, guard, body }
+            }
+
+        rule match_guard -> Expr =
+            _if:kw_if e:expr -> { e }
+
+        // --- Patterns ---
+
+        rule pattern -> Pattern =
+            _l:label_pattern head:pattern_atom tail:pattern_or_tail* -> {
+                if tail.is_empty() {
+                    head
+                } else {
+                    let mut alternatives = vec![head];
+                    alternatives.extend(tail);
+                    Pattern::Or(alternatives)
+                }
+            }
+
+        rule pattern_or_tail -> Pattern =
+            _pipe:punct_pipe p:pattern_atom -> { p }
+
+        rule pattern_atom -> Pattern =
+            r:range_pattern -> { r }
+          | i:pattern_int -> { Pattern::LitInt(i) }
+          | s:str_literal -> { Pattern::LitStr(s) }
+          | _true:kw_true -> { Pattern::LitBool(true) }
+          | _false:kw_false -> { Pattern::LitBool(false) }
+          | v:variant_pattern -> { v }
+
+        rule range_pattern -> Pattern =
+            start:pattern_int _range:punct_dot_dot_eq end:pattern_int -> {
+                Pattern::Range { start, end, inclusive: true }
+            }
+          | start:pattern_int _range:punct_dot_dot end:pattern_int -> {
+                Pattern::Range { start, end, inclusive: false }
+            }
+
+        // -1 => ..., -5..=-1 => ...
+        rule pattern_int -> i64 =
+            _minus:punct_minus i:int_literal -> { -i }
+          | i:int_literal -> { i }
+
+        // A lone name is a binding; the checker turns names of unit variants into variants
+        rule variant_pattern -> Pattern =
+            head:identifier tail:spanned_path_tail* _nl:same_line fields:pattern_fields? -> {
+                match (tail.is_empty(), fields) {
+                    (true, None) if head == "_" => Pattern::Wildcard,
+                    (true, None) => Pattern::Binding(head),
+                    (_, fields) => {
+                        let mut path = vec![head];
+                        path.extend(tail);
+                        Pattern::Variant { path, fields: fields.unwrap_or(PatternFields::Unit) }
+                    }
+                }
+            }
+
+        rule pattern_fields -> PatternFields =
+            _open:punct_open_paren items:pattern_list? _close:punct_close_paren -> {
+                PatternFields::Tuple(items.unwrap_or_default())
+            }
+          | _open:punct_open_brace fields:field_patterns? _close:punct_close_brace -> {
+                PatternFields::Struct(fields.unwrap_or_default())
+            }
+
+        rule pattern_list -> Vec<Pattern> =
+            head:pattern tail:pattern_list_tail* _comma:punct_comma? -> {
+                let mut items = vec![head];
+                items.extend(tail);
+                items
+            }
+
+        rule pattern_list_tail -> Pattern =
+            _comma:punct_comma p:pattern -> { p }
+
+        rule field_patterns -> Vec<FieldPattern> =
+            head:field_pattern tail:field_pattern_tail* _comma:punct_comma? -> {
+                let mut fields = vec![head];
+                fields.extend(tail);
+                fields
+            }
+
+        rule field_pattern_tail -> FieldPattern =
+            _comma:punct_comma f:field_pattern -> { f }
+
+        // Move { x, y: 0 }
+        rule field_pattern -> FieldPattern =
+            name:identifier _colon:punct_colon pattern:pattern -> {
+                FieldPattern { name, pattern }
+            }
+          | name:identifier -> {
+                FieldPattern { pattern: Pattern::Binding(name.clone()), name }
+            }
+
+        rule list_lit -> ExprKind =
+            _open:punct_open_bracket items:call_args? _comma:punct_comma? _close:punct_close_bracket -> {
+                ExprKind::List(items.unwrap_or_default())
+            }
+
+        // Kap 4.2: User(username: name, email: email), users::User(name: name).
+        // Named fields without a `;` set this apart from a call.
+        rule struct_lit -> Expr =
+            head:identifier tail:spanned_path_tail* fields:field_init_list @ fields_span -> {
+                let span = head.span.start..fields_span.end;
+                let mut path = vec![head];
+                path.extend(tail);
+                Expr::new(ExprKind::StructLit { path, fields, variant: false }, span)
+            }
+
+        rule field_init_list -> Vec<FieldInit> =
+            _open:punct_open_paren fields:field_inits _close:punct_close_paren -> { fields }
+
+        rule field_inits -> Vec<FieldInit> =
+            head:field_init tail:field_init_tail* _comma:punct_comma? -> {
+                let mut fields = vec![head];
+                fields.extend(tail);
+                fields
+            }
+
+        rule field_init_tail -> FieldInit =
+            _comma:punct_comma f:field_init -> { f }
+
+        rule field_init -> FieldInit =
+            name:identifier _colon:punct_colon value:expr -> {
+                FieldInit { name, value }
+            }
+
+        // Message::Write("hi"), HashMap::new()
+        rule path_call_expr -> Expr =
+            func:path_expr @ func_span call:call_tail @ call_span -> {
+                Expr::new(
+                    ExprKind::Call {
+                        func: Box::new(Expr::new(func, func_span.clone())),
+                        args: call.0,
+                        config: call.1,
+                    },
+                    func_span.start..call_span.end,
+                )
+            }
+
+        rule path_expr -> ExprKind =
+            head:identifier first:spanned_path_tail tail:spanned_path_tail* -> {
+                let mut path = vec![head, first];
+                path.extend(tail);
+                ExprKind::Path(path)
+            }
+
+        rule path_tail -> String =
+            _sep:punct_path_sep n:identifier -> { n.to_string() }
+
+        rule spanned_path_tail -> Ident =
+            _sep:punct_path_sep n:identifier -> { n }
+
+        // The span of a call is the callee's name, where a propagated error's
+        // trace points to.
+        rule call_expr -> Expr =
+            func:identifier call:call_tail @ call_span -> {
+                let span = func.span.start..call_span.end;
+                let callee = func.span.clone();
+                Expr::new(
+                    ExprKind::Call {
+                        func: Box::new(Expr::new(ExprKind::Variable(func), callee)),
+                        args: call.0,
+                        config: call.1,
+                    },
+                    span,
+                )
+            }
+
+        // Kap 5.2: a lambda after the parentheses is the last argument, and
+        // without other arguments the parentheses can go: users.map fn: a.id
+        rule call_tail -> (Vec<Expr>, Vec<ConfigArg>) =
+            _open:punct_open_paren args:call_args? config:config_args? _close:punct_close_paren trailing:trailing_lambda? -> {
+                let mut args = args.unwrap_or_default();
+                args.extend(trailing);
+                (args, config.unwrap_or_default())
+            }
+          | l:trailing_lambda -> { (vec![l], Vec::new()) }
+
+        rule trailing_lambda -> Expr =
+            _nl:same_line _sp:skip_ws l:lambda_expr @ span -> { Expr::new(l, span) }
+
+        // Kap 5.4: request(url; timeout: 10, method: "POST")
+        rule config_args -> Vec<ConfigArg> =
+            _semi:punct_semi head:config_arg tail:config_arg_tail* -> {
+                let mut args = vec![head];
+                args.extend(tail);
+                args
+            }
+
+        rule config_arg_tail -> ConfigArg =
+            _comma:punct_comma arg:config_arg -> { arg }
+
+        // Positional arguments are accepted here so the checker can report them
+        rule config_arg -> ConfigArg =
+            name:identifier _colon:punct_colon value:expr -> {
+                ConfigArg { name: Some(name), value: Some(value) }
+            }
+          | value:expr -> { ConfigArg { name: None, value: Some(value) } }
 
         rule call_args -> Vec<Expr> =
             head:expr tail:call_args_tail* -> {
@@ -209,92 +1126,1077 @@ grammar! {
             }
 
         rule call_args_tail -> Expr =
-            _sp:skip_ws "," _sp2:skip_ws e:expr -> { e }
+            _comma:punct_comma e:expr -> { e }
 
-        rule str_lit -> Expr =
-            s:string -> {
-                Expr::LitStr(s)
+        rule str_lit -> ExprKind =
+            s:str_literal -> {
+                ExprKind::LitStr(s)
             }
 
-        rule int_lit -> Expr =
-            d:digits -> {
-                Expr::LitInt(d.parse().unwrap())
+        rule int_lit -> ExprKind =
+            i:int_literal -> {
+                ExprKind::LitInt(i)
             }
 
-        rule var_expr -> Expr =
-            n:ident -> { Expr::Variable(Ident::new(&n, Span::call_site())) }
+        rule bool_lit -> ExprKind =
+            _true:kw_true -> { ExprKind::LitBool(true) }
+          | _false:kw_false -> { ExprKind::LitBool(false) }
+
+        rule null_lit -> ExprKind =
+            _null:kw_null -> { ExprKind::LitNull }
 
-        rule digits -> String =
-            d:digit1 -> { d.to_string() }
+        // `self` is a keyword, but in a method body it names the receiver
+        rule var_expr -> ExprKind =
+            n:identifier -> { ExprKind::Variable(n) }
+          | s:kw_self -> { ExprKind::Variable(Ident::new("self", s)) }
     }
 }
 
+/// Precedence climbing over a flat `head (op operand)*` chain. Operators of
+/// equal precedence associate to the left, except `??`.
+fn climb_binary(head: ast::Expr, tail: Vec<(ast::BinOp, ast::Expr)>) -> ast::Expr {
+    let mut rest = tail.into_iter().peekable();
+    climb(head, &mut rest, 0)
+}
+
+fn climb(
+    mut lhs: ast::Expr,
+    rest: &mut Peekable<impl Iterator<Item = (ast::BinOp, ast::Expr)>>,
+    min_precedence: u8,
+) -> ast::Expr {
+    while let Some(op) = rest
+        .peek()
+        .map(|(op, _)| *op)
+        .filter(|op| op.precedence() >= min_precedence)
+    {
+        let Some((_, rhs)) = rest.next() else { break };
+        // Everything binding tighter than `op` belongs to its right operand
+        let next_min = if op.is_right_assoc() {
+            op.precedence()
+        } else {
+            op.precedence() + 1
+        };
+        let rhs = climb(rhs, rest, next_min);
+        let span = lhs.span.start..rhs.span.end;
+        let kind = ast::ExprKind::Binary {
+            op,
+            left: Box::new(lhs),
+            right: Box::new(rhs),
+        };
+        lhs = ast::Expr::new(kind, span);
+    }
+    lhs
+}
+
+/// The item rules leave their span empty; `item` knows it once they matched.
+fn item_at(mut item: ast::Item, at: Range<usize>) -> ast::Item {
+    match &mut item {
+        ast::Item::Fn { span, .. }
+        | ast::Item::Struct { span, .. }
+        | ast::Item::Enum { span, .. }
+        | ast::Item::Impl { span, .. }
+        | ast::Item::Trait { span, .. }
+        | ast::Item::Test { span, .. }
+        | ast::Item::Bench { span, .. }
+        | ast::Item::Grammar { span, .. }
+        | ast::Item::Import { span, .. }
+        | ast::Item::Error { span, .. } => *span = at,
+        ast::Item::Module { .. } => {}
+    }
+    item
+}
+
+/// A block used as an expression spans the same bytes as the block.
+fn block_expr(block: ast::Block) -> ast::Expr {
+    let span = block.span.clone();
+    ast::Expr::new(ast::ExprKind::Block(block), span)
+}
+
 // --- Lowering (AST -> Bridge) ---
 
-fn lower_program(prog: ast::Program) -> Result<BridgeModule> {
-    let mut items = Vec::new();
-    for item in prog.items {
-        if let Some(bridge_item) = lower_item(item)? {
-            items.push(bridge_item);
+fn lower_program(prog: ast::Program, source_map: ast::SourceMap) -> Result<BridgeModule> {
+    let modules: Vec<String> = prog
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            ast::Item::Module { name, .. } => Some(name.to_string()),
+            _ => None,
+        })
+        .collect();
+    lower_module("main", prog.items, source_map, &modules, true)
+}
+
+/// Kap 9.1: the program and each imported file become nested Bridge modules,
+/// each with its own source map and the error enums of its own signatures.
+/// `modules` are the top-level modules, which imports name from the crate root.
+fn lower_module(
+    name: &str,
+    items: Vec<ast::Item>,
+    source_map: ast::SourceMap,
+    modules: &[String],
+    is_root: bool,
+) -> Result<BridgeModule> {
+    let error_enums = collect_error_enums(&items);
+    let private_types: Vec<String> = items
+        .iter()
+        .filter_map(|item| match item {
+            ast::Item::Struct {
+                name,
+                is_pub: false,
+                ..
+            }
+            | ast::Item::Enum {
+                name,
+                is_pub: false,
+                ..
+            } => Some(name.to_string()),
+            _ => None,
+        })
+        .collect();
+    let mut bridge_items = Vec::new();
+    for item in items {
+        match item {
+            ast::Item::Module {
+                name: module,
+                items,
+                source,
+            } => bridge_items.push(BridgeItem::Module(lower_module(
+                &module.to_string(),
+                items,
+                source,
+                modules,
+                false,
+            )?)),
+            ast::Item::Import { path, span } => {
+                bridge_items.extend(lower_import(&path, span, modules, is_root));
+            }
+            item => bridge_items.extend(lower_item(item)?),
         }
     }
+    bridge_items.extend(lower_error_enums(&error_enums, &private_types));
 
     Ok(BridgeModule {
-        name: "main".to_string(),
-        items,
+        name: name.to_string(),
+        source_map: BridgeSourceMap {
+            file: source_map.file,
+            line_starts: source_map.line_starts,
+            multibyte_chars: source_map.multibyte_chars,
+        },
+        items: bridge_items,
     })
 }
 
+/// Kap 9.2: imports of the project's modules are crate-relative, anything
+/// else (`use std::collections::HashMap`) is left to Rust. The crate root
+/// already sees its child modules, so `use users` there needs no `use`.
+fn lower_import(
+    path: &str,
+    span: Range<usize>,
+    modules: &[String],
+    in_root: bool,
+) -> Option<BridgeItem> {
+    let first = path.split("::").next().unwrap_or_default();
+    let path = if !modules.iter().any(|module| module == first) {
+        path.to_string()
+    } else if in_root && first == path {
+        return None;
+    } else {
+        format!("crate::{}", path)
+    };
+    Some(BridgeItem::Use(BridgeUse { path, span }))
+}
+
 fn lower_item(item: ast::Item) -> Result<Option<BridgeItem>> {
     match item {
-        ast::Item::Fn { name, body, .. } => Ok(Some(BridgeItem::Function(BridgeFunction {
+        ast::Item::Fn { .. } => Ok(Some(BridgeItem::Function(lower_fn(item)?))),
+        ast::Item::Struct {
+            name,
+            is_pub,
+            generics,
+            fields,
+            docs,
+            span,
+        } => Ok(Some(BridgeItem::Struct(BridgeStruct {
             name: name.to_string(),
-            args: vec![],
-            ret_type: None,
-            body: lower_block(body)?,
-            span: 0..0,
+            is_pub,
+            generics: lower_generics(&generics),
+            fields: fields.iter().map(lower_field).collect(),
+            docs,
+            span,
         }))),
+        ast::Item::Enum {
+            name,
+            is_pub,
+            generics,
+            variants,
+            docs,
+            span,
+        } => Ok(Some(BridgeItem::Enum(BridgeEnum {
+            name: name.to_string(),
+            is_pub,
+            generics: lower_generics(&generics),
+            variants: variants.iter().map(lower_variant).collect(),
+            docs,
+            span,
+        }))),
+        ast::Item::Impl {
+            generics,
+            trait_ref,
+            target,
+            methods,
+            docs,
+            span,
+        } => {
+            let mut bridge_methods = Vec::new();
+            for method in methods {
+                bridge_methods.push(lower_fn(method)?);
+            }
+            Ok(Some(BridgeItem::Impl(BridgeImpl {
+                generics: lower_generics(&generics),
+                trait_name: trait_ref.as_ref().map(lower_type),
+                target: lower_type(&target),
+                methods: bridge_methods,
+                docs,
+                span,
+            })))
+        }
+        ast::Item::Trait {
+            name,
+            is_pub,
+            generics,
+            methods,
+            docs,
+            span,
+        } => {
+            let mut bridge_methods = Vec::new();
+            for method in methods {
+                let mut args = lower_args(&method.args);
+                args.extend(lower_config_args(&method.config));
+                let default = match method.default {
+                    Some(body) => Some(lower_fn_body(&method.config, body)?),
+                    None => None,
+                };
+                bridge_methods.push(BridgeTraitMethod {
+                    name: method.name.to_string(),
+                    receiver: method.receiver.map(lower_receiver),
+                    args,
+                    ret_type: method.ret_type.as_ref().map(lower_type),
+                    throws: error_type(&method.throws),
+                    default,
+                    docs: method.docs,
+                    span: method.span,
+                });
+            }
+            Ok(Some(BridgeItem::Trait(BridgeTrait {
+                name: name.to_string(),
+                is_pub,
+                generics: lower_generics(&generics),
+                methods: bridge_methods,
+                docs,
+                span,
+            })))
+        }
         _ => Ok(None),
     }
 }
 
+fn lower_fn(item: ast::Item) -> Result<BridgeFunction> {
+    let ast::Item::Fn {
+        name,
+        is_pub,
+        generics,
+        receiver,
+        args,
+        config,
+        throws,
+        ret_type,
+        body,
+        docs,
+        span,
+        ..
+    } = item
+    else {
+        return Err(anyhow::anyhow!("Expected a function, found {:?}", item));
+    };
+
+    let mut bridge_args = lower_args(&args);
+    bridge_args.extend(lower_config_args(&config));
+    Ok(BridgeFunction {
+        name: name.to_string(),
+        is_pub,
+        generics: lower_generics(&generics),
+        receiver: receiver.map(lower_receiver),
+        args: bridge_args,
+        ret_type: ret_type.as_ref().map(lower_type),
+        throws: error_type(&throws),
+        body: lower_fn_body(&config, body)?,
+        docs,
+        span,
+    })
+}
+
+// Error handling (Kap 7.1): a function that `throws` carries its error type
+// in the Bridge IR; the executor turns it into a `Result` whose error records
+// where it was thrown and which calls it passed. With one error type that type
+// is the error; several get a generated enum named after them, with a variant
+// and a `From` impl per type:
+//
+//     enum IoErrorOrNetworkError {
+//         IoError(IoError),
+//         NetworkError(NetworkError),
+//     }
+//
+// Calls whose errors propagate (marked by the checker) become `Try` and
+// `throw value` becomes `Throw`; both convert through those impls.
+fn error_type(throws: &[ast::Type]) -> Option<String> {
+    match throws {
+        [] => None,
+        [single] => Some(lower_type(single)),
+        _ => Some(error_enum_name(throws)),
+    }
+}
+
+fn error_enum_name(throws: &[ast::Type]) -> String {
+    throws
+        .iter()
+        .map(|ty| ty.name.to_string())
+        .collect::<Vec<_>>()
+        .join("Or")
+}
+
+/// The error enums of all multi-error `throws` clauses, once per name.
+fn collect_error_enums(items: &[ast::Item]) -> Vec<(String, Vec<ast::Type>)> {
+    let mut clauses = Vec::new();
+    for item in items {
+        match item {
+            ast::Item::Fn { throws, .. } => clauses.push(throws),
+            ast::Item::Impl { methods, .. } => {
+                for method in methods {
+                    if let ast::Item::Fn { throws, .. } = method {
+                        clauses.push(throws);
+                    }
+                }
+            }
+            ast::Item::Trait { methods, .. } => {
+                clauses.extend(methods.iter().map(|method| &method.throws));
+            }
+            _ => {}
+        }
+    }
+
+    let mut enums: Vec<(String, Vec<ast::Type>)> = Vec::new();
+    for throws in clauses.into_iter().filter(|throws| throws.len() > 1) {
+        let name = error_enum_name(throws);
+        if !enums.iter().any(|(known, _)| *known == name) {
+            enums.push((name, throws.clone()));
+        }
+    }
+    enums
+}
+
+/// Each error enum with `From` impls for its error types and for every other
+/// error enum whose types it covers, so errors propagate into wider clauses.
+/// `private_types` are the module's types that are not `pub`.
+fn lower_error_enums(
+    enums: &[(String, Vec<ast::Type>)],
+    private_types: &[String],
+) -> Vec<BridgeItem> {
+    let mut items = Vec::new();
+    for (name, throws) in enums {
+        // No more visible than the least visible error type it wraps
+        let is_pub = !throws
+            .iter()
+            .any(|ty| private_types.iter().any(|private| ty.name == *private));
+        items.push(BridgeItem::Enum(BridgeEnum {
+            name: name.clone(),
+            is_pub,
+            generics: Vec::new(),
+            variants: throws
+                .iter()
+                .map(|ty| BridgeVariant {
+                    name: ty.name.to_string(),
+                    data: BridgeVariantData::Tuple(vec![lower_type(ty)]),
+                    docs: Vec::new(),
+                    span: 0..0,
+                })
+                .collect(),
+            docs: Vec::new(),
+            span: 0..0,
+        }));
+
+        for ty in throws {
+            let variant = format!("{}::{}", name, ty.name);
+            let body = call_path(&variant, vec![BridgeExpr::Variable("error".to_string())]);
+            items.push(from_impl(name, &lower_type(ty), body));
+        }
+
+        let types: Vec<String> = throws.iter().map(lower_type).collect();
+        for (other, other_throws) in enums.iter().filter(|(other, _)| other != name) {
+            if !other_throws
+                .iter()
+                .all(|ty| types.contains(&lower_type(ty)))
+            {
+                continue;
+            }
+            let arms = other_throws
+                .iter()
+                .map(|ty| BridgeArm {
+                    pattern: BridgePattern::TupleVariant(
+                        format!("{}::{}", other, ty.name),
+                        vec![BridgePattern::Binding("error".to_string())],
+                    ),
+                    guard: None,
+                    body: call_path(
+                        &format!("{}::{}", name, ty.name),
+                        vec![BridgeExpr::Variable("error".to_string())],
+                    ),
+                    span: 0..0,
+                })
+                .collect();
+            let body = BridgeExpr::Match(BridgeMatch {
+                expr: Box::new(BridgeExpr::Variable("error".to_string())),
+                arms,
+                span: 0..0,
+            });
+            items.push(from_impl(name, other, body));
+        }
+    }
+    items
+}
+
+/// `impl From<source> for target { fn from(error: source) -> Self { body } }`
+fn from_impl(target: &str, source: &str, body: BridgeExpr) -> BridgeItem {
+    BridgeItem::Impl(BridgeImpl {
+        generics: Vec::new(),
+        trait_name: Some(format!("From<{}>", source)),
+        target: target.to_string(),
+        methods: vec![BridgeFunction {
+            name: "from".to_string(),
+            is_pub: false,
+            generics: Vec::new(),
+            receiver: None,
+            args: vec![BridgeArg {
+                name: "error".to_string(),
+                ty: source.to_string(),
+                span: 0..0,
+            }],
+            ret_type: Some("Self".to_string()),
+            throws: None,
+            body: BridgeBlock {
+                stmts: vec![BridgeStmt::Expr(body)],
+                span: 0..0,
+            },
+            docs: Vec::new(),
+            span: 0..0,
+        }],
+        docs: Vec::new(),
+        span: 0..0,
+    })
+}
+
+/// `fetch_config() catch { ... }` becomes
+/// `match fetch_config() { Ok(value) => value, Err(error) => { ... } }`.
+/// The `Ok` arm spans the guarded expression, the `Err` arm the handler.
+fn lower_catch(expr: ast::Expr, handler: ast::Block, span: Range<usize>) -> Result<BridgeExpr> {
+    let arm = |variant: &str, binding: &str, body: BridgeExpr, span| BridgeArm {
+        pattern: BridgePattern::TupleVariant(
+            variant.to_string(),
+            vec![BridgePattern::Binding(binding.to_string())],
+        ),
+        guard: None,
+        body,
+        span,
+    };
+    let expr_span = expr.span.clone();
+    let handler_span = handler.span.clone();
+    Ok(BridgeExpr::Match(BridgeMatch {
+        expr: Box::new(lower_expr(expr)?),
+        arms: vec![
+            arm(
+                "Ok",
+                "value",
+                BridgeExpr::Variable("value".to_string()),
+                expr_span,
+            ),
+            arm(
+                "Err",
+                "error",
+                BridgeExpr::Block(lower_block(handler)?),
+                handler_span,
+            ),
+        ],
+        span,
+    }))
+}
+
+fn call_path(path: &str, args: Vec<BridgeExpr>) -> BridgeExpr {
+    BridgeExpr::Call(BridgeCall {
+        func: Box::new(BridgeExpr::Path(path.to_string())),
+        args,
+        span: 0..0,
+    })
+}
+
+// Calling convention for config parameters (Kap 5.4): each one is a trailing
+// `Option<T>` parameter, and the function resolves its own defaults, so call
+// sites only pass `Some(value)` or `None`:
+//
+//     fn request(url: String, timeout: Option<i32>) -> ... {
+//         let timeout = timeout.unwrap_or_else(|| 30);
+//         ...
+//     }
+fn lower_config_args(config: &[ast::FnArg]) -> Vec<BridgeArg> {
+    config
+        .iter()
+        .map(|arg| BridgeArg {
+            name: arg.name.to_string(),
+            ty: format!("Option<{}>", lower_type(&arg.ty)),
+            span: arg_span(arg),
+        })
+        .collect()
+}
+
+fn lower_fn_body(config: &[ast::FnArg], body: ast::Block) -> Result<BridgeBlock> {
+    let mut block = lower_block(body)?;
+    let mut prologue = Vec::new();
+    for arg in config {
+        let Some(default) = arg.default.clone() else {
+            continue;
+        };
+        let name = arg.name.to_string();
+        let span = default.span.clone();
+        let mut default = lower_expr(default)?;
+        if lower_type(&arg.ty) == "String" {
+            default = owned_literal(default);
+        }
+        prologue.push(BridgeStmt::Let(BridgeLetStmt {
+            name: name.clone(),
+            mutable: false,
+            ty: None,
+            init: Some(BridgeExpr::MethodCall(BridgeMethodCall {
+                receiver: Box::new(BridgeExpr::Variable(name)),
+                method: "unwrap_or_else".to_string(),
+                args: vec![BridgeExpr::Closure(BridgeClosure {
+                    params: Vec::new(),
+                    body: Box::new(default),
+                    span: span.clone(),
+                })],
+                span: span.clone(),
+            })),
+            span,
+        }));
+    }
+    prologue.append(&mut block.stmts);
+    block.stmts = prologue;
+    Ok(block)
+}
+
+/// Config arguments as ordered by the checker: `Some(value)` where the call
+/// sets one, `None` where the parameter keeps its default. The call site
+/// doesn't know the parameter types, so string literals are passed as `String`.
+fn lower_config_values(config: Vec<ast::ConfigArg>) -> Result<Vec<BridgeExpr>> {
+    let mut values = Vec::new();
+    for arg in config {
+        values.push(match arg.value {
+            Some(value) => BridgeExpr::Call(BridgeCall {
+                span: value.span.clone(),
+                func: Box::new(BridgeExpr::Path("Some".to_string())),
+                args: vec![owned_literal(lower_expr(value)?)],
+            }),
+            None => BridgeExpr::Path("None".to_string()),
+        });
+    }
+    Ok(values)
+}
+
+fn owned_literal(expr: BridgeExpr) -> BridgeExpr {
+    match expr {
+        BridgeExpr::Literal(BridgeLiteral::String(_)) => BridgeExpr::MethodCall(BridgeMethodCall {
+            receiver: Box::new(expr),
+            method: "to_string".to_string(),
+            args: Vec::new(),
+            span: 0..0,
+        }),
+        other => other,
+    }
+}
+
+fn lower_receiver(receiver: ast::Receiver) -> BridgeReceiver {
+    match receiver {
+        ast::Receiver::Value => BridgeReceiver::Value,
+        ast::Receiver::Ref => BridgeReceiver::Ref,
+        ast::Receiver::RefMut => BridgeReceiver::RefMut,
+    }
+}
+
+fn lower_args(args: &[ast::FnArg]) -> Vec<BridgeArg> {
+    args.iter()
+        .map(|arg| BridgeArg {
+            name: arg.name.to_string(),
+            ty: lower_type(&arg.ty),
+            span: arg_span(arg),
+        })
+        .collect()
+}
+
+/// From the parameter's name to its type, or to its default value if it has one.
+fn arg_span(arg: &ast::FnArg) -> Range<usize> {
+    let end = match &arg.default {
+        Some(default) => default.span.end,
+        None => arg.ty.span.end,
+    };
+    arg.name.span.start..end
+}
+
+fn lower_generics(generics: &[ast::GenericParam]) -> Vec<BridgeGenericParam> {
+    generics
+        .iter()
+        .map(|param| BridgeGenericParam {
+            name: param.name.to_string(),
+            bounds: param.bounds.iter().map(lower_type).collect(),
+            span: param.name.span.start
+                ..param
+                    .bounds
+                    .last()
+                    .map_or(param.name.span.end, |bound| bound.span.end),
+        })
+        .collect()
+}
+
+fn lower_field(field: &ast::FieldDef) -> BridgeField {
+    BridgeField {
+        name: field.name.to_string(),
+        is_pub: field.is_pub,
+        ty: lower_type(&field.ty),
+        docs: field.docs.clone(),
+        span: field.name.span.start..field.ty.span.end,
+    }
+}
+
+fn lower_variant(variant: &ast::EnumVariant) -> BridgeVariant {
+    let data = match &variant.data {
+        ast::VariantData::Unit => BridgeVariantData::Unit,
+        ast::VariantData::Tuple(types) => {
+            BridgeVariantData::Tuple(types.iter().map(lower_type).collect())
+        }
+        ast::VariantData::Struct(fields) => {
+            BridgeVariantData::Struct(fields.iter().map(lower_field).collect())
+        }
+    };
+    BridgeVariant {
+        name: variant.name.to_string(),
+        data,
+        docs: variant.docs.clone(),
+        span: variant.span.clone(),
+    }
+}
+
+/// Renders a Nikaia type as the Rust type string carried by the Bridge IR
+/// (`Box[T]` becomes `Box<T>`, `String?` becomes `Option<String>`).
+fn lower_type(ty: &ast::Type) -> String {
+    let name = if ty.generics.is_empty() {
+        ty.name.to_string()
+    } else {
+        let args: Vec<String> = ty.generics.iter().map(lower_type).collect();
+        format!("{}<{}>", ty.name, args.join(", "))
+    };
+    if ty.nullable {
+        format!("Option<{}>", name)
+    } else {
+        name
+    }
+}
+
 fn lower_block(block: ast::Block) -> Result<BridgeBlock> {
     let mut stmts = Vec::new();
     for stmt in block.stmts {
         stmts.push(lower_stmt(stmt)?);
     }
-    Ok(BridgeBlock { stmts, span: 0..0 })
+    Ok(BridgeBlock {
+        stmts,
+        span: block.span,
+    })
 }
 
 fn lower_stmt(stmt: ast::Stmt) -> Result<BridgeStmt> {
     match stmt {
-        ast::Stmt::Let { name, value, .. } => Ok(BridgeStmt::Let(BridgeLetStmt {
+        ast::Stmt::Let {
+            name,
+            mutable,
+            ty,
+            value,
+            span,
+        } => Ok(BridgeStmt::Let(BridgeLetStmt {
             name: name.to_string(),
-            ty: None,
+            mutable,
+            ty: ty.as_ref().map(lower_type),
             init: Some(lower_expr(value)?),
-            span: 0..0,
+            span,
+        })),
+        ast::Stmt::Assign {
+            target,
+            value,
+            span,
+        } => Ok(BridgeStmt::Assign(BridgeAssign {
+            target: lower_place(target)?,
+            value: lower_expr(value)?,
+            span,
+        })),
+        ast::Stmt::CompoundAssign {
+            target,
+            op,
+            value,
+            span,
+        } => Ok(BridgeStmt::CompoundAssign(BridgeCompoundAssign {
+            op: lower_bin_op(op),
+            target: lower_place(target)?,
+            value: lower_expr(value)?,
+            span,
         })),
         ast::Stmt::Expr(expr) => Ok(BridgeStmt::Expr(lower_expr(expr)?)),
-        _ => Err(anyhow::anyhow!("Unsupported statement type")),
     }
 }
 
 fn lower_expr(expr: ast::Expr) -> Result<BridgeExpr> {
-    match expr {
-        ast::Expr::LitInt(i) => Ok(BridgeExpr::Literal(BridgeLiteral::Int(i))),
-        ast::Expr::LitStr(s) => Ok(BridgeExpr::Literal(BridgeLiteral::String(s))),
-        ast::Expr::Variable(id) => Ok(BridgeExpr::Variable(id.to_string())),
-        ast::Expr::Call { func, args } => {
+    let ast::Expr { kind, span } = expr;
+    match kind {
+        ast::ExprKind::LitInt(i) => Ok(BridgeExpr::Literal(BridgeLiteral::Int(i))),
+        ast::ExprKind::LitStr(s) => Ok(BridgeExpr::Literal(BridgeLiteral::String(s))),
+        ast::ExprKind::LitBool(b) => Ok(BridgeExpr::Literal(BridgeLiteral::Bool(b))),
+        ast::ExprKind::LitNull => Ok(BridgeExpr::Literal(BridgeLiteral::Null)),
+        ast::ExprKind::Variable(id) => Ok(BridgeExpr::Variable(id.to_string())),
+        ast::ExprKind::Path(path) => Ok(BridgeExpr::Path(lower_path(&path))),
+        // Calls keep the span of the callee's name, where traces point to
+        ast::ExprKind::Call { func, args, config } => {
             let mut bridge_args = Vec::new();
             for arg in args {
                 bridge_args.push(lower_expr(arg)?);
             }
+            bridge_args.extend(lower_config_values(config)?);
             Ok(BridgeExpr::Call(BridgeCall {
+                span: func.span.clone(),
                 func: Box::new(lower_expr(*func)?),
                 args: bridge_args,
-                span: 0..0,
             }))
         }
-        _ => Err(anyhow::anyhow!("Unsupported expression type: {:?}", expr)),
+        ast::ExprKind::MethodCall {
+            receiver,
+            method,
+            args,
+            config,
+        } => {
+            let mut bridge_args = Vec::new();
+            for arg in args {
+                bridge_args.push(lower_expr(arg)?);
+            }
+            bridge_args.extend(lower_config_values(config)?);
+            Ok(BridgeExpr::MethodCall(BridgeMethodCall {
+                receiver: Box::new(lower_expr(*receiver)?),
+                method: method.to_string(),
+                args: bridge_args,
+                span: method.span,
+            }))
+        }
+        ast::ExprKind::StructLit {
+            path,
+            fields,
+            variant,
+        } => {
+            let mut bridge_fields = Vec::new();
+            for field in fields {
+                bridge_fields.push(BridgeFieldInit {
+                    name: field.name.to_string(),
+                    span: field.name.span.start..field.value.span.end,
+                    value: lower_expr(field.value)?,
+                });
+            }
+            let lit = BridgeStructLit {
+                name: lower_path(&path),
+                fields: bridge_fields,
+                span,
+            };
+            Ok(if variant {
+                BridgeExpr::StructVariant(lit)
+            } else {
+                BridgeExpr::StructLit(lit)
+            })
+        }
+        ast::ExprKind::Index { expr, index } => Ok(BridgeExpr::Index(BridgeIndex {
+            expr: Box::new(lower_expr(*expr)?),
+            index: Box::new(lower_expr(*index)?),
+            span,
+        })),
+        ast::ExprKind::Field { expr, field } => Ok(BridgeExpr::Field(BridgeFieldAccess {
+            expr: Box::new(lower_expr(*expr)?),
+            field: field.to_string(),
+            span,
+        })),
+        ast::ExprKind::SafeField {
+            expr,
+            field,
+            nullable,
+        } => Ok(BridgeExpr::SafeNav(BridgeSafeNav {
+            expr: Box::new(lower_expr(*expr)?),
+            access: BridgeSafeAccess::Field(field.to_string()),
+            nullable,
+            span,
+        })),
+        ast::ExprKind::SafeMethodCall {
+            receiver,
+            method,
+            args,
+            config,
+            nullable,
+        } => {
+            let mut bridge_args = Vec::new();
+            for arg in args {
+                bridge_args.push(lower_expr(arg)?);
+            }
+            bridge_args.extend(lower_config_values(config)?);
+            Ok(BridgeExpr::SafeNav(BridgeSafeNav {
+                expr: Box::new(lower_expr(*receiver)?),
+                access: BridgeSafeAccess::MethodCall(method.to_string(), bridge_args),
+                nullable,
+                span: method.span,
+            }))
+        }
+        ast::ExprKind::If {
+            cond,
+            then_branch,
+            else_branch,
+        } => Ok(BridgeExpr::If(lower_if(
+            *cond,
+            then_branch,
+            else_branch,
+            span,
+        )?)),
+        ast::ExprKind::Block(block) => Ok(BridgeExpr::Block(lower_block(block)?)),
+        ast::ExprKind::While { cond, body } => Ok(BridgeExpr::While(BridgeWhile {
+            cond: Box::new(lower_expr(*cond)?),
+            body: lower_block(body)?,
+            span,
+        })),
+        ast::ExprKind::Loop { body } => Ok(BridgeExpr::Loop(BridgeLoop {
+            body: lower_block(body)?,
+            span,
+        })),
+        ast::ExprKind::For { var, iter, body } => Ok(BridgeExpr::For(BridgeFor {
+            var: var.to_string(),
+            iter: Box::new(lower_expr(*iter)?),
+            body: lower_block(body)?,
+            span,
+        })),
+        ast::ExprKind::Range {
+            start,
+            end,
+            inclusive,
+        } => Ok(BridgeExpr::Range(BridgeRange {
+            start: Box::new(lower_expr(*start)?),
+            end: Box::new(lower_expr(*end)?),
+            inclusive,
+            span,
+        })),
+        ast::ExprKind::List(items) => {
+            let mut bridge_items = Vec::new();
+            for item in items {
+                bridge_items.push(lower_expr(item)?);
+            }
+            Ok(BridgeExpr::List(BridgeList {
+                items: bridge_items,
+                span,
+            }))
+        }
+        ast::ExprKind::Break(value) => Ok(BridgeExpr::Break(BridgeBreak {
+            value: value.map(|v| lower_expr(*v)).transpose()?.map(Box::new),
+            span,
+        })),
+        ast::ExprKind::Continue => Ok(BridgeExpr::Continue),
+        ast::ExprKind::Binary { op, left, right } => Ok(BridgeExpr::Binary(BridgeBinary {
+            op: lower_bin_op(op),
+            left: Box::new(lower_expr(*left)?),
+            right: Box::new(lower_expr(*right)?),
+            span,
+        })),
+        ast::ExprKind::Match { expr, arms } => {
+            let mut bridge_arms = Vec::new();
+            for arm in arms {
+                bridge_arms.push(BridgeArm {
+                    pattern: lower_pattern(&arm.pattern),
+                    guard: arm.guard.map(lower_expr).transpose()?,
+                    body: lower_expr(arm.body)?,
+                    span: arm.span,
+                });
+            }
+            Ok(BridgeExpr::Match(BridgeMatch {
+                expr: Box::new(lower_expr(*expr)?),
+                arms: bridge_arms,
+                span,
+            }))
+        }
+        ast::ExprKind::Lambda { params, body, .. } => Ok(BridgeExpr::Closure(BridgeClosure {
+            params: params.iter().map(|p| p.to_string()).collect(),
+            body: Box::new(lower_expr(*body)?),
+            span,
+        })),
+        ast::ExprKind::Spawn { body, is_move } => {
+            let body = match *body {
+                ast::Expr {
+                    kind: ast::ExprKind::Block(block),
+                    ..
+                } => lower_block(block)?,
+                expr => BridgeBlock {
+                    span: expr.span.clone(),
+                    stmts: vec![BridgeStmt::Expr(lower_expr(expr)?)],
+                },
+            };
+            Ok(BridgeExpr::Spawn(BridgeSpawn {
+                body,
+                is_move,
+                span,
+            }))
+        }
+        ast::ExprKind::Unary { op, expr } => Ok(BridgeExpr::Unary(BridgeUnary {
+            op: match op {
+                ast::UnOp::Neg => BridgeUnOp::Neg,
+                ast::UnOp::Not => BridgeUnOp::Not,
+            },
+            expr: Box::new(lower_expr(*expr)?),
+            span,
+        })),
+        ast::ExprKind::TryCatch { expr, handler } => lower_catch(*expr, handler, span),
+        ast::ExprKind::Propagate(call) => {
+            let call = lower_expr(*call)?;
+            let span = match &call {
+                BridgeExpr::Call(call) => call.span.clone(),
+                BridgeExpr::MethodCall(call) => call.span.clone(),
+                _ => span,
+            };
+            Ok(BridgeExpr::Try(BridgeTry {
+                expr: Box::new(call),
+                span,
+            }))
+        }
+        // let name: String? = "Ada" holds Some("Ada")
+        ast::ExprKind::Nullable(value) => Ok(BridgeExpr::Call(BridgeCall {
+            func: Box::new(BridgeExpr::Path("Some".to_string())),
+            args: vec![owned_literal(lower_expr(*value)?)],
+            span,
+        })),
+        // The span of the thrown value is the first frame of the error's trace
+        ast::ExprKind::Throw { value, span } => Ok(BridgeExpr::Throw(BridgeThrow {
+            value: Box::new(owned_literal(lower_expr(*value)?)),
+            span,
+        })),
+        kind => Err(anyhow::anyhow!("Unsupported expression type: {:?}", kind)),
+    }
+}
+
+/// Lowers the left-hand side of an assignment: a variable, field or index.
+fn lower_place(target: ast::Expr) -> Result<BridgeExpr> {
+    match target.kind {
+        ast::ExprKind::Variable(_) | ast::ExprKind::Field { .. } | ast::ExprKind::Index { .. } => {
+            lower_expr(target)
+        }
+        _ => Err(anyhow::anyhow!("Cannot assign to {:?}", target)),
+    }
+}
+
+fn lower_path(path: &[ast::Ident]) -> String {
+    path.iter()
+        .map(|segment| segment.to_string())
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+fn lower_pattern(pattern: &ast::Pattern) -> BridgePattern {
+    match pattern {
+        ast::Pattern::Wildcard => BridgePattern::Wildcard,
+        ast::Pattern::Binding(name) => BridgePattern::Binding(name.to_string()),
+        ast::Pattern::LitInt(i) => BridgePattern::Literal(BridgeLiteral::Int(*i)),
+        ast::Pattern::LitStr(s) => BridgePattern::Literal(BridgeLiteral::String(s.clone())),
+        ast::Pattern::LitBool(b) => BridgePattern::Literal(BridgeLiteral::Bool(*b)),
+        ast::Pattern::Range {
+            start,
+            end,
+            inclusive,
+        } => BridgePattern::Range(BridgeRangePattern {
+            start: *start,
+            end: *end,
+            inclusive: *inclusive,
+        }),
+        ast::Pattern::Variant { path, fields } => match fields {
+            ast::PatternFields::Unit => BridgePattern::Path(lower_path(path)),
+            ast::PatternFields::Tuple(items) => BridgePattern::TupleVariant(
+                lower_path(path),
+                items.iter().map(lower_pattern).collect(),
+            ),
+            ast::PatternFields::Struct(fields) => BridgePattern::StructVariant(
+                lower_path(path),
+                fields
+                    .iter()
+                    .map(|field| BridgeFieldPattern {
+                        name: field.name.to_string(),
+                        pattern: lower_pattern(&field.pattern),
+                        span: field.name.span.clone(),
+                    })
+                    .collect(),
+            ),
+        },
+        ast::Pattern::Or(alternatives) => {
+            BridgePattern::Or(alternatives.iter().map(lower_pattern).collect())
+        }
+    }
+}
+
+fn lower_bin_op(op: ast::BinOp) -> BridgeBinOp {
+    match op {
+        ast::BinOp::Add => BridgeBinOp::Add,
+        ast::BinOp::Concat => BridgeBinOp::Concat,
+        ast::BinOp::Sub => BridgeBinOp::Sub,
+        ast::BinOp::Mul => BridgeBinOp::Mul,
+        ast::BinOp::Div => BridgeBinOp::Div,
+        ast::BinOp::Rem => BridgeBinOp::Rem,
+        ast::BinOp::And => BridgeBinOp::And,
+        ast::BinOp::Or => BridgeBinOp::Or,
+        ast::BinOp::Eq => BridgeBinOp::Eq,
+        ast::BinOp::Ne => BridgeBinOp::Ne,
+        ast::BinOp::Lt => BridgeBinOp::Lt,
+        ast::BinOp::Le => BridgeBinOp::Le,
+        ast::BinOp::Gt => BridgeBinOp::Gt,
+        ast::BinOp::Ge => BridgeBinOp::Ge,
+        ast::BinOp::Coalesce => BridgeBinOp::Coalesce,
+    }
+}
+
+fn lower_if(
+    cond: ast::Expr,
+    then_branch: ast::Block,
+    else_branch: Option<ast::Block>,
+    span: Range<usize>,
+) -> Result<BridgeIf> {
+    let else_branch = match else_branch {
+        Some(block) => Some(Box::new(lower_else(block)?)),
+        None => None,
+    };
+    Ok(BridgeIf {
+        cond: Box::new(lower_expr(cond)?),
+        then_branch: lower_block(then_branch)?,
+        else_branch,
+        span,
+    })
+}
+
+/// The grammar stores `else if` as an else block holding just the nested `if`;
+/// that becomes a chained conditional again instead of a nested block.
+fn lower_else(mut block: ast::Block) -> Result<BridgeExpr> {
+    if let [ast::Stmt::Expr(ast::Expr {
+        kind: ast::ExprKind::If { .. },
+        ..
+    })] = block.stmts.as_slice()
+    {
+        if let Some(ast::Stmt::Expr(nested)) = block.stmts.pop() {
+            return lower_expr(nested);
+        }
     }
+    Ok(BridgeExpr::Block(lower_block(block)?))
 }
@@ -0,0 +1,169 @@
+// crates/nikaia/src/parser/recovery.rs
+// Part II, Kap 10.6: Wiederaufsetzen nach Syntaxfehlern.
+//
+// A statement or item that does not parse is skipped up to a synchronization
+// point and kept as an error node, so one mistake does not hide the rest of
+// the file. A statement ends after a `;` or at a line break outside brackets,
+// or in front of the `}` that closes its block. An item ends after the `}`
+// that closes it, or in front of the next item at the top level or at the
+// start of a line. The error is placed at the furthest token the token
+// parsers got to, which is where the alternatives gave up, and lists what
+// they expected there (expected.rs).
+
+use super::expected;
+use super::lexer::{self, Keyword, Token, TokenKind, Trivia};
+use super::trivia;
+use crate::ast::{Diagnostic, Expr, ExprKind, Item};
+use std::iter;
+use std::mem;
+use std::ops::Range;
+use winnow::error::{ContextError, ErrMode};
+use winnow::stream::{AsChar, Location, Stream, StreamIsPartial};
+use winnow::ModalResult;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Level {
+    Statement,
+    Item,
+}
+
+/// A statement that did not parse, as an `ExprKind::Error` spanning it.
+pub(crate) fn skip_statement<I>(input: &mut I) -> ModalResult<Expr>
+where
+    I: Stream + StreamIsPartial + Location,
+    <I as Stream>::Token: AsChar + Clone,
+{
+    let (error, span) = skip(input, Level::Statement)?;
+    Ok(Expr::new(ExprKind::Error(error), span))
+}
+
+/// An item that did not parse, as an `Item::Error` spanning it.
+pub(crate) fn skip_item<I>(input: &mut I) -> ModalResult<Item>
+where
+    I: Stream + StreamIsPartial + Location,
+    <I as Stream>::Token: AsChar + Clone,
+{
+    let (error, span) = skip(input, Level::Item)?;
+    Ok(Item::Error { error, span })
+}
+
+/// Skips at least one token, up to the next synchronization point for
+/// `level`. Fails where there is nothing to skip: at the end of the input,
+/// and for a statement in front of the `}` closing its block.
+fn skip<I>(input: &mut I, level: Level) -> ModalResult<(Diagnostic, Range<usize>)>
+where
+    I: Stream + StreamIsPartial + Location,
+    <I as Stream>::Token: AsChar + Clone,
+{
+    let furthest = expected::take_furthest();
+    let begin = input.checkpoint();
+    trivia::skip_trivia(input)?;
+    let start = input.location();
+    let mut skipped: Vec<Token> = Vec::new();
+    let (mut braces, mut parens) = (0usize, 0usize);
+    let mut line_start = false;
+
+    loop {
+        let checkpoint = input.checkpoint();
+        let Some(token) = lexer::lex(input) else {
+            break;
+        };
+        let newline = token.kind == TokenKind::Trivia(Trivia::Newline);
+        let at_line_start = mem::replace(&mut line_start, newline);
+        let outside = braces == 0 && parens == 0;
+        let stop = match level {
+            // The `}` closing the block belongs to the block
+            Level::Statement if is_punct(&token, "}") && braces == 0 => true,
+            Level::Statement => newline && outside,
+            Level::Item => {
+                !skipped.is_empty() && (outside || at_line_start) && starts_item(&token.kind)
+            }
+        };
+        if stop {
+            input.reset(&checkpoint);
+            break;
+        }
+        if let TokenKind::Trivia(_) = token.kind {
+            continue;
+        }
+
+        skipped.push(token.clone());
+        match token.kind {
+            TokenKind::Punct("{") => braces += 1,
+            TokenKind::Punct("(" | "[") => parens += 1,
+            TokenKind::Punct(")" | "]") => parens = parens.saturating_sub(1),
+            TokenKind::Punct("}") if braces == 1 && level == Level::Item => break,
+            TokenKind::Punct("}") => braces = braces.saturating_sub(1),
+            TokenKind::Punct(";") if outside && level == Level::Statement => break,
+            _ => {}
+        }
+    }
+
+    let Some(last) = skipped.last() else {
+        expected::restore(furthest);
+        input.reset(&begin);
+        return Err(ErrMode::Backtrack(ContextError::new()));
+    };
+    let span = start..last.span.end;
+    let mut error = match skipped
+        .iter()
+        .find(|token| token.span.start >= furthest.offset)
+    {
+        Some(token) => unexpected(token),
+        // `let x = 1 +` ends with its line, the error is on the next one
+        None => found_at(input, furthest.offset),
+    };
+    if error.span.start == furthest.offset {
+        error.expected = furthest.expected;
+    }
+    Ok((error, span))
+}
+
+/// The first token at or after `offset`, or the end of the input, without
+/// consuming anything.
+fn found_at<I>(input: &mut I, offset: usize) -> Diagnostic
+where
+    I: Stream + Location,
+    <I as Stream>::Token: AsChar,
+{
+    let checkpoint = input.checkpoint();
+    let token = iter::from_fn(|| lexer::lex(input))
+        .find(|token| token.span.start >= offset && !matches!(token.kind, TokenKind::Trivia(_)));
+    let end = input.location();
+    input.reset(&checkpoint);
+    match token {
+        Some(token) => unexpected(&token),
+        None => Diagnostic {
+            message: "unexpected end of input".to_string(),
+            span: end..end,
+            expected: Vec::new(),
+        },
+    }
+}
+
+fn unexpected(token: &Token) -> Diagnostic {
+    Diagnostic {
+        message: format!("unexpected {}", token.kind),
+        span: token.span.clone(),
+        expected: Vec::new(),
+    }
+}
+
+fn is_punct(token: &Token, punct: &str) -> bool {
+    matches!(token.kind, TokenKind::Punct(p) if p == punct)
+}
+
+fn starts_item(kind: &TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::Keyword(
+            Keyword::Fn
+                | Keyword::Struct
+                | Keyword::Enum
+                | Keyword::Impl
+                | Keyword::Trait
+                | Keyword::Use
+                | Keyword::Pub
+        ) | TokenKind::Trivia(Trivia::DocComment)
+    )
+}
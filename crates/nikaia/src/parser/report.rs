@@ -0,0 +1,131 @@
+// crates/nikaia/src/parser/report.rs
+// Fehlermeldungen: Ort, Quelltextauszug und erwartete Tokens.
+//
+// A syntax error is shown the way rustc shows one: the message with what was
+// expected instead, the file, line and column, and the source line with the
+// offending token underlined. `--message-format=json` prints the same
+// messages for tools, one JSON object per line.
+
+use crate::ast::{Diagnostic, SourceMap};
+use serde::Serialize;
+use std::fmt;
+use std::ops::Range;
+
+/// A syntax error placed in its file.
+#[derive(Debug, Clone, Serialize)]
+pub struct Message {
+    pub file: String,
+    pub line: usize,
+    /// From 1, in characters (Unicode scalar values), not bytes: `é` before
+    /// the error is one column
+    pub column: usize,
+    pub message: String,
+    pub expected: Vec<String>,
+    pub span: Range<usize>,
+    /// The line the error is on, without its line break
+    pub source_line: String,
+    /// The message as it is printed for people
+    pub rendered: String,
+}
+
+/// The syntax errors of a program, file by file.
+#[derive(Debug, Default)]
+pub struct Report {
+    pub messages: Vec<Message>,
+}
+
+impl Message {
+    pub fn new(diagnostic: &Diagnostic, source_map: &SourceMap, source: &str) -> Self {
+        // An error at the end of the input is shown after the last line, not
+        // on the empty one behind it
+        let offset = match source.strip_suffix('\n') {
+            Some(rest) if diagnostic.span.start == source.len() => rest.len(),
+            _ => diagnostic.span.start,
+        };
+        let (line, column) = source_map.line_column(offset);
+        let line_start = source_map.line_starts[line - 1];
+        let source_line = source[line_start..]
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        let mut message = Message {
+            file: source_map.file.clone(),
+            line,
+            column,
+            message: diagnostic.message.clone(),
+            expected: diagnostic.expected.clone(),
+            span: diagnostic.span.clone(),
+            source_line,
+            rendered: String::new(),
+        };
+        message.rendered = message.render();
+        message
+    }
+
+    //   error: unexpected `=`, expected `mut` or identifier
+    //    --> main.nika:2:9
+    //     |
+    //   2 |     let = 1
+    //     |         ^
+    fn render(&self) -> String {
+        let number = self.line.to_string();
+        let gutter = " ".repeat(number.len());
+        let start = self
+            .source_line
+            .char_indices()
+            .nth(self.column - 1)
+            .map_or(self.source_line.len(), |(i, _)| i);
+        let end = (start + self.span.len()).min(self.source_line.len());
+        // Tabs stay tabs so the carets line up with the text above them
+        let indent: String = self.source_line[..start]
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let carets = "^".repeat(self.source_line[start..end].chars().count().max(1));
+        format!(
+            "error: {}\n{}--> {}:{}:{}\n{} |\n{} | {}\n{} | {}{}",
+            summary(&self.message, &self.expected),
+            gutter,
+            self.file,
+            self.line,
+            self.column,
+            gutter,
+            number,
+            self.source_line,
+            gutter,
+            indent,
+            carets
+        )
+    }
+}
+
+/// `unexpected `=`, expected `mut` or identifier`
+pub(crate) fn summary(message: &str, expected: &[String]) -> String {
+    match expected.split_last() {
+        None => message.to_string(),
+        Some((last, [])) => format!("{}, expected {}", message, last),
+        Some((last, rest)) => format!("{}, expected {} or {}", message, rest.join(", "), last),
+    }
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.rendered)
+    }
+}
+
+// A blank line between messages, as rustc prints them
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, message) in self.messages.iter().enumerate() {
+            if i > 0 {
+                f.write_str("\n\n")?;
+            }
+            write!(f, "{}", message)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Report {}
@@ -0,0 +1,222 @@
+// crates/nikaia/src/parser/trivia.rs
+// Kommentare und Whitespace zwischen Tokens.
+//
+// The grammar only skips plain whitespace in front of punctuation, so comments
+// are consumed wherever a rule asks for `skip_ws`; the token parsers in
+// lexer.rs skip all trivia themselves. Doc comments (`///`) are ordinary
+// trivia there; in front of items and fields `skip_to_docs` leaves them for
+// `doc_comment` to collect.
+
+use winnow::error::{ContextError, ErrMode};
+use winnow::stream::{AsChar, Stream, StreamIsPartial};
+use winnow::ModalResult;
+
+#[derive(PartialEq)]
+pub(super) enum Comment {
+    Line,
+    Doc,
+    Block,
+}
+
+/// Whitespace, `//` and nested `/* */` comments, including doc comments.
+pub(crate) fn skip_trivia<I>(input: &mut I) -> ModalResult<()>
+where
+    I: Stream + StreamIsPartial,
+    <I as Stream>::Token: AsChar + Clone,
+{
+    skip(input, false);
+    Ok(())
+}
+
+/// Like `skip_trivia`, but stops in front of a `///` doc comment.
+pub(crate) fn skip_trivia_keep_docs<I>(input: &mut I) -> ModalResult<()>
+where
+    I: Stream + StreamIsPartial,
+    <I as Stream>::Token: AsChar + Clone,
+{
+    skip(input, true);
+    Ok(())
+}
+
+/// A `.` right after the previous token, with no trivia in between. `..`
+/// does not count.
+pub(crate) fn adjacent_dot<I>(input: &mut I) -> ModalResult<()>
+where
+    I: Stream + StreamIsPartial,
+    <I as Stream>::Token: AsChar + Clone,
+{
+    let start = input.checkpoint();
+    if eat(input, '.') && !eat(input, '.') {
+        return Ok(());
+    }
+    input.reset(&start);
+    Err(ErrMode::Backtrack(ContextError::new()))
+}
+
+/// `?.` right after the previous token, like `adjacent_dot`.
+pub(crate) fn adjacent_safe_dot<I>(input: &mut I) -> ModalResult<()>
+where
+    I: Stream + StreamIsPartial,
+    <I as Stream>::Token: AsChar + Clone,
+{
+    let start = input.checkpoint();
+    if eat(input, '?') && eat(input, '.') {
+        return Ok(());
+    }
+    input.reset(&start);
+    Err(ErrMode::Backtrack(ContextError::new()))
+}
+
+/// One `///` line (without the marker and a single leading space), plus the
+/// trivia around it.
+pub(crate) fn doc_comment<I>(input: &mut I) -> ModalResult<String>
+where
+    I: Stream + StreamIsPartial,
+    <I as Stream>::Token: AsChar + Clone,
+{
+    skip(input, true);
+    let start = input.checkpoint();
+    if open_comment(input) != Some(Comment::Doc) {
+        input.reset(&start);
+        return Err(ErrMode::Backtrack(ContextError::new()));
+    }
+    let line = rest_of_line(input);
+    skip(input, true);
+    Ok(line
+        .strip_prefix(' ')
+        .unwrap_or(&line)
+        .trim_end()
+        .to_string())
+}
+
+fn skip<I>(input: &mut I, keep_docs: bool)
+where
+    I: Stream + StreamIsPartial,
+    <I as Stream>::Token: AsChar + Clone,
+{
+    loop {
+        let start = input.checkpoint();
+        match input.next_token().map(AsChar::as_char) {
+            Some(c) if c.is_whitespace() => {}
+            Some('/') => {
+                input.reset(&start);
+                match open_comment(input) {
+                    Some(Comment::Doc) if keep_docs => {
+                        input.reset(&start);
+                        return;
+                    }
+                    Some(Comment::Line | Comment::Doc) => {
+                        rest_of_line(input);
+                    }
+                    Some(Comment::Block) => skip_block_comment(input),
+                    None => {
+                        input.reset(&start);
+                        return;
+                    }
+                }
+            }
+            _ => {
+                input.reset(&start);
+                return;
+            }
+        }
+    }
+}
+
+/// One whole comment, for the lexer. Leaves `input` alone if none starts here.
+pub(super) fn comment<I>(input: &mut I) -> Option<Comment>
+where
+    I: Stream,
+    <I as Stream>::Token: AsChar,
+{
+    let start = input.checkpoint();
+    match open_comment(input) {
+        Some(Comment::Block) => {
+            skip_block_comment(input);
+            Some(Comment::Block)
+        }
+        Some(kind) => {
+            rest_of_line(input);
+            Some(kind)
+        }
+        None => {
+            input.reset(&start);
+            None
+        }
+    }
+}
+
+/// Consumes a comment opener. `////` is a plain comment, not a doc comment.
+fn open_comment<I>(input: &mut I) -> Option<Comment>
+where
+    I: Stream,
+    <I as Stream>::Token: AsChar,
+{
+    if !eat(input, '/') {
+        return None;
+    }
+    if eat(input, '*') {
+        return Some(Comment::Block);
+    }
+    if !eat(input, '/') {
+        return None;
+    }
+    let after_marker = input.checkpoint();
+    if eat(input, '/') {
+        let is_plain = eat(input, '/');
+        input.reset(&after_marker);
+        if !is_plain {
+            eat(input, '/');
+            return Some(Comment::Doc);
+        }
+    }
+    Some(Comment::Line)
+}
+
+// Block comments nest: /* a /* b */ c */
+fn skip_block_comment<I>(input: &mut I)
+where
+    I: Stream,
+    <I as Stream>::Token: AsChar,
+{
+    let mut depth = 1;
+    while depth > 0 {
+        match input.next_token().map(AsChar::as_char) {
+            Some('/') if eat(input, '*') => depth += 1,
+            Some('*') if eat(input, '/') => depth -= 1,
+            Some(_) => {}
+            None => return,
+        }
+    }
+}
+
+fn rest_of_line<I>(input: &mut I) -> String
+where
+    I: Stream,
+    <I as Stream>::Token: AsChar,
+{
+    let mut line = String::new();
+    loop {
+        let start = input.checkpoint();
+        match input.next_token().map(AsChar::as_char) {
+            Some('\n') | None => {
+                input.reset(&start);
+                return line;
+            }
+            Some(c) => line.push(c),
+        }
+    }
+}
+
+pub(super) fn eat<I>(input: &mut I, expected: char) -> bool
+where
+    I: Stream,
+    <I as Stream>::Token: AsChar,
+{
+    let start = input.checkpoint();
+    if input.next_token().map(AsChar::as_char) == Some(expected) {
+        return true;
+    }
+    input.reset(&start);
+    false
+}
@@ -0,0 +1,561 @@
+#![feature(rustc_private)]
+extern crate rustc_driver;
+
+use bridge_ir::BridgeItem;
+use nikaia_driver::parser::parse_to_bridge;
+use std::path::Path;
+use std::process::Command;
+
+// Lowers `source` to Bridge IR, compiles it with the rustc executor and runs
+// the binary. Returns what it printed.
+fn run(name: &str, source: &str) -> String {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let entry = dir.join(format!("{}.nika", name));
+    let module = parse_to_bridge(source, &entry).expect("Failed to lower");
+    let output = dir.join(name);
+    rustc_executor::execute(&module, output.to_str().unwrap()).expect("Failed to compile");
+    let out = Command::new(&output).output().expect("Failed to run");
+    String::from_utf8(out.stdout).unwrap()
+}
+
+// Kap 1.3: comments are skipped, and doc comments become `#[doc]`
+// attributes of the generated items.
+#[test]
+fn test_doc_comments() {
+    let source = r#"
+/* a block comment /* with a nested one */ */
+/// Adds two numbers.
+fn add(a: i32, b: i32) -> i32 {
+    a /* inline */ + b // trailing
+}
+
+fn main() {
+    // a line comment
+    if add(1, 2) == 3 {
+        println("documented")
+    }
+}
+"#;
+    assert_eq!(run("doc_comments", source), "documented\n");
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let rust = std::fs::read_to_string(dir.join("doc_comments.rs")).unwrap();
+    assert!(rust.contains("#[doc = \"Adds two numbers.\"]"), "{}", rust);
+}
+
+// Kap 4.1: a struct becomes a Rust struct with its generics and fields.
+#[test]
+fn test_struct_definitions() {
+    let source = r#"
+struct Pair[T] {
+    pub first: T,
+    second: T,
+}
+
+fn main() {
+    let pair = Pair(first: 1, second: 2)
+    if pair.first + pair.second == 3 {
+        println("pair")
+    }
+}
+"#;
+    assert_eq!(run("struct_definitions", source), "pair\n");
+}
+
+// Kap 4.4: enums with unit, tuple and struct-like variants become Rust
+// enums; values of them are built and matched on.
+#[test]
+fn test_enums() {
+    let source = r#"
+enum Message {
+    Quit,
+    Write(String),
+    Move { x: i32, y: i32 },
+}
+
+fn describe(message: Message) -> String {
+    match message {
+        Message::Quit => "quit".to_string(),
+        Message::Write(text) => text,
+        Message::Move { x, y } => {
+            if x + y == 3 {
+                "move".to_string()
+            } else {
+                "stay".to_string()
+            }
+        }
+    }
+}
+
+fn main() {
+    let quit = describe(Message::Quit)
+    let write = describe(Message::Write("hi".to_string()))
+    let step = describe(Message::Move(x: 1, y: 2))
+    if quit == "quit" && write == "hi" && step == "move" {
+        println("enums")
+    }
+}
+"#;
+    assert_eq!(run("enums", source), "enums\n");
+}
+
+// Kap 4.3: methods take `self`, `&self` or `&mut self`, and calls through
+// them read and update the receiver.
+#[test]
+fn test_methods() {
+    let source = r#"
+struct Counter {
+    count: i32,
+}
+
+impl Counter {
+    fn bump(&mut self, by: i32) {
+        self.count += by
+    }
+
+    fn get(&self) -> i32 {
+        self.count
+    }
+
+    fn into_count(self) -> i32 {
+        self.count
+    }
+}
+
+fn main() {
+    let mut counter = Counter(count: 1)
+    counter.bump(2)
+    counter.bump(3)
+    if counter.get() == 6 && counter.into_count() == 6 {
+        println("bumped")
+    }
+}
+"#;
+    assert_eq!(run("methods", source), "bumped\n");
+}
+
+// Kap 4.7: a trait impl gets the trait's default methods, and a generic
+// function calls trait methods through its bound.
+#[test]
+fn test_traits() {
+    let source = r#"
+trait Shape {
+    fn area(&self) -> i32
+
+    fn name(&self) -> String {
+        "shape".to_string()
+    }
+}
+
+struct Square {
+    side: i32,
+}
+
+impl Shape for Square {
+    fn area(&self) -> i32 {
+        self.side * self.side
+    }
+}
+
+fn total[T: Shape](shape: T) -> i32 {
+    shape.area()
+}
+
+fn main() {
+    let square = Square(side: 3)
+    if square.name() == "shape" && total(square) == 9 {
+        println("traits")
+    }
+}
+"#;
+    assert_eq!(run("traits", source), "traits\n");
+}
+
+// Kap 5.1: `if`/`else if`/`else` is an expression whose value is the value
+// of the branch taken.
+#[test]
+fn test_if_expressions() {
+    let source = r#"
+fn grade(score: i32) -> i32 {
+    let level = if score >= 90 {
+        3
+    } else if score >= 50 {
+        2
+    } else {
+        1
+    }
+    level
+}
+
+fn main() {
+    if grade(95) == 3 && grade(60) == 2 && grade(10) == 1 {
+        println("graded")
+    }
+}
+"#;
+    assert_eq!(run("if_expressions", source), "graded\n");
+}
+
+// Kap 3.3: `for` over exclusive and inclusive ranges and lists, `while` with
+// `continue`, and a `loop` whose value is the one passed to `break`.
+#[test]
+fn test_loops() {
+    let source = r#"
+fn count_up() -> i32 {
+    let mut total = 0
+    for i in 0..5 {
+        total += i
+    }
+    for i in 1..=3 {
+        total += i
+    }
+    for x in [10, 20] {
+        total += x
+    }
+    let mut count = 0
+    while count < 5 {
+        count += 1
+        if count == 2 {
+            continue
+        }
+        total += 1
+    }
+    let found = loop {
+        count += 1
+        if count == 8 {
+            break count
+        }
+    }
+    total + found
+}
+
+fn main() {
+    if count_up() == 58 {
+        println("looped")
+    }
+}
+"#;
+    assert_eq!(run("loops", source), "looped\n");
+}
+
+// Kap 2: arithmetic follows operator precedence, and compound assignment
+// updates a `let mut` binding in place.
+#[test]
+fn test_operators() {
+    let source = r#"
+fn compute() -> i32 {
+    let mut x = 2 + 3 * 4
+    x -= 4
+    x *= 3
+    x /= 4
+    x %= 4
+    x += -1 + 11 % 4
+    x
+}
+
+fn main() {
+    let x = compute()
+    if x == 5 && !false || x < 0 {
+        if "n: " + x == "n: 5" && (x + 1) * 2 - (3 - 1) == 10 && !(x < 0) {
+            println("computed")
+        }
+    }
+}
+"#;
+    assert_eq!(run("operators", source), "computed\n");
+}
+
+// Kap 4.4: literal, or-, range and guarded arms, negative numbers included,
+// are tried in order before the catch-all.
+#[test]
+fn test_match_arms() {
+    let source = r#"
+fn classify(n: i32) {
+    match n {
+        0 => println("zero"),
+        -1 => println("minus one"),
+        1 | 2 | 3 => println("few"),
+        4..=9 => println("some"),
+        -9..=-2 => println("minus some"),
+        x if x < 0 => println("negative"),
+        _ => println("many"),
+    }
+}
+
+fn main() {
+    classify(0)
+    classify(2)
+    classify(7)
+    classify(-1)
+    classify(-4)
+    classify(-40)
+    classify(12)
+}
+"#;
+    assert_eq!(
+        run("match_arms", source),
+        "zero\nfew\nsome\nminus one\nminus some\nnegative\nmany\n"
+    );
+}
+
+// Kap 8.2: spawned tasks run on their own threads, and the program waits
+// for them before it exits.
+#[test]
+fn test_spawn() {
+    let source = r#"
+fn main() {
+    spawn({
+        println("spawned")
+    })
+    spawn move fn: println("spawned")
+}
+"#;
+    assert_eq!(run("spawn", source), "spawned\nspawned\n");
+}
+
+// Kap 5.2: `fn:` lambdas take the implicit parameters their body uses, in
+// positional order, and capture the variables around them.
+#[test]
+fn test_implicit_lambdas() {
+    let source = r#"
+fn main() {
+    let offset = 10
+    let add = fn: a + b
+    let second = fn: b
+    let shift = fn: a + offset
+    let sum = fn { a + b + c }
+    if add(1, 2) == 3 && second(7, 8) == 8 && shift(5) == 15 && sum(1, 2, 3) == 6 {
+        println("called")
+    }
+}
+"#;
+    assert_eq!(run("implicit_lambdas", source), "called\n");
+}
+
+// An impl declares the generic parameters of its target type.
+#[test]
+fn test_impl_generics() {
+    let source = r#"
+struct Stack[T] {
+    items: Vec[T],
+}
+
+impl[T: Clone] Stack[T] {
+    fn top(&self) -> T {
+        self.items[0].clone()
+    }
+}
+
+fn main() {
+    let stack = Stack(items: [1, 2])
+    if stack.top() == 1 {
+        println("top is 1")
+    }
+}
+"#;
+    assert_eq!(run("impl_generics", source), "top is 1\n");
+}
+
+// Kap 2: plain and compound assignment to variables and struct fields.
+#[test]
+fn test_assignment() {
+    let source = r#"
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+fn main() {
+    let mut point = Point(x: 1, y: 2)
+    let mut step = 5
+    step = 20
+    point.x = 10
+    point.y += step
+    if point.x == 10 && point.y == 22 {
+        println("assigned")
+    }
+}
+"#;
+    assert_eq!(run("assignment", source), "assigned\n");
+}
+
+// Only variables, fields and indexes can be assigned to.
+#[test]
+fn test_assign_target() {
+    let source = r#"
+fn origin() -> i32 {
+    0
+}
+
+fn main() {
+    origin() = 1
+}
+"#;
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let error = parse_to_bridge(source, &dir.join("assign_target.nika"))
+        .expect_err("assignment to a call was lowered")
+        .to_string();
+    assert!(error.starts_with("Cannot assign to"), "{}", error);
+}
+
+// Maps take keys that convert into their key type, and index targets work
+// with compound assignment.
+#[test]
+fn test_index_assign() {
+    let source = r#"
+use std::collections::HashMap
+
+fn main() {
+    let mut scores: HashMap[String, i32] = HashMap::new()
+    scores["Player1"] = 100
+    scores["Player1"] += 5
+    let mut numbers = [1, 2, 3]
+    numbers[0] = 10
+    numbers[0] *= 2
+    if scores["Player1"] == 105 && numbers[0] == 20 {
+        println("updated")
+    }
+}
+"#;
+    assert_eq!(run("index_assign", source), "updated\n");
+}
+
+// `+` concatenates whenever an operand is known to be a string: variables,
+// parameters, fields and return values, not just literals.
+#[test]
+fn test_string_concat() {
+    let source = r#"
+struct User {
+    name: String,
+}
+
+fn join(a: String, b: String) -> String {
+    a + b
+}
+
+fn main() {
+    let user = User(name: join("Ada".to_string(), " ".to_string()))
+    let last = "Lovelace"
+    let mut full = user.name + last
+    full += last
+    let count = 2 + 3
+    if full == "Ada LovelaceLovelace" && count == 5 {
+        println("joined")
+    }
+}
+"#;
+    assert_eq!(run("string_concat", source), "joined\n");
+}
+
+// A value that goes into a `T?` slot becomes `Some(value)`: annotated lets,
+// assignments, arguments, struct fields and return values.
+#[test]
+fn test_nullable_coercion() {
+    let source = r#"
+struct Profile {
+    bio: String?,
+}
+
+fn greet(name: String?) -> String {
+    name ?? "nobody"
+}
+
+fn nick() -> String? {
+    "Ace"
+}
+
+fn pick(long: bool) -> String? {
+    if long {
+        "Ada Lovelace"
+    } else {
+        null
+    }
+}
+
+fn main() {
+    let first = "Ada".to_string()
+    let shout: String? = first + "!"
+    let mut count: i32? = null
+    count = 2 + 3
+    let profile = Profile(bio: "Countess".to_string())
+    let bio = profile.bio ?? ""
+    let picked = pick(true) ?? ""
+    let none = pick(false) ?? "none"
+    if greet("Ada") == "Ada" && nick() ?? "" == "Ace" && shout ?? "" == "Ada!" {
+        println("wrapped")
+    }
+    if count ?? 0 == 5 && bio == "Countess" && picked == "Ada Lovelace" && none == "none" {
+        println("assigned")
+    }
+}
+"#;
+    assert_eq!(run("nullable_coercion", source), "wrapped\nassigned\n");
+}
+
+// Kap 7.1: a caught error prints where it was thrown and every call it
+// propagated through; columns count characters, so the `ü` is one.
+// interpreter.rs runs the same program.
+#[test]
+fn test_error_trace() {
+    let source = r#"
+enum IoError {
+    Missing,
+}
+
+fn read(id: i32) throws IoError -> i32 {
+    if id > 0 && "ü" != "" { throw IoError::Missing }
+    id
+}
+
+fn load() throws IoError -> i32 {
+    read(1)
+}
+
+fn main() {
+    let value = load() catch {
+        println(error)
+        0
+    }
+}
+"#;
+    let file = Path::new(env!("CARGO_TARGET_TMPDIR")).join("error_trace.nika");
+    let expected = format!(
+        "error: IoError\n  thrown at {0}:7:36\n  via read at {0}:12:5\n",
+        file.display()
+    );
+    assert_eq!(run("error_trace", source), expected);
+}
+
+// A generated error enum is no more visible than the error types it wraps.
+#[test]
+fn test_error_enum_visibility() {
+    let source = r#"
+pub struct IoError {}
+
+struct ParseError {}
+
+pub struct NetworkError {}
+
+pub fn fetch() throws IoError, NetworkError {
+}
+
+fn parse() throws IoError, ParseError {
+}
+"#;
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let module = parse_to_bridge(source, &dir.join("error_enums.nika")).expect("Failed to lower");
+    let enums: Vec<(String, bool)> = module
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            BridgeItem::Enum(e) => Some((e.name.clone(), e.is_pub)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        enums,
+        [
+            ("IoErrorOrNetworkError".to_string(), true),
+            ("IoErrorOrParseError".to_string(), false),
+        ]
+    );
+}
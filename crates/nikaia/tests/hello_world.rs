@@ -1,4 +1,4 @@
-use nikaia_driver::ast::{Expr, Item, Stmt};
+use nikaia_driver::ast::{Expr, ExprKind, Item, Stmt};
 use nikaia_driver::parser::CompilerGrammar;
 use winnow::Parser;
 use winnow::stream::LocatingSlice;
@@ -30,15 +30,18 @@ fn test_advanced_hello_world_compilation() {
 
         // Verify println("Hello Nikaia")
         match &body.stmts[0] {
-            Stmt::Expr(Expr::Call { func, args }) => {
-                if let Expr::Variable(fname) = &**func {
+            Stmt::Expr(Expr {
+                kind: ExprKind::Call { func, args, .. },
+                ..
+            }) => {
+                if let ExprKind::Variable(fname) = &func.kind {
                     assert_eq!(fname.to_string(), "println");
                 } else {
                     panic!("Expected function name");
                 }
 
                 assert_eq!(args.len(), 1);
-                if let Expr::LitStr(s) = &args[0] {
+                if let ExprKind::LitStr(s) = &args[0].kind {
                     assert_eq!(s, "Hello Nikaia");
                 } else {
                     panic!("Expected string literal");
@@ -49,13 +52,17 @@ fn test_advanced_hello_world_compilation() {
 
         // Verify spawn({ ... })
         match &body.stmts[1] {
-            Stmt::Expr(Expr::Spawn {
-                body: spawn_body,
-                is_move,
+            Stmt::Expr(Expr {
+                kind:
+                    ExprKind::Spawn {
+                        body: spawn_body,
+                        is_move,
+                    },
+                ..
             }) => {
                 assert!(!is_move, "Should not be move by default");
                 // spawn body is a Block expression
-                if let Expr::Block(inner_block) = &**spawn_body {
+                if let ExprKind::Block(inner_block) = &spawn_body.kind {
                     assert_eq!(inner_block.stmts.len(), 1);
                 } else {
                     panic!("Spawn body should be a block");
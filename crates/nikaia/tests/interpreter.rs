@@ -0,0 +1,344 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+// Runs `source` with the interpreter backend of the nikaia binary. Returns
+// what the program printed, without the kernel's own messages.
+fn run(name: &str, source: &str) -> String {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let entry = dir.join(format!("interpret_{}.nika", name));
+    fs::write(&entry, source).expect("Failed to write source");
+    let out = Command::new(env!("CARGO_BIN_EXE_nikaia"))
+        .args(["--backend", "interpreter", "--input"])
+        .arg(&entry)
+        .output()
+        .expect("Failed to run");
+    String::from_utf8(out.stdout)
+        .unwrap()
+        .lines()
+        .filter(|line| !line.starts_with("[Nikaia Kernel]"))
+        .map(|line| format!("{}\n", line))
+        .collect()
+}
+
+// Kap 4.3: methods are dispatched on the receiver's type, and a `&mut self`
+// method writes the receiver back.
+#[test]
+fn test_methods() {
+    let source = r#"
+struct Counter {
+    count: i32,
+}
+
+impl Counter {
+    fn bump(&mut self, by: i32) {
+        self.count += by
+    }
+
+    fn get(&self) -> i32 {
+        self.count
+    }
+}
+
+fn main() {
+    let mut counter = Counter(count: 1)
+    counter.bump(2)
+    counter.bump(3)
+    println(counter.get())
+}
+"#;
+    assert_eq!(run("methods", source), "6\n");
+}
+
+// Kap 4.7: an impl without a method of its own uses the trait's default.
+#[test]
+fn test_trait_defaults() {
+    let source = r#"
+trait Shape {
+    fn area(&self) -> i32
+
+    fn double(&self) -> i32 {
+        self.area() * 2
+    }
+}
+
+struct Square {
+    side: i32,
+}
+
+impl Shape for Square {
+    fn area(&self) -> i32 {
+        self.side * self.side
+    }
+}
+
+fn total[T: Shape](shape: T) -> i32 {
+    shape.area()
+}
+
+fn main() {
+    let square = Square(side: 3)
+    println(square.double())
+    println(total(square))
+}
+"#;
+    assert_eq!(run("trait_defaults", source), "18\n9\n");
+}
+
+// Kap 5.1: the value of an `if` chain is the value of the branch taken.
+#[test]
+fn test_if_expressions() {
+    let source = r#"
+fn grade(score: i32) -> i32 {
+    let level = if score >= 90 {
+        3
+    } else if score >= 50 {
+        2
+    } else {
+        1
+    }
+    level
+}
+
+fn main() {
+    println(grade(95))
+    println(grade(60))
+    println(grade(10))
+}
+"#;
+    assert_eq!(run("if_expressions", source), "3\n2\n1\n");
+}
+
+// Kap 3.3: ranges, lists, `continue` and a `loop` that breaks with a value.
+#[test]
+fn test_loops() {
+    let source = r#"
+fn count_up() -> i32 {
+    let mut total = 0
+    for i in 0..5 {
+        total += i
+    }
+    for i in 1..=3 {
+        total += i
+    }
+    for x in [10, 20] {
+        total += x
+    }
+    let mut count = 0
+    while count < 5 {
+        count += 1
+        if count == 2 {
+            continue
+        }
+        total += 1
+    }
+    let found = loop {
+        count += 1
+        if count == 8 {
+            break count
+        }
+    }
+    total + found
+}
+
+fn main() {
+    println(count_up())
+}
+"#;
+    assert_eq!(run("loops", source), "58\n");
+}
+
+// Kap 2: precedence, compound assignment and string concatenation.
+#[test]
+fn test_operators() {
+    let source = r#"
+fn compute() -> i32 {
+    let mut x = 2 + 3 * 4
+    x -= 4
+    x *= 3
+    x /= 4
+    x %= 4
+    x += -1 + 11 % 4
+    x
+}
+
+fn main() {
+    let x = compute()
+    println("n: " + x)
+    println(x == 5 && !false || x < 0)
+    println((x + 1) * 2 - (3 - 1))
+    println(!(x < 0))
+}
+"#;
+    assert_eq!(run("operators", source), "n: 5\ntrue\n10\ntrue\n");
+}
+
+// Kap 2: division by zero and overflow stop the program, like the panic of
+// the compiled binary; `catch` doesn't handle them.
+#[test]
+fn test_arithmetic_faults() {
+    let source = r#"
+fn divide(a: i32, b: i32) -> i32 {
+    a / b
+}
+
+fn main() {
+    println(divide(10, 2))
+    let zero = 0
+    println(divide(1, zero))
+    println("after")
+}
+"#;
+    assert_eq!(
+        run("division_by_zero", source),
+        "5\n[Nikaia Runtime] Panic: division by zero\n"
+    );
+
+    let source = r#"
+fn main() {
+    let mut big = 9223372036854775807
+    big += 1
+    println("after")
+}
+"#;
+    assert_eq!(
+        run("overflow", source),
+        "[Nikaia Runtime] Panic: overflow\n"
+    );
+}
+
+// Kap 4.4: arms are tried in order; guards and ranges refine them.
+#[test]
+fn test_match_arms() {
+    let source = r#"
+fn classify(n: i32) {
+    match n {
+        0 => println("zero"),
+        -1 => println("minus one"),
+        1 | 2 | 3 => println("few"),
+        4..=9 => println("some"),
+        -9..=-2 => println("minus some"),
+        x if x < 0 => println("negative"),
+        _ => println("many"),
+    }
+}
+
+fn main() {
+    classify(0)
+    classify(2)
+    classify(7)
+    classify(-1)
+    classify(-4)
+    classify(-40)
+    classify(12)
+}
+"#;
+    assert_eq!(
+        run("match_arms", source),
+        "zero\nfew\nsome\nminus one\nminus some\nnegative\nmany\n"
+    );
+}
+
+// Kap 4.4: every kind of variant is built and matched, struct-like ones
+// with named fields like a struct.
+#[test]
+fn test_enums() {
+    let source = r#"
+enum Message {
+    Quit,
+    Write(String),
+    Move { x: i32, y: i32 },
+}
+
+fn describe(message: Message) {
+    match message {
+        Message::Quit => println("quit"),
+        Message::Write(text) => println(text),
+        Message::Move { x, y } => println(x + y),
+    }
+}
+
+fn main() {
+    describe(Message::Quit)
+    describe(Message::Write("hi"))
+    describe(Message::Move(y: 2, x: 1))
+    println(Message::Move(x: 4, y: 5))
+}
+"#;
+    assert_eq!(run("enums", source), "quit\nhi\n3\nMove { x: 4, y: 5 }\n");
+}
+
+// Kap 7.1: a caught error prints where it was thrown and every call it
+// propagated through; columns count characters, so the `ü` is one.
+// codegen.rs runs the same program.
+#[test]
+fn test_error_trace() {
+    let source = r#"
+enum IoError {
+    Missing,
+}
+
+fn read(id: i32) throws IoError -> i32 {
+    if id > 0 && "ü" != "" { throw IoError::Missing }
+    id
+}
+
+fn load() throws IoError -> i32 {
+    read(1)
+}
+
+fn main() {
+    let value = load() catch {
+        println(error)
+        0
+    }
+}
+"#;
+    let file = Path::new(env!("CARGO_TARGET_TMPDIR")).join("interpret_error_trace.nika");
+    let expected = format!(
+        "error: IoError\n  thrown at {0}:7:36\n  via read at {0}:12:5\n",
+        file.display()
+    );
+    assert_eq!(run("error_trace", source), expected);
+}
+
+// Kap 2: assignments update variables and fields in place.
+#[test]
+fn test_assignment() {
+    let source = r#"
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+fn main() {
+    let mut point = Point(x: 1, y: 2)
+    let mut step = 5
+    step = 20
+    point.x = 10
+    point.y += step
+    println(point.x)
+    println(point.y)
+}
+"#;
+    assert_eq!(run("assignment", source), "10\n22\n");
+}
+
+// Kap 5.2: lambdas are values; `fn: b` still takes two arguments.
+#[test]
+fn test_implicit_lambdas() {
+    let source = r#"
+fn main() {
+    let offset = 10
+    let add = fn: a + b
+    let second = fn: b
+    let shift = fn: a + offset
+    let sum = fn { a + b + c }
+    println(add(1, 2))
+    println(second(7, 8))
+    println(shift(5))
+    println(sum(1, 2, 3))
+}
+"#;
+    assert_eq!(run("implicit_lambdas", source), "3\n8\n15\n6\n");
+}
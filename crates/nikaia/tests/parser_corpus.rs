@@ -0,0 +1,1426 @@
+use bridge_ir::BridgeItem;
+use nikaia_driver::ast::{
+    BinOp, Block, ConfigArg, Expr, ExprKind, Ident, Item, Pattern, Program, SourceMap, Stmt, Type,
+    UnOp,
+};
+use nikaia_driver::checker::check_program;
+use nikaia_driver::loader::load_program;
+use nikaia_driver::parser::lexer::{tokenize, Keyword, TokenKind, Trivia};
+use nikaia_driver::parser::{parse_to_ast, parse_to_bridge, parse_with_diagnostics};
+use std::path::Path;
+
+// Lambda and trailing-lambda corpus (Kap 5.2 / 5.3). Each case is the body of
+// `fn main`, paired with the shape of its last statement after the checker
+// has filled in implicit parameters.
+const CASES: &[(&str, &str)] = &[
+    ("users.map fn: a.id", "users.map(fn(a) a.id)"),
+    (
+        "numbers.reduce(0) fn: a + b",
+        "numbers.reduce(0, fn(a, b) (a + b))",
+    ),
+    (
+        r#"app.route("/") fn: "Hello""#,
+        r#"app.route("/", fn() "Hello")"#,
+    ),
+    (
+        "users.map fn: a.id .filter fn: a > 1",
+        "users.map(fn(a) a.id).filter(fn(a) (a > 1))",
+    ),
+    (
+        "users\n    .map fn: a.id\n    .filter fn: a > 1",
+        "users.map(fn(a) a.id).filter(fn(a) (a > 1))",
+    ),
+    (
+        "users.map fn: a.profile.name",
+        "users.map(fn(a) a.profile.name)",
+    ),
+    (
+        "scores.map fn { a.score + 1 }",
+        "scores.map(fn(a) { (a.score + 1) })",
+    ),
+    (
+        "users.map fn(user) { user.name }",
+        "users.map(fn(user) { user.name })",
+    ),
+    (
+        "users.map fn { a.id }.len()",
+        "users.map(fn(a) { a.id }).len()",
+    ),
+    ("run fn: 1", "run(fn() 1)"),
+    (
+        "items.each fn: a.tags.map fn: a.len()",
+        "items.each(fn(a) a.tags.map(fn(a) a.len()))",
+    ),
+    (
+        "let add = fn(x, y) { x + y }",
+        "let add = fn(x, y) { (x + y) }",
+    ),
+    ("pair.swap fn: b", "pair.swap(fn(_, b) b)"),
+];
+
+// Subject ; Config calls (Kap 5.4) against `fn request` below. The checker
+// puts config arguments in declaration order and marks the defaults.
+const REQUEST: &str = r#"
+fn request(url: String; timeout: i32 = 30, method: String = "GET") -> String {
+    method + " " + url
+}
+"#;
+
+const CONFIG_CASES: &[(&str, &str)] = &[
+    (
+        r#"request("/")"#,
+        r#"request("/"; timeout: default, method: default)"#,
+    ),
+    (
+        r#"request("/"; method: "POST")"#,
+        r#"request("/"; timeout: default, method: "POST")"#,
+    ),
+    (
+        r#"request("/"; method: "POST", timeout: 5)"#,
+        r#"request("/"; timeout: 5, method: "POST")"#,
+    ),
+];
+
+const CONFIG_ERRORS: &[(&str, &str)] = &[
+    (
+        r#"request("/"; 5)"#,
+        "Positional argument after `;` in call to `request`",
+    ),
+    (
+        r#"request("/"; retries: 3)"#,
+        "Unknown config argument `retries` for `request`",
+    ),
+    (
+        r#"request("/"; url: "/")"#,
+        "`url` is a subject argument of `request` and goes before `;`",
+    ),
+    (
+        r#"request("/"; timeout: 1, timeout: 2)"#,
+        "Config argument `timeout` is passed twice in call to `request`",
+    ),
+    (
+        r#"println("hi"; timeout: 1)"#,
+        "`println` takes no config arguments",
+    ),
+];
+
+// Nullable types (Kap 2.3 / 3.5). `?.` renders as `?.` for a plain member
+// and `?.!` where the checker found the member itself nullable.
+const PROFILE: &str = r#"
+struct User {
+    name: String,
+    profile: Profile?,
+}
+
+struct Profile {
+    bio: String?,
+}
+"#;
+
+const NULLABLE_CASES: &[(&str, &str)] = &[
+    ("let nick: String? = null", "let nick = null"),
+    (r#"let nick: String? = "Ada""#, r#"let nick = Some("Ada")"#),
+    ("let total: i32? = 1 + 2", "let total = Some((1 + 2))"),
+    ("user?.name", "user?.name"),
+    ("user?.profile?.bio", "user?.!profile?.!bio"),
+    (
+        r#"user?.profile?.bio ?? "none""#,
+        r#"(user?.!profile?.!bio ?? "none")"#,
+    ),
+    ("a ?? b ?? c", "(a ?? (b ?? c))"),
+    ("a ?? 1 + 2", "(a ?? (1 + 2))"),
+    ("a ?? 0 > 1", "((a ?? 0) > 1)"),
+    ("nullable", "nullable"),
+];
+
+// Errors (Kap 7.1). Each case is the body of a function declaring
+// `throws IoError, NetworkError`; calls whose errors bubble up render as
+// `call?`, a caught call stays as written.
+const FETCH: &str = r#"
+fn read(path: String) throws IoError -> String {
+    path
+}
+
+fn fetch_config() throws IoError, NetworkError -> String {
+    read("config.txt")
+}
+"#;
+
+const THROWS_CASES: &[(&str, &str)] = &[
+    ("fetch_config()", "fetch_config()?"),
+    ("fetch_config().len()", "fetch_config()?.len()"),
+    (
+        r#"let content = fetch_config() catch { "default" }"#,
+        r#"let content = fetch_config() catch { "default" }"#,
+    ),
+    (
+        "read(fetch_config()) catch { error }",
+        "read(fetch_config()?) catch { error }",
+    ),
+    ("throw NetworkError::Timeout", "throw NetworkError::Timeout"),
+];
+
+// The same against `fn main`, which declares no `throws`.
+const THROWS_ERRORS: &[(&str, &str)] = &[
+    (
+        "fetch_config()",
+        "`fetch_config` can throw `IoError`, which `main` does not declare",
+    ),
+    (
+        "throw IoError::Missing",
+        "`throw` in `main`, which does not declare `throws`",
+    ),
+    (
+        r#"println("hi") catch { 0 }"#,
+        "`catch` in `main` needs a call to a function that throws",
+    ),
+    (
+        "let load = fn: read(a)",
+        "`read` throws inside a lambda in `main`; handle its errors with `catch`",
+    ),
+];
+
+#[test]
+fn test_lambda_corpus() {
+    for (body, expected) in CASES {
+        let block = main_body(body, "");
+        let last = block.stmts.last().expect("empty body");
+        assert_eq!(render_stmt(last), *expected, "source: {:?}", body);
+    }
+}
+
+// Precedence climbing (Kap 2 / 3.5): each case is the body of `fn main` and
+// the fully parenthesised expression it parses to.
+const PRECEDENCE_CASES: &[(&str, &str)] = &[
+    ("1 + 2 * 3", "(1 + (2 * 3))"),
+    ("1 * 2 + 3", "((1 * 2) + 3)"),
+    ("1 - 2 - 3", "((1 - 2) - 3)"),
+    ("8 / 4 % 3", "((8 / 4) % 3)"),
+    ("a ?? b ?? c", "(a ?? (b ?? c))"),
+    ("a ?? b + 1", "(a ?? (b + 1))"),
+    ("a < b ?? c", "(a < (b ?? c))"),
+    ("a == b && c != d", "((a == b) && (c != d))"),
+    ("!a && b || c", "(((!a) && b) || c)"),
+    ("-a * b", "((-a) * b)"),
+    ("(a + b) * c", "((a + b) * c)"),
+    ("a - (b - c)", "(a - (b - c))"),
+    ("!(x < y)", "(!(x < y))"),
+    ("(a ?? b) + 1", "((a ?? b) + 1)"),
+    ("-(a + b) * c", "((-(a + b)) * c)"),
+];
+
+#[test]
+fn test_precedence() {
+    for (body, expected) in PRECEDENCE_CASES {
+        let block = main_body(body, "");
+        let last = block.stmts.last().expect("empty body");
+        assert_eq!(render_stmt(last), *expected, "source: {:?}", body);
+    }
+}
+
+// Kap 8.2: every spelling of `spawn`, with `move` recorded where it is
+// written. Rendered as `spawn [move] <body>`.
+const SPAWN_CASES: &[(&str, &str)] = &[
+    ("spawn({ work() })", "spawn { work() }"),
+    ("spawn(move { work() })", "spawn move { work() }"),
+    ("spawn fn: work()", "spawn work()"),
+    ("spawn fn { work() }", "spawn { work() }"),
+    ("spawn move fn: work()", "spawn move work()"),
+    ("spawn move fn { work() }", "spawn move { work() }"),
+];
+
+#[test]
+fn test_spawn() {
+    for (body, expected) in SPAWN_CASES {
+        let block = main_body(body, "fn work() {\n}\n");
+        let last = block.stmts.last().expect("empty body");
+        assert_eq!(render_stmt(last), *expected, "source: {:?}", body);
+    }
+}
+
+#[test]
+fn test_config_args() {
+    for (body, expected) in CONFIG_CASES {
+        let block = main_body(body, REQUEST);
+        assert_eq!(
+            render_stmt(&block.stmts[0]),
+            *expected,
+            "source: {:?}",
+            body
+        );
+    }
+
+    for (body, expected) in CONFIG_ERRORS {
+        let error = main_error(body, REQUEST);
+        assert!(error.contains(expected), "{:?} reported {:?}", body, error);
+    }
+}
+
+#[test]
+fn test_nullable_corpus() {
+    for (body, expected) in NULLABLE_CASES {
+        let block = main_body(body, PROFILE);
+        assert_eq!(
+            render_stmt(&block.stmts[0]),
+            *expected,
+            "source: {:?}",
+            body
+        );
+    }
+
+    let program = parse_to_ast(PROFILE).expect("Failed to parse");
+    let Item::Struct { fields, .. } = &program.items[0] else {
+        panic!("Expected struct User");
+    };
+    assert!(!fields[0].ty.nullable);
+    assert!(fields[1].ty.nullable);
+}
+
+#[test]
+fn test_config_params_need_defaults() {
+    let source = "fn request(url: String = \"/\"; timeout: i32) {\n}\n";
+    let mut program = parse_to_ast(source).expect("Failed to parse");
+    let error = check_program(&mut program).unwrap_err().to_string();
+    assert!(error.contains("Default value on subject parameter `url` of `request`"));
+    assert!(error.contains("Config parameter `timeout` of `request` needs a default value"));
+}
+
+#[test]
+fn test_throws_corpus() {
+    for (body, expected) in THROWS_CASES {
+        let block = checked_body(&format!(
+            "fn load() throws IoError, NetworkError {{\n    {}\n}}\n{}",
+            body, FETCH
+        ));
+        assert_eq!(
+            render_stmt(&block.stmts[0]),
+            *expected,
+            "source: {:?}",
+            body
+        );
+    }
+
+    for (body, expected) in THROWS_ERRORS {
+        let error = main_error(body, FETCH);
+        assert!(error.contains(expected), "{:?} reported {:?}", body, error);
+    }
+
+    let program = parse_to_ast(FETCH).expect("Failed to parse");
+    let Item::Fn { throws, .. } = &program.items[1] else {
+        panic!("Expected fn fetch_config");
+    };
+    let names: Vec<String> = throws.iter().map(|t| t.name.to_string()).collect();
+    assert_eq!(names, ["IoError", "NetworkError"]);
+}
+
+// Kap 4.1: a struct names its generic parameters and fields; fields may be
+// `pub` and the list may end with a comma.
+#[test]
+fn test_struct_definitions() {
+    let source = "struct Pair[T] {\n    pub first: T,\n    second: Vec[T?],\n}\n";
+    let program = parse_to_ast(source).expect("Failed to parse");
+    let Item::Struct {
+        name,
+        generics,
+        fields,
+        ..
+    } = &program.items[0]
+    else {
+        panic!("Expected a struct");
+    };
+    assert_eq!(name.to_string(), "Pair");
+    let generics: Vec<String> = generics.iter().map(|g| g.name.to_string()).collect();
+    assert_eq!(generics, ["T"]);
+    let fields: Vec<(String, String, bool)> = fields
+        .iter()
+        .map(|f| (f.name.to_string(), render_type(&f.ty), f.is_pub))
+        .collect();
+    assert_eq!(
+        fields,
+        [
+            ("first".to_string(), "T".to_string(), true),
+            ("second".to_string(), "Vec[T?]".to_string(), false),
+        ]
+    );
+}
+
+// Kap 4.7: an impl of a trait has to provide every method without a default.
+#[test]
+fn test_trait_impls() {
+    let shape = "trait Shape {\n    fn area(&self) -> i32\n    fn name(&self) -> String {\n        \"shape\".to_string()\n    }\n}\n\nstruct Square {\n    side: i32\n}\n";
+    let complete = format!(
+        "{}\nimpl Shape for Square {{\n    fn area(&self) -> i32 {{\n        self.side * self.side\n    }}\n}}\n",
+        shape
+    );
+    let mut program = parse_to_ast(&complete).expect("Failed to parse");
+    check_program(&mut program).expect("checker rejected a complete impl");
+
+    let partial = format!("{}\nimpl Shape for Square {{}}\n", shape);
+    let mut program = parse_to_ast(&partial).expect("Failed to parse");
+    let error = check_program(&mut program).unwrap_err().to_string();
+    assert!(
+        error.contains("Missing trait method `area` in `impl Shape for Square`"),
+        "unexpected error: {}",
+        error
+    );
+}
+
+// Kap 4.4: a `match` on an enum names every variant, unless a catch-all arm
+// takes the rest. Guarded arms and arms with refutable fields don't count.
+const MESSAGE: &str =
+    "enum Message {\n    Quit,\n    Write(String),\n    Move { x: i32, y: i32 },\n}\n";
+
+const MATCH_CASES: &[(&str, Option<&str>)] = &[
+    ("Quit => 1, Write(text) => 2, Move { x, y } => 3", None),
+    ("Message::Quit => 1, _ => 2", None),
+    ("Quit => 1, other => 2", None),
+    (
+        "Quit => 1, Write(text) => 2",
+        Some("Non-exhaustive `match` on `Message`: missing `Move`"),
+    ),
+    (
+        "Quit => 1, Write(text) if text == \"\" => 2, Move { x, y } => 3",
+        Some("Non-exhaustive `match` on `Message`: missing `Write`"),
+    ),
+    (
+        "Write(\"hi\") => 1, Move { x: 0, y } => 2",
+        Some("Non-exhaustive `match` on `Message`: missing `Quit`, `Write`, `Move`"),
+    ),
+    (
+        "Quit | Move { x, y } => 1",
+        Some("Non-exhaustive `match` on `Message`: missing `Write`"),
+    ),
+];
+
+#[test]
+fn test_match_exhaustiveness() {
+    for (arms, expected) in MATCH_CASES {
+        let source = format!(
+            "{}\nfn handle(message: Message) -> i32 {{\n    match message {{\n        {}\n    }}\n}}\n",
+            MESSAGE, arms
+        );
+        let mut program =
+            parse_to_ast(&source).unwrap_or_else(|e| panic!("failed to parse {:?}: {}", arms, e));
+        let result = check_program(&mut program);
+        match expected {
+            None => result.unwrap_or_else(|e| panic!("checker rejected {:?}: {}", arms, e)),
+            Some(message) => {
+                let error = result.expect_err(arms).to_string();
+                assert!(error.contains(message), "arms {:?}: {}", arms, error);
+            }
+        }
+    }
+
+    // Bare variant names are qualified with their enum
+    let source = format!(
+        "{}\nfn handle(message: Message) -> i32 {{\n    match message {{\n        Quit => 1,\n        _ => 2\n    }}\n}}\n",
+        MESSAGE
+    );
+    let mut program = parse_to_ast(&source).expect("Failed to parse");
+    check_program(&mut program).expect("checker rejected the match");
+    let Item::Fn { body, .. } = &program.items[1] else {
+        panic!("Second item is not a function");
+    };
+    let Some(Stmt::Expr(Expr {
+        kind: ExprKind::Match { arms, .. },
+        ..
+    })) = body.stmts.last()
+    else {
+        panic!("Body does not end in a match");
+    };
+    let Pattern::Variant { path, .. } = &arms[0].pattern else {
+        panic!("`Quit` is not a variant pattern: {:?}", arms[0].pattern);
+    };
+    assert_eq!(render_path(path), "Message::Quit");
+}
+
+// Kap 4.4: a struct-like variant is built like a struct, by its path and
+// named fields, which the checker holds against the variant's.
+const VARIANT_ERRORS: &[(&str, &str)] = &[
+    (
+        "let step = Message::Move(x: 1)",
+        "Missing fields in `Message::Move(...)`: y",
+    ),
+    (
+        "let step = Message::Move(x: 1, y: 2, z: 3)",
+        "`Message::Move` has no field `z`",
+    ),
+    ("let step = Message::Jump(x: 1)", "`Jump` is not a struct"),
+];
+
+#[test]
+fn test_struct_variants() {
+    let body = main_body("let step = Message::Move(y: 2, x: 1)", MESSAGE);
+    let Some(Stmt::Let { value, .. }) = body.stmts.last() else {
+        panic!("Body does not end in a let");
+    };
+    assert!(
+        matches!(value.kind, ExprKind::StructLit { variant: true, .. }),
+        "not built as a variant: {:?}",
+        value.kind
+    );
+    for (body, expected) in VARIANT_ERRORS {
+        let error = main_error(body, MESSAGE);
+        assert!(error.contains(expected), "{:?} reported {:?}", body, error);
+    }
+}
+
+// Kap 1.3: comments go wherever whitespace does, block comments nest, and
+// `///` lines document the item, field, variant or trait method after them.
+const DOCUMENTED: &str = r#"/* header /* nested */ still the header */
+// a line comment
+
+/// A point on the plane.
+/// Both axes are integers.
+pub struct Point {
+    /// Horizontal.
+    x: i32, // trailing
+    /* leading */ y: i32,
+}
+
+/// Shapes.
+enum Shape {
+    /// Nothing to draw.
+    Empty,
+    Circle(i32),
+}
+
+/// Things with an area.
+trait Area {
+    /// Computes it.
+    fn area(&self) -> i32
+}
+
+/// Adds two numbers.
+fn add(a: i32, /* inline */ b: i32) -> i32 {
+    // a comment in the body
+    a /* between */ + b
+}
+"#;
+
+#[test]
+fn test_doc_comments() {
+    let program = parse_to_ast(DOCUMENTED).expect("Failed to parse");
+    let Item::Struct { docs, fields, .. } = &program.items[0] else {
+        panic!("Expected struct Point");
+    };
+    assert_eq!(docs, &["A point on the plane.", "Both axes are integers."]);
+    assert_eq!(fields[0].docs, ["Horizontal."]);
+    assert!(fields[1].docs.is_empty());
+    let Item::Enum { docs, variants, .. } = &program.items[1] else {
+        panic!("Expected enum Shape");
+    };
+    assert_eq!(docs, &["Shapes."]);
+    assert_eq!(variants[0].docs, ["Nothing to draw."]);
+    assert!(variants[1].docs.is_empty());
+    let Item::Trait { docs, methods, .. } = &program.items[2] else {
+        panic!("Expected trait Area");
+    };
+    assert_eq!(docs, &["Things with an area."]);
+    assert_eq!(methods[0].docs, ["Computes it."]);
+    let Item::Fn { docs, args, .. } = &program.items[3] else {
+        panic!("Expected fn add");
+    };
+    assert_eq!(docs, &["Adds two numbers."]);
+    assert_eq!(args.len(), 2);
+
+    // The docs are carried into Bridge IR
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let module =
+        parse_to_bridge(DOCUMENTED, &dir.join("documented.nika")).expect("Failed to lower");
+    let BridgeItem::Struct(point) = &module.items[0] else {
+        panic!("Expected struct Point");
+    };
+    assert_eq!(
+        point.docs,
+        ["A point on the plane.", "Both axes are integers."]
+    );
+    assert_eq!(point.fields[0].docs, ["Horizontal."]);
+    let BridgeItem::Enum(shape) = &module.items[1] else {
+        panic!("Expected enum Shape");
+    };
+    assert_eq!(shape.variants[0].docs, ["Nothing to draw."]);
+    let BridgeItem::Trait(area) = &module.items[2] else {
+        panic!("Expected trait Area");
+    };
+    assert_eq!(area.methods[0].docs, ["Computes it."]);
+    let BridgeItem::Function(add) = &module.items[3] else {
+        panic!("Expected fn add");
+    };
+    assert_eq!(add.docs, ["Adds two numbers."]);
+}
+
+// Kap 9.2: items and fields are private unless marked `pub`.
+#[test]
+fn test_visibility() {
+    let source = r#"
+struct Config {
+    port: i32
+}
+
+pub struct Server {
+    config: Config,
+    pub name: String,
+    publisher: String
+}
+
+pub enum Mode { Fast }
+
+trait Named {
+    fn name(&self) -> String
+}
+
+impl Server {
+    pub fn new() {
+    }
+
+    fn reset(&self) {
+    }
+}
+"#;
+    let mut program = parse_to_ast(source).expect("Failed to parse");
+    check_program(&mut program).expect("checker rejected visibility");
+
+    let visibility: Vec<bool> = program
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Struct { is_pub, .. }
+            | Item::Enum { is_pub, .. }
+            | Item::Trait { is_pub, .. } => Some(*is_pub),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(visibility, [false, true, true, false]);
+
+    let Item::Struct { fields, .. } = &program.items[1] else {
+        panic!("Expected struct Server");
+    };
+    let fields: Vec<(String, bool)> = fields
+        .iter()
+        .map(|f| (f.name.to_string(), f.is_pub))
+        .collect();
+    assert_eq!(
+        fields,
+        [
+            ("config".to_string(), false),
+            ("name".to_string(), true),
+            ("publisher".to_string(), false),
+        ]
+    );
+
+    let Item::Impl { methods, .. } = &program.items[4] else {
+        panic!("Expected impl Server");
+    };
+    let methods: Vec<bool> = methods
+        .iter()
+        .map(|m| matches!(m, Item::Fn { is_pub: true, .. }))
+        .collect();
+    assert_eq!(methods, [true, false]);
+
+    let source = format!(
+        "{}
+impl Named for Server {{
+    pub fn name(&self) -> String {{
+        self.name
+    }}
+}}
+",
+        source
+    );
+    let mut program = parse_to_ast(&source).expect("Failed to parse");
+    let error = check_program(&mut program).unwrap_err().to_string();
+    assert!(
+        error.contains("`pub` on `name` in `impl Named for Server`"),
+        "reported {:?}",
+        error
+    );
+}
+
+// Kap 9.1: imported files become nested modules; bad imports are reported.
+#[test]
+fn test_modules() {
+    let src = std::env::temp_dir().join(format!("nikaia_modules_{}/src", std::process::id()));
+    std::fs::create_dir_all(src.join("std")).expect("Failed to create src/std");
+    let files = [
+        (
+            "users.nika",
+            "pub struct User {\n    pub name: String\n}\n\npub fn greet(name: String) -> String {\n    \"Hello \" + name\n}\n\nfn secret() -> i32 {\n    42\n}\n",
+        ),
+        (
+            "std/http.nika",
+            "pub fn get(url: String) -> String {\n    url\n}\n",
+        ),
+        ("a.nika", "use b\n"),
+        ("b.nika", "use a\n"),
+    ];
+    for (file, source) in files {
+        std::fs::write(src.join(file), source).expect("Failed to write module");
+    }
+    let entry = src.join("main.nika");
+
+    let main = "use users::User\nuse std::http\n\nfn main() {\n    println(users::greet(\"Alice\"))\n    println(http::get(\"/\"))\n}\n";
+    let (mut program, _) = load_program(&entry, main).expect("Failed to load modules");
+    check_program(&mut program).expect("checker rejected modules");
+    let modules: Vec<String> = program
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Module { name, items, .. } => {
+                let children: Vec<String> = items
+                    .iter()
+                    .filter_map(|child| match child {
+                        Item::Module { name, .. } => Some(name.to_string()),
+                        _ => None,
+                    })
+                    .collect();
+                Some(format!("{}{:?}", name, children))
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(modules, ["users[]", "std[\"http\"]"]);
+
+    let cases = [
+        ("use users::Nobody", "module `users` has no item `Nobody`"),
+        (
+            "use billing::Invoice",
+            "Unresolved import `billing::Invoice`",
+        ),
+        ("use a", "Import cycle: a -> b -> a"),
+        ("use std::htp", "no module `std::htp` in Rust"),
+    ];
+    for (import, expected) in cases {
+        let error = load_program(&entry, import).unwrap_err().to_string();
+        assert!(
+            error.contains(expected),
+            "{:?} reported {:?}",
+            import,
+            error
+        );
+    }
+
+    let (mut program, _) = load_program(&entry, "use users::secret\n").expect("Failed to load");
+    let error = check_program(&mut program).unwrap_err().to_string();
+    assert!(
+        error.contains("`users::secret` is private to module `users`"),
+        "reported {:?}",
+        error
+    );
+}
+
+// Kap 9.2: private items and fields stay in their module, also when another
+// module names them through one of its imports.
+#[test]
+fn test_privacy() {
+    let src = std::env::temp_dir().join(format!("nikaia_privacy_{}/src", std::process::id()));
+    std::fs::create_dir_all(src.join("std")).expect("Failed to create src/std");
+    let files = [
+        (
+            "users.nika",
+            "pub struct User {\n    pub name: String,\n    email: String\n}\n\nimpl User {\n    pub fn greet(&self) -> String {\n        self.name\n    }\n\n    fn secret(&self) -> String {\n        self.email\n    }\n\n    fn helper() -> i32 {\n        1\n    }\n}\n\npub fn find() -> User {\n    User(name: \"Ada\", email: \"ada@example.com\")\n}\n",
+        ),
+        (
+            "std/http.nika",
+            "pub fn get(url: String) -> String {\n    url\n}\n\nfn connect() {\n}\n",
+        ),
+        (
+            "orders.nika",
+            "use std::http\n\npub fn load() {\n    http::connect()\n}\n",
+        ),
+    ];
+    for (file, source) in files {
+        std::fs::write(src.join(file), source).expect("Failed to write module");
+    }
+    let entry = src.join("main.nika");
+    // Members of the same name in `main` don't make `User`'s visible
+    let check = |import: &str, body: &str| {
+        let main = format!(
+            "use {}\n\nstruct Local {{\n    email: String\n}}\n\nimpl Local {{\n    fn secret(&self) -> String {{\n        self.email\n    }}\n}}\n\nfn main() {{\n    {}\n}}\n",
+            import, body
+        );
+        let (mut program, _) = load_program(&entry, &main).expect("Failed to load modules");
+        check_program(&mut program).map_err(|e| e.to_string())
+    };
+
+    check(
+        "users",
+        "let user = users::find()\n    let name = user.name",
+    )
+    .expect("`name` is pub");
+    check(
+        "users",
+        "let local = Local(email: \"ada@example.com\")\n    let email = local.email",
+    )
+    .expect("`Local` is declared in `main`");
+    check(
+        "users",
+        "let user = users::find()\n    let greeting = user.greet()",
+    )
+    .expect("`greet` is pub");
+    let cases = [
+        (
+            "users",
+            "let user = users::find()\n    let email = user.email",
+            "Field `email` of `User` is private to module `users`",
+        ),
+        (
+            "users",
+            "let user = users::find()\n    let email = user.secret()",
+            "Method `secret` of `User` is private to module `users`",
+        ),
+        (
+            "users",
+            "let one = users::User::helper()",
+            "Method `helper` of `User` is private to module `users`",
+        ),
+        (
+            "orders",
+            "orders::load()",
+            "`std::http::connect` is private to module `std::http`",
+        ),
+    ];
+    for (import, body, expected) in cases {
+        let error = check(import, body).unwrap_err();
+        assert!(error.contains(expected), "{:?} reported {:?}", body, error);
+    }
+}
+
+// Kap 9.2: functions are known by their module path, so two modules may
+// each have a `create` without the checker mixing up their signatures.
+#[test]
+fn test_module_functions() {
+    let src = std::env::temp_dir().join(format!("nikaia_module_fns_{}/src", std::process::id()));
+    std::fs::create_dir_all(&src).expect("Failed to create src");
+    let files = [
+        (
+            "users.nika",
+            "pub fn create(name: String; admin: bool = false) -> String {\n    name\n}\n",
+        ),
+        (
+            "orders.nika",
+            "pub fn create(id: i32) throws IoError -> i32 {\n    id\n}\n",
+        ),
+    ];
+    for (file, source) in files {
+        std::fs::write(src.join(file), source).expect("Failed to write module");
+    }
+    let entry = src.join("main.nika");
+    let check = |body: &str| {
+        let main = format!("use users\nuse orders\n\nfn main() {{\n    {}\n}}\n", body);
+        let (mut program, _) = load_program(&entry, &main).expect("Failed to load modules");
+        check_program(&mut program).map_err(|e| e.to_string())
+    };
+
+    check("users::create(\"Ada\"; admin: true)").expect("users::create takes admin");
+    let cases = [
+        (
+            "orders::create(1; admin: true)",
+            "`orders::create` takes no config arguments",
+        ),
+        (
+            "orders::create(1)",
+            "`orders::create` can throw `IoError`, which `main` does not declare",
+        ),
+    ];
+    for (body, expected) in cases {
+        let error = check(body).unwrap_err();
+        assert!(error.contains(expected), "{:?} reported {:?}", body, error);
+    }
+}
+
+// Kap 4.2: `User(...)` calls the anonymous constructor, `User(name: ...)`
+// names the fields, which only the declaring module may do for private ones.
+#[test]
+fn test_construction() {
+    let src = std::env::temp_dir().join(format!("nikaia_construction_{}/src", std::process::id()));
+    std::fs::create_dir_all(&src).expect("Failed to create src");
+    let users = "pub struct User {\n    pub name: String,\n    email: String\n}\n\nimpl User {\n    pub fn(name: String, email: String) -> User {\n        User(name: name, email: email)\n    }\n}\n";
+    std::fs::write(src.join("users.nika"), users).expect("Failed to write module");
+    let entry = src.join("main.nika");
+
+    let main = "use users::User\n\nstruct Point {\n    x: i32,\n    y: i32\n}\n\nfn main() {\n    let user = User(\"Alice\", \"alice@example.com\")\n    let point = Point(y: 2, x: 1)\n}\n";
+    let (mut program, _) = load_program(&entry, main).expect("Failed to load");
+    let Item::Module { items, .. } = &program.items[3] else {
+        panic!("Expected module users");
+    };
+    let Item::Impl { methods, .. } = &items[1] else {
+        panic!("Expected impl User");
+    };
+    assert!(matches!(&methods[0], Item::Fn { name, is_pub: true, .. } if name == "new"));
+
+    check_program(&mut program).expect("checker rejected construction");
+    let Item::Fn { body, .. } = &program.items[2] else {
+        panic!("Expected fn main");
+    };
+    let rendered: Vec<String> = body.stmts.iter().map(render_stmt).collect();
+    assert_eq!(
+        rendered,
+        [
+            "let user = User::new(\"Alice\", \"alice@example.com\")",
+            "let point = Point(y: 2, x: 1)",
+        ]
+    );
+
+    let cases = [
+        (
+            "let user = users::User(name: \"Alice\", email: \"a@b\")",
+            "`User` has private fields (email) and cannot be built field by field outside module `users`",
+        ),
+        ("let point = Point(1, 2)", "`Point` has no constructor"),
+        ("let point = Point(x: 1, z: 2)", "`Point` has no field `z`"),
+        ("let point = Point(x: 1)", "Missing fields in `Point(...)`: y"),
+    ];
+    for (stmt, expected) in cases {
+        let main = format!(
+            "use users::User\n\nstruct Point {{\n    x: i32,\n    y: i32\n}}\n\nfn main() {{\n    {}\n}}\n",
+            stmt
+        );
+        let (mut program, _) = load_program(&entry, &main).expect("Failed to load");
+        let error = check_program(&mut program).unwrap_err().to_string();
+        assert!(error.contains(expected), "{:?} reported {:?}", stmt, error);
+    }
+}
+
+// Kap 9.2: structs are known by their module path too, so `a::Item` and
+// `b::Item` keep their own fields, constructors and privacy.
+#[test]
+fn test_module_structs() {
+    let src =
+        std::env::temp_dir().join(format!("nikaia_module_structs_{}/src", std::process::id()));
+    std::fs::create_dir_all(&src).expect("Failed to create src");
+    let files = [
+        ("a.nika", "pub struct Item {\n    pub id: i32\n}\n"),
+        (
+            "b.nika",
+            "pub struct Item {\n    pub name: String,\n    qty: i32\n}\n\nimpl Item {\n    pub fn(name: String) -> Item {\n        Item(name: name, qty: 1)\n    }\n}\n",
+        ),
+    ];
+    for (file, source) in files {
+        std::fs::write(src.join(file), source).expect("Failed to write module");
+    }
+    let entry = src.join("main.nika");
+    let check = |body: &str| {
+        let main = format!("use a\nuse b\n\nfn main() {{\n    {}\n}}\n", body);
+        let (mut program, _) = load_program(&entry, &main).expect("Failed to load modules");
+        check_program(&mut program).map_err(|e| e.to_string())
+    };
+
+    check("let first = a::Item(id: 1)\n    let second = b::Item(\"bolt\")")
+        .expect("each Item keeps its own fields and constructor");
+    let cases = [
+        ("let item = a::Item(\"bolt\")", "`Item` has no constructor"),
+        (
+            "let item = a::Item(name: \"bolt\")",
+            "`Item` has no field `name`",
+        ),
+        (
+            "let item = b::Item(name: \"bolt\", qty: 2)",
+            "`Item` has private fields (qty) and cannot be built field by field outside module `b`",
+        ),
+    ];
+    for (body, expected) in cases {
+        let error = check(body).unwrap_err();
+        assert!(error.contains(expected), "{:?} reported {:?}", body, error);
+    }
+}
+
+// Error traces point at the callee of a propagating call and at the thrown value.
+#[test]
+fn test_throws_locations() {
+    let source = "fn fail() throws IoError {\n    throw IoError::Missing\n}\n\nfn load() throws IoError {\n    let x = 1\n    fail()\n    config.parse()\n}\n";
+    let program = parse_to_ast(source).expect("Failed to parse");
+    let map = SourceMap::new("main.nika", source);
+
+    let Item::Fn { body, .. } = &program.items[0] else {
+        panic!("Expected fn fail");
+    };
+    let Stmt::Expr(Expr {
+        kind: ExprKind::Throw { span, .. },
+        ..
+    }) = &body.stmts[0]
+    else {
+        panic!("Expected a throw");
+    };
+    assert_eq!(map.location(span.start), "main.nika:2:11");
+
+    let Item::Fn { body, .. } = &program.items[1] else {
+        panic!("Expected fn load");
+    };
+    let Stmt::Expr(Expr {
+        kind: ExprKind::Call { func, .. },
+        ..
+    }) = &body.stmts[1]
+    else {
+        panic!("Expected a call");
+    };
+    assert_eq!(map.location(func.span.start), "main.nika:7:5");
+    let Stmt::Expr(Expr {
+        kind: ExprKind::MethodCall { method, .. },
+        ..
+    }) = &body.stmts[2]
+    else {
+        panic!("Expected a method call");
+    };
+    assert_eq!(map.location(method.span.start), "main.nika:8:12");
+}
+
+// Every item, statement, expression, type and name records the bytes it was
+// parsed from.
+#[test]
+fn test_spans() {
+    let source = "fn add(a: i32) -> i32 {\n    a + 1\n}\n\nfn main() {\n    let total = add(39)\n    println(total)\n}\n";
+    let program = parse_to_ast(source).expect("Failed to parse");
+    let text = |span: &std::ops::Range<usize>| &source[span.clone()];
+
+    let Item::Fn {
+        name,
+        args,
+        body,
+        span,
+        ..
+    } = &program.items[0]
+    else {
+        panic!("Expected fn add");
+    };
+    assert_eq!(text(span), "fn add(a: i32) -> i32 {\n    a + 1\n}");
+    assert_eq!(text(&name.span), "add");
+    assert_eq!(text(&args[0].ty.span), "i32");
+    assert_eq!(text(&body.span), "{\n    a + 1\n}");
+    let Stmt::Expr(sum) = &body.stmts[0] else {
+        panic!("Expected an expression");
+    };
+    assert_eq!(text(&sum.span), "a + 1");
+
+    let Item::Fn { body, .. } = &program.items[1] else {
+        panic!("Expected fn main");
+    };
+    let Stmt::Let {
+        name, value, span, ..
+    } = &body.stmts[0]
+    else {
+        panic!("Expected a let");
+    };
+    assert_eq!(text(span), "let total = add(39)");
+    assert_eq!(text(&name.span), "total");
+    assert_eq!(text(&value.span), "add(39)");
+    let ExprKind::Call { args, .. } = &value.kind else {
+        panic!("Expected a call");
+    };
+    assert_eq!(text(&args[0].span), "39");
+    let Stmt::Expr(call) = &body.stmts[1] else {
+        panic!("Expected an expression");
+    };
+    assert_eq!(text(&call.span), "println(total)");
+}
+
+// The AST serializes with names as strings next to their spans, and reads back.
+#[test]
+fn test_ast_json() {
+    let source = "fn add(a: i32) -> i32 {\n    a + 1\n}\n";
+    let program = parse_to_ast(source).expect("Failed to parse");
+    let json = serde_json::to_value(&program).expect("Failed to serialize");
+    assert_eq!(
+        json["items"][0]["Fn"]["name"],
+        serde_json::json!({ "name": "add", "span": { "start": 3, "end": 6 } })
+    );
+
+    let back: Program = serde_json::from_value(json).expect("Failed to deserialize");
+    assert_eq!(format!("{:?}", back), format!("{:?}", program));
+
+    // Names are not Rust identifiers and may be Rust keywords
+    assert_eq!(Ident::new("match", 0..0), Ident::new("match", 4..9));
+}
+
+// Keywords are settled by the lexer: they never name anything, and a name
+// that starts with one is still a name.
+#[test]
+fn test_keywords() {
+    let tokens = tokenize("let letter = 1 // one");
+    let kinds: Vec<_> = tokens
+        .iter()
+        .filter(|token| !matches!(token.kind, TokenKind::Trivia(Trivia::Whitespace)))
+        .map(|token| (token.kind.clone(), token.span.clone()))
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            (TokenKind::Keyword(Keyword::Let), 0..3),
+            (TokenKind::Ident("letter".to_string()), 4..10),
+            (TokenKind::Punct("="), 11..12),
+            (TokenKind::Int(1), 13..14),
+            (TokenKind::Trivia(Trivia::Comment), 15..21),
+        ]
+    );
+
+    let source = "fn main() {\n    let nullable = null\n    let iffy = true\n}\n";
+    let program = parse_to_ast(source).expect("Failed to parse");
+    let Item::Fn { body, .. } = &program.items[0] else {
+        panic!("Expected fn main");
+    };
+    assert_eq!(
+        render_block(body),
+        "{ let nullable = null; let iffy = true }"
+    );
+
+    let error = parse_to_ast("fn main() {\n    let let = 1\n}\n").unwrap_err();
+    assert!(
+        error.to_string().contains("keyword `let`"),
+        "unexpected error: {}",
+        error
+    );
+}
+
+// Punctuation is lexed and matched by token parsers as well: `<=` is one
+// token, and syntax errors name the punctuation that was expected.
+#[test]
+fn test_punctuation() {
+    let kinds: Vec<_> = tokenize("a<=b")
+        .into_iter()
+        .map(|token| token.kind)
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            TokenKind::Ident("a".to_string()),
+            TokenKind::Punct("<="),
+            TokenKind::Ident("b".to_string()),
+        ]
+    );
+
+    for (source, expected) in [
+        (
+            "fn main() {\n    let x: i32 1\n}\n",
+            "byte 27: unexpected integer `1`, expected `[`, `?` or `=`",
+        ),
+        (
+            "fn main() {\n    run(\n}\n",
+            "byte 21: unexpected `}`, expected expression, `;` or `)`",
+        ),
+        (
+            "fn main(a: i32 {}\n",
+            "byte 15: unexpected `{`, expected `[`, `?`, `=`, `,`, `;` or `)`",
+        ),
+    ] {
+        let error = parse_to_ast(source).unwrap_err().to_string();
+        assert_eq!(error, expected, "source: {:?}", source);
+    }
+
+    let source = "fn main() {\n    match x {\n        _ => 1\n    }\n}\n";
+    let program = parse_to_ast(source).expect("Failed to parse");
+    let Item::Fn { body, .. } = &program.items[0] else {
+        panic!("Expected fn main");
+    };
+    let Stmt::Expr(Expr {
+        kind: ExprKind::Match { arms, .. },
+        ..
+    }) = &body.stmts[0]
+    else {
+        panic!("Expected a match");
+    };
+    assert!(matches!(arms[0].pattern, Pattern::Wildcard));
+}
+
+// A statement ends with its line: binary operators, assignments, `catch`,
+// indexing and trailing lambdas only continue an expression on the same line.
+const LINE_CASES: &[(&str, &str)] = &[
+    ("a > b", "{ (a > b) }"),
+    ("run fn: a > 1", "{ run(fn() (a > 1)) }"),
+    ("x = 1", "{ x = 1 }"),
+    ("x += 1", "{ x += 1 }"),
+    ("xs[0]", "{ xs[0] }"),
+    ("xs\n    [0]", "{ xs; [0] }"),
+    ("fetch() catch { 1 }", "{ fetch() catch { 1 } }"),
+    ("run fn: 1", "{ run(fn() 1) }"),
+    ("run\n    fn: 1", "{ run; fn() 1 }"),
+];
+
+const LINE_ERRORS: &[(&str, &str)] = &[
+    ("a\n    > b", "unexpected `>`"),
+    ("run fn: a\n    > 1", "unexpected `>`"),
+    ("x\n    = 1", "unexpected `=`"),
+    ("fetch()\n    catch { 1 }", "unexpected keyword `catch`"),
+    ("throw\n    x", "unexpected identifier `x`"),
+];
+
+#[test]
+fn test_line_breaks() {
+    for (body, expected) in LINE_CASES {
+        let source = format!("fn main() {{\n    {}\n}}\n", body);
+        let program =
+            parse_to_ast(&source).unwrap_or_else(|e| panic!("failed to parse {:?}: {}", body, e));
+        let Item::Fn { body: block, .. } = &program.items[0] else {
+            panic!("Top level item is not a function");
+        };
+        assert_eq!(render_block(block), *expected, "source: {:?}", body);
+    }
+    for (body, expected) in LINE_ERRORS {
+        let source = format!("fn main() {{\n    {}\n}}\n", body);
+        let error = parse_to_ast(&source).unwrap_err().to_string();
+        assert!(error.contains(expected), "{:?} reported {:?}", body, error);
+    }
+}
+
+// A broken statement or item becomes an error node; parsing resumes after it
+// and every error in the file is reported (Part II, Kap 10.6).
+#[test]
+fn test_recovery() {
+    let source = "fn main() {\n    let = 1\n    println(\"still here\")\n}\n\nstruct { a: Int }\n\nfn other() {}\n";
+    let (program, errors) = parse_with_diagnostics(source);
+    let map = SourceMap::new("main.nika", source);
+    let errors: Vec<String> = errors
+        .iter()
+        .map(|error| format!("{}: {}", map.location(error.span.start), error.message))
+        .collect();
+    assert_eq!(
+        errors,
+        vec![
+            "main.nika:2:9: unexpected `=`",
+            "main.nika:6:8: unexpected `{`"
+        ]
+    );
+
+    let Item::Fn { body, .. } = &program.items[0] else {
+        panic!("Expected fn main");
+    };
+    assert_eq!(render_block(body), "{ <error>; println(\"still here\") }");
+    let Item::Error { span, .. } = &program.items[1] else {
+        panic!("Expected an error item");
+    };
+    assert_eq!(&source[span.clone()], "struct { a: Int }");
+    assert!(matches!(&program.items[2], Item::Fn { name, .. } if name == "other"));
+
+    let error = parse_to_ast(source).unwrap_err();
+    assert_eq!(error.0.len(), 2);
+}
+
+// Syntax errors name what was expected and are shown with their source line
+// underlined; the JSON form carries the same data.
+#[test]
+fn test_error_messages() {
+    let source = "fn main() {\n    let = 1\n    let x =\n}\n";
+    let errors = parse_to_ast(source).unwrap_err();
+    let map = SourceMap::new("main.nika", source);
+    let report = errors.report(&map, source);
+    let rendered: Vec<String> = report.messages.iter().map(|m| m.to_string()).collect();
+    assert_eq!(
+        rendered,
+        vec![
+            "error: unexpected `=`, expected `mut` or identifier\n --> main.nika:2:9\n  |\n2 |     let = 1\n  |         ^",
+            "error: unexpected `}`, expected expression\n --> main.nika:4:1\n  |\n4 | }\n  | ^",
+        ]
+    );
+
+    let json = serde_json::to_value(&report.messages[0]).expect("Failed to serialize");
+    assert_eq!(json["file"], "main.nika");
+    assert_eq!(json["line"], 2);
+    assert_eq!(json["column"], 9);
+    assert_eq!(json["message"], "unexpected `=`");
+    assert_eq!(json["expected"], serde_json::json!(["`mut`", "identifier"]));
+    assert_eq!(json["source_line"], "    let = 1");
+    assert_eq!(json["rendered"], rendered[0].as_str());
+
+    // Columns count characters: `ü` is two bytes but one column
+    let source = "fn main() {\n    \"über\" let = 1\n}\n";
+    let errors = parse_to_ast(source).unwrap_err();
+    let report = errors.report(&SourceMap::new("main.nika", source), source);
+    assert_eq!(report.messages[0].column, 16);
+    assert_eq!(
+        report.messages[0].to_string(),
+        "error: unexpected `=`, expected `mut` or identifier\n --> main.nika:2:16\n  |\n2 |     \"über\" let = 1\n  |                ^"
+    );
+
+    let error = parse_to_ast("fn main() {\n").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "byte 12: unexpected end of input, expected `let`, expression or `}`"
+    );
+}
+
+// Parses and checks `source` and returns the body of its first item, a
+// function.
+fn checked_body(source: &str) -> Block {
+    let mut program =
+        parse_to_ast(source).unwrap_or_else(|e| panic!("failed to parse {:?}: {}", source, e));
+    check_program(&mut program).unwrap_or_else(|e| panic!("checker rejected {:?}: {}", source, e));
+    let Item::Fn { body, .. } = program.items.into_iter().next().expect("empty program") else {
+        panic!("Top level item is not a function");
+    };
+    body
+}
+
+// `body` as the body of `fn main`, followed by the items in `extra`.
+fn main_body(body: &str, extra: &str) -> Block {
+    checked_body(&format!("fn main() {{\n    {}\n}}\n{}", body, extra))
+}
+
+// What the checker reports for `body` as the body of `fn main`.
+fn main_error(body: &str, extra: &str) -> String {
+    let source = format!("fn main() {{\n    {}\n}}\n{}", body, extra);
+    let mut program =
+        parse_to_ast(&source).unwrap_or_else(|e| panic!("failed to parse {:?}: {}", source, e));
+    check_program(&mut program).expect_err(&source).to_string()
+}
+
+fn render_stmt(stmt: &Stmt) -> String {
+    match stmt {
+        Stmt::Let { name, value, .. } => format!("let {} = {}", name, render(value)),
+        Stmt::Assign { target, value, .. } => format!("{} = {}", render(target), render(value)),
+        Stmt::CompoundAssign {
+            target, op, value, ..
+        } => format!(
+            "{} {}= {}",
+            render(target),
+            binary_symbol(*op),
+            render(value)
+        ),
+        Stmt::Expr(expr) => render(expr),
+    }
+}
+
+fn render(expr: &Expr) -> String {
+    match &expr.kind {
+        ExprKind::LitInt(n) => n.to_string(),
+        ExprKind::LitStr(s) => format!("{:?}", s),
+        ExprKind::LitBool(b) => b.to_string(),
+        ExprKind::LitNull => "null".to_string(),
+        ExprKind::Variable(name) => name.to_string(),
+        ExprKind::Path(path) => render_path(path),
+        ExprKind::Field { expr, field } => format!("{}.{}", render(expr), field),
+        ExprKind::SafeField {
+            expr,
+            field,
+            nullable,
+        } => format!(
+            "{}?.{}{}",
+            render(expr),
+            if *nullable { "!" } else { "" },
+            field
+        ),
+        ExprKind::Call {
+            func, args, config, ..
+        } => format!(
+            "{}({}{})",
+            render(func),
+            render_args(args),
+            render_config(config)
+        ),
+        ExprKind::MethodCall {
+            receiver,
+            method,
+            args,
+            config,
+            ..
+        } => format!(
+            "{}.{}({}{})",
+            render(receiver),
+            method,
+            render_args(args),
+            render_config(config)
+        ),
+        ExprKind::Binary { op, left, right } => {
+            format!(
+                "({} {} {})",
+                render(left),
+                binary_symbol(*op),
+                render(right)
+            )
+        }
+        ExprKind::Unary { op, expr } => {
+            let symbol = match op {
+                UnOp::Neg => "-",
+                UnOp::Not => "!",
+            };
+            format!("({}{})", symbol, render(expr))
+        }
+        ExprKind::Lambda { params, body, .. } => {
+            let params: Vec<String> = params.iter().map(|p| p.to_string()).collect();
+            format!("fn({}) {}", params.join(", "), render(body))
+        }
+        ExprKind::Index { expr, index } => format!("{}[{}]", render(expr), render(index)),
+        ExprKind::List(items) => format!("[{}]", render_args(items)),
+        ExprKind::Block(block) => render_block(block),
+        ExprKind::Spawn { body, is_move } => format!(
+            "spawn {}{}",
+            if *is_move { "move " } else { "" },
+            render(body)
+        ),
+        ExprKind::TryCatch { expr, handler } => {
+            format!("{} catch {}", render(expr), render_block(handler))
+        }
+        ExprKind::Propagate(call) => format!("{}?", render(call)),
+        ExprKind::Nullable(value) => format!("Some({})", render(value)),
+        ExprKind::Throw { value, .. } => format!("throw {}", render(value)),
+        ExprKind::Error(_) => "<error>".to_string(),
+        ExprKind::StructLit { path, fields, .. } => {
+            let fields: Vec<String> = fields
+                .iter()
+                .map(|field| format!("{}: {}", field.name, render(&field.value)))
+                .collect();
+            format!("{}({})", render_path(path), fields.join(", "))
+        }
+        other => panic!("unexpected expression {:?}", other),
+    }
+}
+
+fn render_block(block: &Block) -> String {
+    let stmts: Vec<String> = block.stmts.iter().map(render_stmt).collect();
+    format!("{{ {} }}", stmts.join("; "))
+}
+
+fn render_type(ty: &Type) -> String {
+    let mut out = ty.name.to_string();
+    if !ty.generics.is_empty() {
+        let generics: Vec<String> = ty.generics.iter().map(render_type).collect();
+        out.push_str(&format!("[{}]", generics.join(", ")));
+    }
+    if ty.nullable {
+        out.push('?');
+    }
+    out
+}
+
+fn render_path(path: &[Ident]) -> String {
+    path.iter()
+        .map(|segment| segment.to_string())
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+fn render_args(args: &[Expr]) -> String {
+    args.iter().map(render).collect::<Vec<_>>().join(", ")
+}
+
+fn render_config(config: &[ConfigArg]) -> String {
+    if config.is_empty() {
+        return String::new();
+    }
+    let args: Vec<String> = config
+        .iter()
+        .map(|arg| {
+            let name = arg.name.as_ref().map(|n| n.to_string()).unwrap_or_default();
+            let value = arg.value.as_ref().map_or("default".to_string(), render);
+            format!("{}: {}", name, value)
+        })
+        .collect();
+    format!("; {}", args.join(", "))
+}
+
+fn binary_symbol(op: BinOp) -> &'static str {
+    match op {
+        BinOp::Or => "||",
+        BinOp::And => "&&",
+        BinOp::Eq => "==",
+        BinOp::Ne => "!=",
+        BinOp::Lt => "<",
+        BinOp::Le => "<=",
+        BinOp::Gt => ">",
+        BinOp::Ge => ">=",
+        BinOp::Coalesce => "??",
+        BinOp::Add | BinOp::Concat => "+",
+        BinOp::Sub => "-",
+        BinOp::Mul => "*",
+        BinOp::Div => "/",
+        BinOp::Rem => "%",
+    }
+}
@@ -14,30 +14,53 @@ use std::process::Command;
 
 use anyhow::{anyhow, Result};
 use bridge_ir::{
-    BridgeCall, BridgeExpr, BridgeFunction, BridgeItem, BridgeLetStmt, BridgeLiteral, BridgeModule,
-    BridgeStmt,
+    BridgeArg, BridgeArm, BridgeBinOp, BridgeBinary, BridgeBlock, BridgeCall, BridgeClosure,
+    BridgeExpr, BridgeField, BridgeFieldAccess, BridgeFunction, BridgeGenericParam, BridgeIf,
+    BridgeImpl, BridgeItem, BridgeLetStmt, BridgeList, BridgeLiteral, BridgeMatch,
+    BridgeMethodCall, BridgeModule, BridgePattern, BridgeReceiver, BridgeSafeAccess, BridgeSafeNav,
+    BridgeSpawn, BridgeStmt, BridgeThrow, BridgeTrait, BridgeTry, BridgeUnOp, BridgeVariant,
+    BridgeVariantData,
 };
 
 use rustc_ast::{
-    self as ast, Block, BlockCheckMode, Crate, Expr, ExprKind, Fn, FnHeader, FnRetTy, FnSig,
-    Generics, ItemKind, Local, LocalKind, MacCall, NodeId, Pat, PatKind, Path, Stmt, StmtKind,
-    StrStyle, Ty, TyKind, Visibility, VisibilityKind,
+    self as ast, AssignOpKind, BinOpKind, Block, BlockCheckMode, Crate, Expr, ExprKind, Fn,
+    FnHeader, FnRetTy, FnSig, ForLoopKind, Generics, ItemKind, Local, LocalKind, MacCall, NodeId,
+    Pat, PatKind, Path, RangeLimits, Stmt, StmtKind, Ty, TyKind, UnOp, VariantData,
+    Visibility, VisibilityKind,
 };
 
+use rustc_ast::attr::{self, AttrIdGenerator};
 use rustc_ast::token::{self, Lit as TokenLit, Token, TokenKind};
 use rustc_ast::tokenstream::{DelimSpan, TokenStream, TokenTree};
+use rustc_ast::visit::{self, Visitor};
 use rustc_data_structures::thin_vec::{thin_vec, ThinVec};
-use rustc_span::symbol::{Ident, Symbol};
-use rustc_span::{Span, DUMMY_SP};
+use rustc_span::symbol::{sym, Ident, Symbol};
+use rustc_span::DUMMY_SP;
 
 pub fn execute(bridge_module: &BridgeModule, output_path: &str) -> Result<()> {
-    let krate = lower_module(bridge_module)?;
+    // Symbols are interned in the session globals, which only live for this closure.
+    let rust_code = rustc_span::create_default_session_globals_then(|| -> Result<String> {
+        let mut krate = lower_module(bridge_module)?;
 
-    let mut rust_code = String::new();
-    for item in &krate.items {
-        rust_code.push_str(&rustc_ast_pretty::pprust::item_to_string(item));
-        rust_code.push('\n');
-    }
+        let mut rust_code = String::new();
+        if uses_helper(&krate, INDEX_ASSIGN) || uses_helper(&krate, INDEX_SLOT) {
+            rust_code.push_str(INDEX_ASSIGN_PRELUDE);
+        }
+        if uses_helper(&krate, SPAWN) {
+            rust_code.push_str(SPAWN_PRELUDE);
+            join_tasks_in_main(&mut krate)?;
+        }
+        if throws_anywhere(bridge_module) {
+            rust_code.push_str(&source_table(bridge_module));
+            rust_code.push_str(ERROR_PRELUDE);
+            add_source_consts(&mut krate.items, bridge_module)?;
+        }
+        for item in &krate.items {
+            rust_code.push_str(&rustc_ast_pretty::pprust::item_to_string(item));
+            rust_code.push('\n');
+        }
+        Ok(rust_code)
+    })?;
 
     let temp_file_path = format!("{}.rs", output_path);
     let mut file = File::create(&temp_file_path)?;
@@ -45,7 +68,9 @@ pub fn execute(bridge_module: &BridgeModule, output_path: &str) -> Result<()> {
 
     println!("Generated Rust source at: {}", temp_file_path);
 
+    // Edition 2018 paths: `::std` is Rust's, even next to a `mod std`
     let status = Command::new("rustc")
+        .arg("--edition=2021")
         .arg(&temp_file_path)
         .arg("-o")
         .arg(output_path)
@@ -58,6 +83,252 @@ pub fn execute(bridge_module: &BridgeModule, output_path: &str) -> Result<()> {
     Ok(())
 }
 
+const INDEX_ASSIGN: &str = "nikaia_index_assign";
+const INDEX_SLOT: &str = "nikaia_index_slot";
+
+// Rust has no `IndexMut` for maps, so `scores["Player1"] = 100` is emitted as a
+// method call that inserts into maps and overwrites vector elements, and
+// `scores["Player1"] += 1` updates the slot the other method returns. Map keys
+// convert, so a `&str` literal indexes a `HashMap<String, _>`.
+const INDEX_ASSIGN_PRELUDE: &str = "\
+trait NikaiaIndexAssign<Q, V> {
+    fn nikaia_index_assign(&mut self, key: Q, value: V);
+    fn nikaia_index_slot(&mut self, key: Q) -> &mut V;
+}
+impl<T> NikaiaIndexAssign<usize, T> for Vec<T> {
+    fn nikaia_index_assign(&mut self, key: usize, value: T) {
+        self[key] = value;
+    }
+    fn nikaia_index_slot(&mut self, key: usize) -> &mut T {
+        &mut self[key]
+    }
+}
+impl<K: std::hash::Hash + Eq, V, Q: Into<K>> NikaiaIndexAssign<Q, V>
+    for std::collections::HashMap<K, V>
+{
+    fn nikaia_index_assign(&mut self, key: Q, value: V) {
+        self.insert(key.into(), value);
+    }
+    fn nikaia_index_slot(&mut self, key: Q) -> &mut V {
+        self.get_mut(&key.into()).expect(\"key not found in map\")
+    }
+}
+";
+
+const SPAWN: &str = "nikaia_spawn";
+
+// `spawn` runs its body on a new thread. `main` holds a `NikaiaTasks` guard
+// that joins every task before the process exits, so no task is cut short.
+const SPAWN_PRELUDE: &str = "\
+static NIKAIA_TASKS: std::sync::Mutex<Vec<std::thread::JoinHandle<()>>> =
+    std::sync::Mutex::new(Vec::new());
+fn nikaia_spawn<F: FnOnce() + Send + 'static>(task: F) {
+    let handle = std::thread::spawn(task);
+    NIKAIA_TASKS.lock().unwrap().push(handle);
+}
+struct NikaiaTasks;
+impl Drop for NikaiaTasks {
+    fn drop(&mut self) {
+        // Tasks may spawn further tasks, so drain until none are left.
+        loop {
+            let handles = std::mem::take(&mut *NIKAIA_TASKS.lock().unwrap());
+            if handles.is_empty() {
+                break;
+            }
+            for handle in handles {
+                let _ = handle.join();
+            }
+        }
+    }
+}
+";
+
+const THROW: &str = "nikaia_throw";
+const VIA: &str = "nikaia_via";
+
+// Kap 7.1: a function that `throws E` returns `Result<T, NikaiaError<E>>`. The
+// error remembers the type that was thrown, where, and every propagating call
+// it passed on the way out; its `Display` prints that chain. Locations are a
+// source file, the `NIKAIA_SOURCE` of the module the code is in, and a byte
+// offset, resolved against the file's line table in `NIKAIA_SOURCES` only
+// when the error is printed. Columns count characters, as the checker's do.
+const ERROR_PRELUDE: &str = "\
+pub struct NikaiaError<E> {
+    error: E,
+    name: &'static str,
+    thrown_at: (&'static str, usize),
+    via: Vec<(&'static str, &'static str, usize)>,
+}
+fn nikaia_throw<V, E: From<V>>(error: V, file: &'static str, offset: usize) -> NikaiaError<E> {
+    let name = std::any::type_name::<V>().rsplit(\"::\").next().unwrap_or_default();
+    NikaiaError { error: E::from(error), name, thrown_at: (file, offset), via: Vec::new() }
+}
+impl<E> NikaiaError<E> {
+    fn nikaia_via<F: From<E>>(self, callee: &'static str, file: &'static str, offset: usize) -> NikaiaError<F> {
+        let mut via = self.via;
+        via.push((callee, file, offset));
+        NikaiaError { error: F::from(self.error), name: self.name, thrown_at: self.thrown_at, via }
+    }
+}
+impl<E> std::ops::Deref for NikaiaError<E> {
+    type Target = E;
+    fn deref(&self) -> &E {
+        &self.error
+    }
+}
+impl<E> std::fmt::Display for NikaiaError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let (file, offset) = self.thrown_at;
+        write!(f, \"error: {}\\n  thrown at {}\", self.name, nikaia_location(file, offset))?;
+        for (callee, file, offset) in &self.via {
+            write!(f, \"\\n  via {} at {}\", callee, nikaia_location(file, *offset))?;
+        }
+        Ok(())
+    }
+}
+fn nikaia_location(file: &str, offset: usize) -> String {
+    let (line_starts, multibyte_chars) = NIKAIA_SOURCES
+        .iter()
+        .find(|(name, ..)| *name == file)
+        .map(|(_, line_starts, multibyte_chars)| (*line_starts, *multibyte_chars))
+        .unwrap_or_default();
+    let line = line_starts.partition_point(|&start| start <= offset).max(1);
+    let start = line_starts.get(line - 1).copied().unwrap_or(0);
+    let extra: usize = multibyte_chars
+        .iter()
+        .filter(|(at, _)| (start..offset).contains(at))
+        .map(|(_, len)| len - 1)
+        .sum();
+    format!(\"{}:{}:{}\", file, line, offset - start - extra + 1)
+}
+";
+
+const SOURCE: &str = "NIKAIA_SOURCE";
+
+/// The line table and multi-byte characters of every source file in the
+/// module tree, which `nikaia_location` resolves offsets with.
+fn source_table(module: &BridgeModule) -> String {
+    fn collect(module: &BridgeModule, out: &mut Vec<String>) {
+        let source_map = &module.source_map;
+        if !source_map.file.is_empty() {
+            out.push(format!(
+                "({:?}, &{:?}, &{:?})",
+                source_map.file, source_map.line_starts, source_map.multibyte_chars
+            ));
+        }
+        for item in &module.items {
+            if let BridgeItem::Module(child) = item {
+                collect(child, out);
+            }
+        }
+    }
+    let mut sources = Vec::new();
+    collect(module, &mut sources);
+    format!(
+        "const NIKAIA_SOURCES: &[(&str, &[usize], &[(usize, usize)])] = &[{}];\n",
+        sources.join(", ")
+    )
+}
+
+/// Gives each module with throwing code `const NIKAIA_SOURCE: &str`, the
+/// file its error locations point into.
+fn add_source_consts(items: &mut ThinVec<Box<ast::Item>>, module: &BridgeModule) -> Result<()> {
+    if declares_throws(module) {
+        let file = lower_expr(&BridgeExpr::Literal(BridgeLiteral::String(
+            module.source_map.file.clone(),
+        )))?;
+        let kind = ItemKind::Const(Box::new(ast::ConstItem {
+            defaultness: ast::Defaultness::Final,
+            ident: Ident::from_str(SOURCE),
+            generics: Generics::default(),
+            ty: Box::new(lower_ty("&str")?),
+            rhs: Some(ast::ConstItemRhs::Body(Box::new(file))),
+            define_opaque: None,
+        }));
+        items.push(Box::new(mk_item(kind, inherited_vis())));
+    }
+    for child in module.items.iter().filter_map(|item| match item {
+        BridgeItem::Module(child) => Some(child),
+        _ => None,
+    }) {
+        for item in items.iter_mut() {
+            if let ItemKind::Mod(_, ident, ast::ModKind::Loaded(mod_items, ..)) = &mut item.kind {
+                if ident.name.as_str() == child.name {
+                    add_source_consts(mod_items, child)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Whether any function or method of `module` itself `throws`, so that it
+/// needs `NikaiaError`.
+fn declares_throws(module: &BridgeModule) -> bool {
+    module.items.iter().any(|item| match item {
+        BridgeItem::Function(func) => func.throws.is_some(),
+        BridgeItem::Impl(imp) => imp.methods.iter().any(|method| method.throws.is_some()),
+        BridgeItem::Trait(tr) => tr.methods.iter().any(|method| method.throws.is_some()),
+        _ => false,
+    })
+}
+
+/// Whether `module` or any module nested in it declares throwing code.
+fn throws_anywhere(module: &BridgeModule) -> bool {
+    declares_throws(module)
+        || module.items.iter().any(|item| match item {
+            BridgeItem::Module(child) => throws_anywhere(child),
+            _ => false,
+        })
+}
+
+/// Whether the lowered crate calls the prelude helper `name`, as a function or
+/// as a method.
+fn uses_helper(krate: &Crate, name: &str) -> bool {
+    struct Finder<'a>(&'a str, bool);
+    impl<'ast> Visitor<'ast> for Finder<'_> {
+        fn visit_expr(&mut self, expr: &'ast Expr) {
+            match &expr.kind {
+                ExprKind::MethodCall(call) => self.1 |= call.seg.ident.name.as_str() == self.0,
+                ExprKind::Call(func, _) => {
+                    if let ExprKind::Path(None, path) = &func.kind {
+                        self.1 |= path.segments.len() == 1
+                            && path.segments[0].ident.name.as_str() == self.0;
+                    }
+                }
+                _ => {}
+            }
+            visit::walk_expr(self, expr);
+        }
+    }
+    let mut finder = Finder(name, false);
+    visit::walk_crate(&mut finder, krate);
+    finder.1
+}
+
+/// Puts `let _nikaia_tasks = NikaiaTasks;` at the top of `main`.
+fn join_tasks_in_main(krate: &mut Crate) -> Result<()> {
+    for item in &mut krate.items {
+        let ItemKind::Fn(func) = &mut item.kind else {
+            continue;
+        };
+        if func.ident.name.as_str() != "main" {
+            continue;
+        }
+        if let Some(body) = &mut func.body {
+            let guard = lower_stmt(&BridgeStmt::Let(BridgeLetStmt {
+                name: "_nikaia_tasks".to_string(),
+                mutable: false,
+                ty: None,
+                init: Some(BridgeExpr::Path("NikaiaTasks".to_string())),
+                span: 0..0,
+            }))?;
+            body.stmts.insert(0, guard);
+        }
+    }
+    Ok(())
+}
+
 fn lower_module(module: &BridgeModule) -> Result<Crate> {
     let mut items = ThinVec::new();
     for item in &module.items {
@@ -84,38 +355,442 @@ fn lower_item(item: &BridgeItem) -> Result<Option<ast::Item>> {
             let ident = Ident::from_str(&func.name);
             let kind = ItemKind::Fn(Box::new(lower_fn(func, ident)?));
             Ok(Some(ast::Item {
-                attrs: ThinVec::new(),
+                attrs: lower_docs(&func.docs),
                 id: NodeId::from_u32(0),
                 kind,
-                vis: Visibility {
-                    kind: VisibilityKind::Public,
-                    span: DUMMY_SP,
-                    tokens: None,
+                vis: lower_vis(func.is_pub),
+                span: DUMMY_SP,
+                tokens: None,
+            }))
+        }
+        BridgeItem::Struct(strukt) => {
+            let mut fields = ThinVec::new();
+            for field in &strukt.fields {
+                fields.push(lower_field(field, lower_vis(field.is_pub))?);
+            }
+            let kind = ItemKind::Struct(
+                Ident::from_str(&strukt.name),
+                lower_generics(&strukt.generics)?,
+                VariantData::Struct {
+                    fields,
+                    recovered: ast::Recovered::No,
                 },
+            );
+            Ok(Some(ast::Item {
+                attrs: lower_docs(&strukt.docs),
+                id: NodeId::from_u32(0),
+                kind,
+                vis: lower_vis(strukt.is_pub),
+                span: DUMMY_SP,
+                tokens: None,
+            }))
+        }
+        BridgeItem::Enum(enm) => {
+            let mut variants = ThinVec::new();
+            for variant in &enm.variants {
+                variants.push(lower_variant(variant)?);
+            }
+            let kind = ItemKind::Enum(
+                Ident::from_str(&enm.name),
+                lower_generics(&enm.generics)?,
+                ast::EnumDef { variants },
+            );
+            Ok(Some(ast::Item {
+                attrs: lower_docs(&enm.docs),
+                id: NodeId::from_u32(0),
+                kind,
+                vis: lower_vis(enm.is_pub),
+                span: DUMMY_SP,
+                tokens: None,
+            }))
+        }
+        BridgeItem::Trait(tr) => {
+            let kind = ItemKind::Trait(Box::new(lower_trait(tr)?));
+            Ok(Some(ast::Item {
+                attrs: lower_docs(&tr.docs),
+                id: NodeId::from_u32(0),
+                kind,
+                vis: lower_vis(tr.is_pub),
+                span: DUMMY_SP,
+                tokens: None,
+            }))
+        }
+        BridgeItem::Impl(imp) => {
+            let kind = ItemKind::Impl(lower_impl(imp)?);
+            Ok(Some(ast::Item {
+                attrs: lower_docs(&imp.docs),
+                id: NodeId::from_u32(0),
+                kind,
+                vis: inherited_vis(),
                 span: DUMMY_SP,
                 tokens: None,
             }))
         }
-        _ => Ok(None),
+        BridgeItem::Use(import) => {
+            let tree = ast::UseTree {
+                prefix: lower_path(&import.path),
+                kind: ast::UseTreeKind::Simple(None),
+                span: DUMMY_SP,
+            };
+            Ok(Some(mk_item(ItemKind::Use(tree), inherited_vis())))
+        }
+        BridgeItem::Module(module) => {
+            let kind = ItemKind::Mod(
+                ast::Safety::Default,
+                Ident::from_str(&module.name),
+                ast::ModKind::Loaded(
+                    lower_mod_items(module)?,
+                    ast::Inline::Yes,
+                    Default::default(),
+                ),
+            );
+            // Privacy between Nikaia modules is the checker's business
+            Ok(Some(mk_item(kind, lower_vis(true))))
+        }
     }
 }
 
-fn lower_fn(func: &BridgeFunction, ident: Ident) -> Result<Fn> {
-    let sig = FnSig {
-        header: FnHeader::default(),
-        decl: Box::new(ast::FnDecl {
-            // P -> Box
-            inputs: ThinVec::new(),
-            output: FnRetTy::Default(DUMMY_SP),
-        }),
+/// Kap 9.1: a nested module sees the crate root's items (the preludes, the
+/// other modules) through `use crate::*;`. A project module named `std`
+/// re-exports Rust's `std`, so `std::process::exit` keeps working next to
+/// `std::http`.
+fn lower_mod_items(module: &BridgeModule) -> Result<ThinVec<Box<ast::Item>>> {
+    let mut items = thin_vec![Box::new(mk_item(
+        ItemKind::Use(glob_use(lower_path("crate"))),
+        inherited_vis()
+    ))];
+    if module.name == "std" {
+        let mut rust_std = lower_path("std");
+        rust_std
+            .segments
+            .insert(0, ast::PathSegment::path_root(DUMMY_SP));
+        items.push(Box::new(mk_item(
+            ItemKind::Use(glob_use(rust_std)),
+            lower_vis(true),
+        )));
+    }
+    for item in &module.items {
+        if let Some(ast_item) = lower_item(item)? {
+            items.push(Box::new(ast_item));
+        }
+    }
+    Ok(items)
+}
+
+fn glob_use(prefix: Path) -> ast::UseTree {
+    ast::UseTree {
+        prefix,
+        kind: ast::UseTreeKind::Glob,
         span: DUMMY_SP,
+    }
+}
+
+fn lower_trait(tr: &BridgeTrait) -> Result<ast::Trait> {
+    let mut items = ThinVec::new();
+    for method in &tr.methods {
+        let body = match &method.default {
+            Some(block) => Some(Box::new(lower_fn_body(
+                block,
+                method.ret_type.is_some(),
+                method.throws.is_some(),
+            )?)),
+            None => None,
+        };
+        let ret_type = result_type(method.ret_type.as_deref(), method.throws.as_deref());
+        let func = Fn {
+            defaultness: ast::Defaultness::Final,
+            generics: Generics::default(),
+            sig: lower_fn_sig(method.receiver, &method.args, ret_type.as_deref())?,
+            body,
+            contract: None,
+            define_opaque: None,
+            eii_impls: ThinVec::new(),
+            ident: Ident::from_str(&method.name),
+        };
+        items.push(Box::new(ast::AssocItem {
+            attrs: lower_docs(&method.docs),
+            id: NodeId::from_u32(0),
+            span: DUMMY_SP,
+            vis: inherited_vis(),
+            kind: ast::AssocItemKind::Fn(Box::new(func)),
+            tokens: None,
+        }));
+    }
+
+    Ok(ast::Trait {
+        constness: ast::Const::No,
+        safety: ast::Safety::Default,
+        is_auto: ast::IsAuto::No,
+        ident: Ident::from_str(&tr.name),
+        generics: lower_generics(&tr.generics)?,
+        bounds: Vec::new(),
+        items,
+    })
+}
+
+fn lower_impl(imp: &BridgeImpl) -> Result<ast::Impl> {
+    let mut items = ThinVec::new();
+    for method in &imp.methods {
+        let ident = Ident::from_str(&method.name);
+        // Methods of a trait impl take the trait's visibility; Rust rejects a `pub` on them.
+        let is_pub = method.is_pub && imp.trait_name.is_none();
+        items.push(Box::new(ast::AssocItem {
+            attrs: lower_docs(&method.docs),
+            id: NodeId::from_u32(0),
+            span: DUMMY_SP,
+            vis: lower_vis(is_pub),
+            kind: ast::AssocItemKind::Fn(Box::new(lower_fn(method, ident)?)),
+            tokens: None,
+        }));
+    }
+
+    let of_trait = match &imp.trait_name {
+        Some(name) => Some(Box::new(ast::TraitImplHeader {
+            defaultness: ast::Defaultness::Final,
+            safety: ast::Safety::Default,
+            polarity: ast::ImplPolarity::Positive,
+            trait_ref: ast::TraitRef {
+                path: lower_type_path(name)?,
+                ref_id: NodeId::from_u32(0),
+            },
+        })),
+        None => None,
+    };
+
+    Ok(ast::Impl {
+        generics: lower_generics(&imp.generics)?,
+        constness: ast::Const::No,
+        of_trait,
+        self_ty: Box::new(lower_ty(&imp.target)?),
+        items,
+    })
+}
+
+fn lower_variant(variant: &BridgeVariant) -> Result<ast::Variant> {
+    // Variant fields take the enum's visibility; Rust rejects a `pub` on them.
+    let data = match &variant.data {
+        BridgeVariantData::Unit => VariantData::Unit(NodeId::from_u32(0)),
+        BridgeVariantData::Tuple(types) => {
+            let mut fields = ThinVec::new();
+            for ty in types {
+                fields.push(ast::FieldDef {
+                    attrs: ThinVec::new(),
+                    id: NodeId::from_u32(0),
+                    span: DUMMY_SP,
+                    vis: inherited_vis(),
+                    safety: ast::Safety::Default,
+                    ident: None,
+                    ty: Box::new(lower_ty(ty)?),
+                    default: None,
+                    is_placeholder: false,
+                });
+            }
+            VariantData::Tuple(fields, NodeId::from_u32(0))
+        }
+        BridgeVariantData::Struct(bridge_fields) => {
+            let mut fields = ThinVec::new();
+            for field in bridge_fields {
+                fields.push(lower_field(field, inherited_vis())?);
+            }
+            VariantData::Struct {
+                fields,
+                recovered: ast::Recovered::No,
+            }
+        }
+    };
+
+    Ok(ast::Variant {
+        attrs: lower_docs(&variant.docs),
+        id: NodeId::from_u32(0),
+        span: DUMMY_SP,
+        vis: inherited_vis(),
+        ident: Ident::from_str(&variant.name),
+        data,
+        disr_expr: None,
+        is_placeholder: false,
+    })
+}
+
+/// Doc comments become `#[doc = "..."]` attributes, one per source line.
+fn lower_docs(docs: &[String]) -> ThinVec<ast::Attribute> {
+    let ids = AttrIdGenerator::new();
+    docs.iter()
+        .map(|line| {
+            attr::mk_attr_name_value_str(
+                &ids,
+                ast::AttrStyle::Outer,
+                ast::Safety::Default,
+                sym::doc,
+                Symbol::intern(line),
+                DUMMY_SP,
+            )
+        })
+        .collect()
+}
+
+/// Kap 9.2: `pub` items and fields stay `pub`, everything else is private.
+fn lower_vis(is_pub: bool) -> Visibility {
+    if is_pub {
+        Visibility {
+            kind: VisibilityKind::Public,
+            span: DUMMY_SP,
+            tokens: None,
+        }
+    } else {
+        inherited_vis()
+    }
+}
+
+fn inherited_vis() -> Visibility {
+    Visibility {
+        kind: VisibilityKind::Inherited,
+        span: DUMMY_SP,
+        tokens: None,
+    }
+}
+
+fn lower_generics(params: &[BridgeGenericParam]) -> Result<Generics> {
+    let mut generics = Generics::default();
+    for param in params {
+        let mut bounds = Vec::new();
+        for bound in &param.bounds {
+            bounds.push(ast::GenericBound::Trait(ast::PolyTraitRef::new(
+                ThinVec::new(),
+                lower_type_path(bound)?,
+                ast::TraitBoundModifiers::NONE,
+                DUMMY_SP,
+                ast::Parens::No,
+            )));
+        }
+        generics.params.push(ast::GenericParam {
+            id: NodeId::from_u32(0),
+            ident: Ident::from_str(&param.name),
+            attrs: ThinVec::new(),
+            bounds,
+            is_placeholder: false,
+            kind: ast::GenericParamKind::Type { default: None },
+            colon_span: None,
+        });
+    }
+    Ok(generics)
+}
+
+fn lower_field(field: &BridgeField, vis: Visibility) -> Result<ast::FieldDef> {
+    Ok(ast::FieldDef {
+        attrs: lower_docs(&field.docs),
+        id: NodeId::from_u32(0),
+        span: DUMMY_SP,
+        vis,
+        safety: ast::Safety::Default,
+        ident: Some(Ident::from_str(&field.name)),
+        ty: Box::new(lower_ty(&field.ty)?),
+        default: None,
+        is_placeholder: false,
+    })
+}
+
+/// Lowers a Bridge type string (`i32`, `&str`, `Vec<Box<T>>`, `std::fmt::Result`)
+/// into a `rustc_ast::Ty`.
+fn lower_ty(ty: &str) -> Result<Ty> {
+    let ty = ty.trim();
+    let kind = if let Some(inner) = ty.strip_prefix('&') {
+        let inner = inner.trim_start();
+        let (mutbl, inner) = match inner.strip_prefix("mut ") {
+            Some(rest) => (ast::Mutability::Mut, rest),
+            None => (ast::Mutability::Not, inner),
+        };
+        TyKind::Ref(
+            None,
+            ast::MutTy {
+                ty: Box::new(lower_ty(inner)?),
+                mutbl,
+            },
+        )
+    } else if ty == "()" {
+        TyKind::Tup(ThinVec::new())
+    } else {
+        TyKind::Path(None, lower_type_path(ty)?)
+    };
+
+    Ok(Ty {
+        id: NodeId::from_u32(0),
+        kind,
+        span: DUMMY_SP,
+        tokens: None,
+    })
+}
+
+fn lower_type_path(ty: &str) -> Result<Path> {
+    let Some(open) = ty.find('<') else {
+        return Ok(lower_path(ty));
     };
+    let inner = ty[open + 1..]
+        .strip_suffix('>')
+        .ok_or_else(|| anyhow!("Malformed type: {}", ty))?;
+
+    let mut args = ThinVec::new();
+    for arg in split_type_args(inner) {
+        args.push(ast::AngleBracketedArg::Arg(ast::GenericArg::Type(
+            Box::new(lower_ty(arg)?),
+        )));
+    }
+
+    let mut path = lower_path(&ty[..open]);
+    if let Some(last) = path.segments.last_mut() {
+        last.args = Some(Box::new(ast::GenericArgs::AngleBracketed(
+            ast::AngleBracketedArgs {
+                span: DUMMY_SP,
+                args,
+            },
+        )));
+    }
+    Ok(path)
+}
+
+/// Splits `A, B<C, D>` at the top-level commas only.
+fn split_type_args(args: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in args.char_indices() {
+        match c {
+            '<' | '(' => depth += 1,
+            '>' | ')' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(args[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    let last = args[start..].trim();
+    if !last.is_empty() {
+        parts.push(last);
+    }
+    parts
+}
+
+fn lower_path(path: &str) -> Path {
+    Path {
+        span: DUMMY_SP,
+        segments: path
+            .split("::")
+            .map(|segment| ast::PathSegment::from_ident(Ident::from_str(segment.trim())))
+            .collect(),
+        tokens: None,
+    }
+}
+
+fn lower_fn(func: &BridgeFunction, ident: Ident) -> Result<Fn> {
+    let ret_type = result_type(func.ret_type.as_deref(), func.throws.as_deref());
+    let sig = lower_fn_sig(func.receiver, &func.args, ret_type.as_deref())?;
+    let body = lower_fn_body(&func.body, func.ret_type.is_some(), func.throws.is_some())?;
 
     Ok(Fn {
         defaultness: ast::Defaultness::Final,
-        generics: Generics::default(),
+        generics: lower_generics(&func.generics)?,
         sig,
-        body: Some(Box::new(lower_block(&func.body)?)), // P -> Box
+        body: Some(Box::new(body)), // P -> Box
         contract: None,
         define_opaque: None,
         eii_impls: ThinVec::new(),
@@ -123,6 +798,114 @@ fn lower_fn(func: &BridgeFunction, ident: Ident) -> Result<Fn> {
     })
 }
 
+/// `T` of a function that `throws E` becomes `Result<T, NikaiaError<E>>`, with
+/// `()` when it returns nothing.
+fn result_type(ret_type: Option<&str>, throws: Option<&str>) -> Option<String> {
+    match throws {
+        Some(error) => Some(format!(
+            "Result<{}, NikaiaError<{}>>",
+            ret_type.unwrap_or("()"),
+            error
+        )),
+        None => ret_type.map(str::to_string),
+    }
+}
+
+/// Only functions that declare a return type hand their last expression back.
+/// One that `throws` hands it back as `Ok(value)`, or `Ok(())` when it returns
+/// nothing.
+fn lower_fn_body(body: &BridgeBlock, returns_value: bool, throws: bool) -> Result<Block> {
+    if !throws {
+        return if returns_value {
+            lower_value_block(body)
+        } else {
+            lower_block(body)
+        };
+    }
+    let mut body = body.clone();
+    if !returns_value {
+        let unit = BridgeExpr::Literal(BridgeLiteral::Unit);
+        body.stmts
+            .push(BridgeStmt::Expr(call_path("Ok", vec![unit])));
+    } else if let Some(BridgeStmt::Expr(value)) = body.stmts.last_mut() {
+        let inner = std::mem::replace(value, BridgeExpr::Continue);
+        *value = call_path("Ok", vec![inner]);
+    }
+    lower_value_block(&body)
+}
+
+fn lower_fn_sig(
+    receiver: Option<BridgeReceiver>,
+    args: &[BridgeArg],
+    ret_type: Option<&str>,
+) -> Result<FnSig> {
+    let mut inputs = ThinVec::new();
+    if let Some(receiver) = receiver {
+        inputs.push(lower_receiver(receiver));
+    }
+    for arg in args {
+        inputs.push(ast::Param {
+            attrs: ThinVec::new(),
+            ty: Box::new(lower_ty(&arg.ty)?),
+            pat: Box::new(lower_ident_pat(&arg.name, false)),
+            id: NodeId::from_u32(0),
+            span: DUMMY_SP,
+            is_placeholder: false,
+        });
+    }
+
+    let output = match ret_type {
+        Some(ty) => FnRetTy::Ty(Box::new(lower_ty(ty)?)),
+        None => FnRetTy::Default(DUMMY_SP),
+    };
+
+    Ok(FnSig {
+        header: FnHeader::default(),
+        decl: Box::new(ast::FnDecl { inputs, output }), // P -> Box
+        span: DUMMY_SP,
+    })
+}
+
+fn lower_receiver(receiver: BridgeReceiver) -> ast::Param {
+    let kind = match receiver {
+        BridgeReceiver::Value => ast::SelfKind::Value(ast::Mutability::Not),
+        BridgeReceiver::Ref => ast::SelfKind::Region(None, ast::Mutability::Not),
+        BridgeReceiver::RefMut => ast::SelfKind::Region(None, ast::Mutability::Mut),
+    };
+    ast::Param::from_self(
+        ThinVec::new(),
+        rustc_span::source_map::respan(DUMMY_SP, kind),
+        Ident::from_str("self"),
+    )
+}
+
+fn lower_ident_pat(name: &str, mutable: bool) -> Pat {
+    let mode = if mutable {
+        ast::BindingMode::MUT
+    } else {
+        ast::BindingMode::NONE
+    };
+    Pat {
+        id: NodeId::from_u32(0),
+        kind: PatKind::Ident(mode, Ident::from_str(name), None),
+        span: DUMMY_SP,
+        tokens: None,
+    }
+}
+
+/// Lowers a block whose last expression statement is its value (Spec 3.1).
+fn lower_value_block(block: &bridge_ir::BridgeBlock) -> Result<Block> {
+    let mut lowered = lower_block(block)?;
+    if let Some(last) = lowered.stmts.pop() {
+        let kind = match last.kind {
+            StmtKind::Semi(expr) => StmtKind::Expr(expr),
+            kind => kind,
+        };
+        lowered.stmts.push(Stmt { kind, ..last });
+    }
+    Ok(lowered)
+}
+
 fn lower_block(block: &bridge_ir::BridgeBlock) -> Result<Block> {
     let mut stmts = ThinVec::new();
     for stmt in &block.stmts {
@@ -142,18 +925,13 @@ fn lower_stmt(stmt: &BridgeStmt) -> Result<Stmt> {
     match stmt {
         BridgeStmt::Let(let_stmt) => {
             let local = Local {
-                pat: Box::new(Pat {
-                    // P -> Box
-                    id: NodeId::from_u32(0),
-                    kind: PatKind::Ident(
-                        ast::BindingMode::NONE,
-                        Ident::from_str(&let_stmt.name),
-                        None,
-                    ),
-                    span: DUMMY_SP,
-                    tokens: None,
-                }),
-                ty: None,
+                pat: Box::new(lower_ident_pat(&let_stmt.name, let_stmt.mutable)), // P -> Box
+                ty: let_stmt
+                    .ty
+                    .as_deref()
+                    .map(lower_ty)
+                    .transpose()?
+                    .map(Box::new),
                 kind: if let Some(init) = &let_stmt.init {
                     LocalKind::Init(Box::new(lower_expr(init)?)) // P -> Box
                 } else {
@@ -173,6 +951,53 @@ fn lower_stmt(stmt: &BridgeStmt) -> Result<Stmt> {
                 span: DUMMY_SP,
             })
         }
+        BridgeStmt::Assign(assign) => {
+            let value = Box::new(lower_expr(&assign.value)?);
+            let kind = match &assign.target {
+                BridgeExpr::Index(index) => ExprKind::MethodCall(Box::new(ast::MethodCall {
+                    seg: ast::PathSegment::from_ident(Ident::from_str(INDEX_ASSIGN)),
+                    receiver: Box::new(lower_expr(&index.expr)?),
+                    args: thin_vec![Box::new(lower_expr(&index.index)?), value],
+                    span: DUMMY_SP,
+                })),
+                target => ExprKind::Assign(Box::new(lower_expr(target)?), value, DUMMY_SP),
+            };
+            Ok(Stmt {
+                id: NodeId::from_u32(0),
+                kind: StmtKind::Semi(Box::new(mk_expr(kind))),
+                span: DUMMY_SP,
+            })
+        }
+        BridgeStmt::CompoundAssign(assign) => {
+            let value = if assign.op == BridgeBinOp::Concat {
+                borrowed_str(&assign.value)?
+            } else {
+                lower_expr(&assign.value)?
+            };
+            let target = match &assign.target {
+                // *scores.nikaia_index_slot("Player1") += 1
+                BridgeExpr::Index(index) => mk_expr(ExprKind::Unary(
+                    UnOp::Deref,
+                    Box::new(mk_expr(ExprKind::MethodCall(Box::new(ast::MethodCall {
+                        seg: ast::PathSegment::from_ident(Ident::from_str(INDEX_SLOT)),
+                        receiver: Box::new(lower_expr(&index.expr)?),
+                        args: thin_vec![Box::new(lower_expr(&index.index)?)],
+                        span: DUMMY_SP,
+                    })))),
+                )),
+                target => lower_expr(target)?,
+            };
+            let kind = ExprKind::AssignOp(
+                rustc_span::source_map::respan(DUMMY_SP, lower_assign_op(assign.op)?),
+                Box::new(target),
+                Box::new(value),
+            );
+            Ok(Stmt {
+                id: NodeId::from_u32(0),
+                kind: StmtKind::Semi(Box::new(mk_expr(kind))),
+                span: DUMMY_SP,
+            })
+        }
         BridgeStmt::Expr(expr) => {
             Ok(Stmt {
                 id: NodeId::from_u32(0),
@@ -187,6 +1012,7 @@ fn lower_expr(expr: &BridgeExpr) -> Result<Expr> {
     let kind = match expr {
         BridgeExpr::Literal(lit) => lower_lit_expr(lit)?,
         BridgeExpr::Variable(name) => ExprKind::Path(None, Path::from_ident(Ident::from_str(name))),
+        BridgeExpr::Path(path) => ExprKind::Path(None, lower_path(path)),
         BridgeExpr::Call(call) => {
             let func_name = match &*call.func {
                 BridgeExpr::Variable(name) => name.as_str(),
@@ -204,21 +1030,522 @@ fn lower_expr(expr: &BridgeExpr) -> Result<Expr> {
                 ExprKind::Call(Box::new(func), args) // P -> Box
             }
         }
+        BridgeExpr::MethodCall(call) => {
+            let mut args = ThinVec::new();
+            for arg in &call.args {
+                args.push(Box::new(lower_expr(arg)?));
+            }
+            ExprKind::MethodCall(Box::new(ast::MethodCall {
+                seg: ast::PathSegment::from_ident(Ident::from_str(&call.method)),
+                receiver: Box::new(lower_expr(&call.receiver)?),
+                args,
+                span: DUMMY_SP,
+            }))
+        }
+        BridgeExpr::Field(access) => ExprKind::Field(
+            Box::new(lower_expr(&access.expr)?),
+            Ident::from_str(&access.field),
+        ),
+        BridgeExpr::Index(index) => ExprKind::Index(
+            Box::new(lower_expr(&index.expr)?),
+            Box::new(lower_expr(&index.index)?),
+            DUMMY_SP,
+        ),
+        // Message::Move { x: 1, y: 2 } is built like a struct, by its path
+        BridgeExpr::StructLit(lit) | BridgeExpr::StructVariant(lit) => {
+            let mut fields = ThinVec::new();
+            for field in &lit.fields {
+                fields.push(ast::ExprField {
+                    ident: Ident::from_str(&field.name),
+                    expr: Box::new(lower_expr(&field.value)?),
+                    is_shorthand: false,
+                    attrs: ThinVec::new(),
+                    id: NodeId::from_u32(0),
+                    span: DUMMY_SP,
+                    is_placeholder: false,
+                });
+            }
+            ExprKind::Struct(Box::new(ast::StructExpr {
+                qself: None,
+                path: lower_path(&lit.name),
+                fields,
+                rest: ast::StructRest::None,
+            }))
+        }
+        BridgeExpr::If(if_expr) => lower_if(if_expr)?,
+        BridgeExpr::Block(block) => ExprKind::Block(Box::new(lower_value_block(block)?), None),
+        BridgeExpr::While(while_expr) => ExprKind::While(
+            Box::new(lower_expr(&while_expr.cond)?),
+            Box::new(lower_block(&while_expr.body)?),
+            None,
+        ),
+        BridgeExpr::Loop(loop_expr) => {
+            ExprKind::Loop(Box::new(lower_block(&loop_expr.body)?), None, DUMMY_SP)
+        }
+        BridgeExpr::For(for_expr) => ExprKind::ForLoop {
+            pat: Box::new(lower_ident_pat(&for_expr.var, false)),
+            iter: Box::new(lower_expr(&for_expr.iter)?),
+            body: Box::new(lower_block(&for_expr.body)?),
+            label: None,
+            kind: ForLoopKind::For,
+        },
+        BridgeExpr::Range(range) => ExprKind::Range(
+            Some(Box::new(lower_expr(&range.start)?)),
+            Some(Box::new(lower_expr(&range.end)?)),
+            if range.inclusive {
+                RangeLimits::Closed
+            } else {
+                RangeLimits::HalfOpen
+            },
+        ),
+        BridgeExpr::List(list) => lower_list(list)?,
+        BridgeExpr::Break(break_expr) => ExprKind::Break(
+            None,
+            break_expr
+                .value
+                .as_ref()
+                .map(|value| lower_expr(value).map(Box::new))
+                .transpose()?,
+        ),
+        BridgeExpr::Continue => ExprKind::Continue(None),
+        BridgeExpr::Binary(binary) if binary.op == BridgeBinOp::Coalesce => lower_coalesce(binary)?,
+        BridgeExpr::Binary(binary) if binary.op == BridgeBinOp::Concat => ExprKind::Binary(
+            rustc_span::source_map::respan(DUMMY_SP, BinOpKind::Add),
+            Box::new(owned_string(&binary.left)?),
+            Box::new(borrowed_str(&binary.right)?),
+        ),
+        BridgeExpr::Binary(binary) => ExprKind::Binary(
+            rustc_span::source_map::respan(DUMMY_SP, lower_bin_op(binary.op)),
+            Box::new(lower_expr(&binary.left)?),
+            Box::new(lower_expr(&binary.right)?),
+        ),
+        BridgeExpr::Match(match_expr) => {
+            let mut arms = ThinVec::new();
+            for arm in &match_expr.arms {
+                arms.push(ast::Arm {
+                    attrs: ThinVec::new(),
+                    pat: Box::new(lower_pattern(&arm.pattern)?),
+                    guard: arm
+                        .guard
+                        .as_ref()
+                        .map(lower_expr)
+                        .transpose()?
+                        .map(Box::new),
+                    body: Some(Box::new(lower_expr(&arm.body)?)),
+                    span: DUMMY_SP,
+                    id: NodeId::from_u32(0),
+                    is_placeholder: false,
+                });
+            }
+            ExprKind::Match(
+                Box::new(lower_expr(&match_expr.expr)?),
+                arms,
+                ast::MatchKind::Prefix,
+            )
+        }
+        BridgeExpr::Closure(closure) => lower_closure(closure)?,
+        BridgeExpr::Spawn(spawn) => lower_spawn(spawn)?,
+        BridgeExpr::SafeNav(nav) => lower_safe_nav(nav)?,
+        BridgeExpr::Try(try_expr) => lower_try(try_expr)?,
+        BridgeExpr::Throw(throw) => lower_throw(throw)?,
+        BridgeExpr::Unary(unary) => ExprKind::Unary(
+            match unary.op {
+                BridgeUnOp::Neg => UnOp::Neg,
+                BridgeUnOp::Not => UnOp::Not,
+            },
+            Box::new(lower_expr(&unary.expr)?),
+        ),
     };
 
-    Ok(Expr {
+    Ok(mk_expr(kind))
+}
+
+fn lower_pattern(pattern: &BridgePattern) -> Result<Pat> {
+    let kind = match pattern {
+        BridgePattern::Wildcard => PatKind::Wild,
+        BridgePattern::Binding(name) => return Ok(lower_ident_pat(name, false)),
+        BridgePattern::Literal(BridgeLiteral::Null) => PatKind::Path(None, lower_path("None")),
+        BridgePattern::Literal(lit) => PatKind::Expr(Box::new(mk_expr(lower_lit_expr(lit)?))),
+        BridgePattern::Range(range) => PatKind::Range(
+            Some(Box::new(mk_expr(lower_lit_expr(&BridgeLiteral::Int(
+                range.start,
+            ))?))),
+            Some(Box::new(mk_expr(lower_lit_expr(&BridgeLiteral::Int(
+                range.end,
+            ))?))),
+            rustc_span::source_map::respan(
+                DUMMY_SP,
+                if range.inclusive {
+                    ast::RangeEnd::Included(ast::RangeSyntax::DotDotEq)
+                } else {
+                    ast::RangeEnd::Excluded
+                },
+            ),
+        ),
+        BridgePattern::Path(path) => PatKind::Path(None, lower_path(path)),
+        BridgePattern::TupleVariant(path, items) => {
+            let mut pats = ThinVec::new();
+            for item in items {
+                pats.push(lower_pattern(item)?);
+            }
+            PatKind::TupleStruct(None, lower_path(path), pats)
+        }
+        BridgePattern::StructVariant(path, fields) => {
+            let mut pat_fields = ThinVec::new();
+            for field in fields {
+                pat_fields.push(ast::PatField {
+                    ident: Ident::from_str(&field.name),
+                    pat: Box::new(lower_pattern(&field.pattern)?),
+                    is_shorthand: matches!(&field.pattern, BridgePattern::Binding(b) if *b == field.name),
+                    attrs: ThinVec::new(),
+                    id: NodeId::from_u32(0),
+                    span: DUMMY_SP,
+                    is_placeholder: false,
+                });
+            }
+            PatKind::Struct(None, lower_path(path), pat_fields, ast::PatFieldsRest::None)
+        }
+        BridgePattern::Or(alternatives) => {
+            let mut pats = ThinVec::new();
+            for alternative in alternatives {
+                pats.push(lower_pattern(alternative)?);
+            }
+            PatKind::Or(pats)
+        }
+    };
+    Ok(Pat {
         id: NodeId::from_u32(0),
         kind,
         span: DUMMY_SP,
+        tokens: None,
+    })
+}
+
+fn mk_item(kind: ItemKind, vis: Visibility) -> ast::Item {
+    ast::Item {
         attrs: ThinVec::new(),
+        id: NodeId::from_u32(0),
+        kind,
+        vis,
+        span: DUMMY_SP,
         tokens: None,
+    }
+}
+
+fn mk_expr(kind: ExprKind) -> Expr {
+    Expr {
+        id: NodeId::from_u32(0),
+        kind,
+        span: DUMMY_SP,
+        attrs: ThinVec::new(),
+        tokens: None,
+    }
+}
+
+fn lower_bin_op(op: BridgeBinOp) -> BinOpKind {
+    match op {
+        BridgeBinOp::Add | BridgeBinOp::Concat => BinOpKind::Add,
+        // Lowered as a method call by `lower_coalesce`, never as a binary operator
+        BridgeBinOp::Coalesce => unreachable!("`??` has no Rust operator"),
+        BridgeBinOp::Sub => BinOpKind::Sub,
+        BridgeBinOp::Mul => BinOpKind::Mul,
+        BridgeBinOp::Div => BinOpKind::Div,
+        BridgeBinOp::Rem => BinOpKind::Rem,
+        BridgeBinOp::And => BinOpKind::And,
+        BridgeBinOp::Or => BinOpKind::Or,
+        BridgeBinOp::Eq => BinOpKind::Eq,
+        BridgeBinOp::Ne => BinOpKind::Ne,
+        BridgeBinOp::Lt => BinOpKind::Lt,
+        BridgeBinOp::Le => BinOpKind::Le,
+        BridgeBinOp::Gt => BinOpKind::Gt,
+        BridgeBinOp::Ge => BinOpKind::Ge,
+    }
+}
+
+fn lower_assign_op(op: BridgeBinOp) -> Result<AssignOpKind> {
+    match op {
+        BridgeBinOp::Add | BridgeBinOp::Concat => Ok(AssignOpKind::AddAssign),
+        BridgeBinOp::Sub => Ok(AssignOpKind::SubAssign),
+        BridgeBinOp::Mul => Ok(AssignOpKind::MulAssign),
+        BridgeBinOp::Div => Ok(AssignOpKind::DivAssign),
+        BridgeBinOp::Rem => Ok(AssignOpKind::RemAssign),
+        _ => Err(anyhow!("Operator {:?} has no compound assignment", op)),
+    }
+}
+
+// Concatenation: `"User: " + name` becomes `"User: ".to_string() + &name.to_string()`,
+// so any Display operand works. A nested concatenation is already an owned String.
+fn owned_string(expr: &BridgeExpr) -> Result<Expr> {
+    match expr {
+        BridgeExpr::Binary(binary) if binary.op == BridgeBinOp::Concat => lower_expr(expr),
+        _ => Ok(to_string_call(lower_expr(expr)?)),
+    }
+}
+
+fn borrowed_str(expr: &BridgeExpr) -> Result<Expr> {
+    match expr {
+        BridgeExpr::Literal(BridgeLiteral::String(_)) => lower_expr(expr),
+        _ => Ok(mk_expr(ExprKind::AddrOf(
+            ast::BorrowKind::Ref,
+            ast::Mutability::Not,
+            Box::new(to_string_call(lower_expr(expr)?)),
+        ))),
+    }
+}
+
+fn to_string_call(receiver: Expr) -> Expr {
+    mk_expr(ExprKind::MethodCall(Box::new(ast::MethodCall {
+        seg: ast::PathSegment::from_ident(Ident::from_str("to_string")),
+        receiver: Box::new(receiver),
+        args: ThinVec::new(),
+        span: DUMMY_SP,
+    })))
+}
+
+/// Lowers a conditional. Branches only yield their last expression when an
+/// `else` exists; a lone `if` stays a unit statement.
+fn lower_if(if_expr: &BridgeIf) -> Result<ExprKind> {
+    let cond = Box::new(lower_expr(&if_expr.cond)?);
+    let Some(else_branch) = &if_expr.else_branch else {
+        return Ok(ExprKind::If(
+            cond,
+            Box::new(lower_block(&if_expr.then_branch)?),
+            None,
+        ));
+    };
+    Ok(ExprKind::If(
+        cond,
+        Box::new(lower_value_block(&if_expr.then_branch)?),
+        Some(Box::new(lower_expr(else_branch)?)),
+    ))
+}
+
+/// `fn: a + b` becomes `|a, b| a + b`; rustc infers the parameter types from
+/// how the closure is used.
+fn lower_closure(closure: &BridgeClosure) -> Result<ExprKind> {
+    let mut inputs = ThinVec::new();
+    for param in &closure.params {
+        inputs.push(ast::Param {
+            attrs: ThinVec::new(),
+            ty: Box::new(Ty {
+                id: NodeId::from_u32(0),
+                kind: TyKind::Infer,
+                span: DUMMY_SP,
+                tokens: None,
+            }),
+            pat: Box::new(lower_ident_pat(param, false)),
+            id: NodeId::from_u32(0),
+            span: DUMMY_SP,
+            is_placeholder: false,
+        });
+    }
+    let body = lower_expr(&closure.body)?;
+    Ok(mk_closure(inputs, body, false))
+}
+
+// Kap 3.5: nullable values are `Option`s.
+
+/// `name ?? "Anonymous"` becomes `name.unwrap_or_else(|| "Anonymous".to_string())`.
+/// A string literal fallback is owned, like the strings it stands in for.
+fn lower_coalesce(binary: &BridgeBinary) -> Result<ExprKind> {
+    let fallback = match &*binary.right {
+        BridgeExpr::Literal(BridgeLiteral::String(_)) => to_string_call(lower_expr(&binary.right)?),
+        right => lower_expr(right)?,
+    };
+    Ok(ExprKind::MethodCall(Box::new(ast::MethodCall {
+        seg: ast::PathSegment::from_ident(Ident::from_str("unwrap_or_else")),
+        receiver: Box::new(lower_expr(&binary.left)?),
+        args: thin_vec![Box::new(mk_expr(mk_closure(
+            ThinVec::new(),
+            fallback,
+            false
+        )))],
+        span: DUMMY_SP,
+    })))
+}
+
+const SAFE_NAV_VALUE: &str = "_nikaia_value";
+
+/// `user?.profile` becomes `user.as_ref().map(|v| v.profile.clone())`, and
+/// `.and_then(..)` when the field or method result is nullable itself.
+/// Going through `as_ref` leaves `user` usable after the access.
+fn lower_safe_nav(nav: &BridgeSafeNav) -> Result<ExprKind> {
+    let value = Box::new(BridgeExpr::Variable(SAFE_NAV_VALUE.to_string()));
+    let access = match &nav.access {
+        BridgeSafeAccess::Field(field) => BridgeExpr::MethodCall(BridgeMethodCall {
+            receiver: Box::new(BridgeExpr::Field(BridgeFieldAccess {
+                expr: value,
+                field: field.clone(),
+                span: nav.span.clone(),
+            })),
+            method: "clone".to_string(),
+            args: Vec::new(),
+            span: nav.span.clone(),
+        }),
+        BridgeSafeAccess::MethodCall(method, args) => BridgeExpr::MethodCall(BridgeMethodCall {
+            receiver: value,
+            method: method.clone(),
+            args: args.clone(),
+            span: nav.span.clone(),
+        }),
+    };
+    let borrowed = BridgeExpr::MethodCall(BridgeMethodCall {
+        receiver: nav.expr.clone(),
+        method: "as_ref".to_string(),
+        args: Vec::new(),
+        span: nav.span.clone(),
+    });
+    let chained = BridgeExpr::MethodCall(BridgeMethodCall {
+        receiver: Box::new(borrowed),
+        method: if nav.nullable { "and_then" } else { "map" }.to_string(),
+        args: vec![BridgeExpr::Closure(BridgeClosure {
+            params: vec![SAFE_NAV_VALUE.to_string()],
+            body: Box::new(access),
+            span: nav.span.clone(),
+        })],
+        span: nav.span.clone(),
+    });
+    Ok(lower_expr(&chained)?.kind)
+}
+
+/// `spawn fn: ...` becomes `nikaia_spawn(move || { ... })`. Spawned tasks
+/// outlive the current function, so the closure always moves its captures
+/// (Kap 5.4); `is_move` only records what the source spelled out.
+fn lower_spawn(spawn: &BridgeSpawn) -> Result<ExprKind> {
+    let body = mk_expr(ExprKind::Block(Box::new(lower_block(&spawn.body)?), None));
+    let closure = mk_expr(mk_closure(ThinVec::new(), body, true));
+    let func = mk_expr(ExprKind::Path(None, lower_path(SPAWN)));
+    Ok(ExprKind::Call(Box::new(func), thin_vec![Box::new(closure)]))
+}
+
+/// A propagating call `read(path)` becomes
+/// `match read(path) { Ok(value) => value, Err(error) => return Err(error.nikaia_via("read", NIKAIA_SOURCE, 57)) }`,
+/// where 57 is the offset of the call in the module's source.
+fn lower_try(try_expr: &BridgeTry) -> Result<ExprKind> {
+    let callee = match &*try_expr.expr {
+        BridgeExpr::Call(call) => match &*call.func {
+            BridgeExpr::Variable(name) | BridgeExpr::Path(name) => name.clone(),
+            _ => "fn".to_string(),
+        },
+        BridgeExpr::MethodCall(call) => call.method.clone(),
+        _ => String::new(),
+    };
+    let arm = |variant: &str, binding: &str, body: BridgeExpr| BridgeArm {
+        pattern: BridgePattern::TupleVariant(
+            variant.to_string(),
+            vec![BridgePattern::Binding(binding.to_string())],
+        ),
+        guard: None,
+        body,
+        span: try_expr.span.clone(),
+    };
+    let via = BridgeExpr::MethodCall(BridgeMethodCall {
+        receiver: Box::new(BridgeExpr::Variable("error".to_string())),
+        method: VIA.to_string(),
+        args: vec![
+            BridgeExpr::Literal(BridgeLiteral::String(callee)),
+            BridgeExpr::Path(SOURCE.to_string()),
+            BridgeExpr::Literal(BridgeLiteral::Int(try_expr.span.start as i64)),
+        ],
+        span: try_expr.span.clone(),
+    });
+    let matched = BridgeExpr::Match(BridgeMatch {
+        expr: try_expr.expr.clone(),
+        arms: vec![
+            arm("Ok", "value", BridgeExpr::Variable("value".to_string())),
+            arm("Err", "error", via),
+        ],
+        span: try_expr.span.clone(),
+    });
+    let mut lowered = lower_expr(&matched)?.kind;
+    if let ExprKind::Match(_, arms, _) = &mut lowered {
+        if let Some(via) = arms[1].body.take() {
+            arms[1].body = Some(Box::new(return_err(*via)));
+        }
+    }
+    Ok(lowered)
+}
+
+/// `throw value` becomes `return Err(nikaia_throw(value, NIKAIA_SOURCE, 40))`,
+/// where 40 is the offset of the value in the module's source.
+fn lower_throw(throw: &BridgeThrow) -> Result<ExprKind> {
+    let error = lower_expr(&call_path(
+        THROW,
+        vec![
+            (*throw.value).clone(),
+            BridgeExpr::Path(SOURCE.to_string()),
+            BridgeExpr::Literal(BridgeLiteral::Int(throw.span.start as i64)),
+        ],
+    ))?;
+    Ok(return_err(error).kind)
+}
+
+fn return_err(error: Expr) -> Expr {
+    let err = mk_expr(ExprKind::Path(None, lower_path("Err")));
+    let value = mk_expr(ExprKind::Call(Box::new(err), thin_vec![Box::new(error)]));
+    mk_expr(ExprKind::Ret(Some(Box::new(value))))
+}
+
+fn call_path(path: &str, args: Vec<BridgeExpr>) -> BridgeExpr {
+    BridgeExpr::Call(BridgeCall {
+        func: Box::new(BridgeExpr::Path(path.to_string())),
+        args,
+        span: 0..0,
     })
 }
 
+fn mk_closure(inputs: ThinVec<ast::Param>, body: Expr, is_move: bool) -> ExprKind {
+    let capture_clause = if is_move {
+        ast::CaptureBy::Value { move_kw: DUMMY_SP }
+    } else {
+        ast::CaptureBy::Ref
+    };
+    ExprKind::Closure(Box::new(ast::Closure {
+        binder: ast::ClosureBinder::NotPresent,
+        capture_clause,
+        constness: ast::Const::No,
+        coroutine_kind: None,
+        movability: ast::Movability::Movable,
+        fn_decl: Box::new(ast::FnDecl {
+            inputs,
+            output: FnRetTy::Default(DUMMY_SP),
+        }),
+        body: Box::new(body),
+        fn_decl_span: DUMMY_SP,
+        fn_arg_span: DUMMY_SP,
+    }))
+}
+
+/// Lists are emitted as `Vec::from([a, b, c])`, which needs no macro tokens.
+fn lower_list(list: &BridgeList) -> Result<ExprKind> {
+    let mut items = ThinVec::new();
+    for item in &list.items {
+        items.push(Box::new(lower_expr(item)?));
+    }
+    let array = mk_expr(ExprKind::Array(items));
+    let func = mk_expr(ExprKind::Path(None, lower_path("Vec::from")));
+    Ok(ExprKind::Call(Box::new(func), thin_vec![Box::new(array)]))
+}
+
 fn lower_lit_expr(lit: &BridgeLiteral) -> Result<ExprKind> {
     let kind = match lit {
+        BridgeLiteral::Null => return Ok(ExprKind::Path(None, lower_path("None"))),
+        BridgeLiteral::Unit => return Ok(ExprKind::Tup(ThinVec::new())),
         // ExprKind::Lit takes token::Lit, not LitKind
         // wait, I need token::Lit here.
+        // Only patterns hold negative literals (`-1 => ...`); like rustc's
+        // parser, they are a negation of the magnitude
+        BridgeLiteral::Int(i) if *i < 0 => {
+            let magnitude = TokenLit::new(
+                token::Integer,
+                Symbol::intern(&i.unsigned_abs().to_string()),
+                None,
+            );
+            return Ok(ExprKind::Unary(
+                UnOp::Neg,
+                Box::new(mk_expr(ExprKind::Lit(magnitude))),
+            ));
+        }
         BridgeLiteral::Int(i) => {
             TokenLit::new(token::Integer, Symbol::intern(&i.to_string()), None)
         }
@@ -240,15 +1567,32 @@ fn lower_println(call: &BridgeCall) -> Result<Expr> {
             ));
         }
 
+        let str_token = |s: &str| {
+            let lit = TokenLit::new(ast::token::Str, Symbol::intern(s), None);
+            TokenTree::Token(
+                Token::new(TokenKind::Literal(lit), DUMMY_SP),
+                ast::tokenstream::Spacing::Alone,
+            )
+        };
         match arg {
-            BridgeExpr::Literal(BridgeLiteral::String(s)) => {
-                let token = Token::new(
-                    TokenKind::Literal(TokenLit::new(ast::token::Str, Symbol::intern(s), None)),
-                    DUMMY_SP,
-                );
-                trees.push(TokenTree::Token(token, ast::tokenstream::Spacing::Alone));
+            BridgeExpr::Literal(BridgeLiteral::String(s)) => trees.push(str_token(s)),
+            // println(error): `println!("{}", error)`
+            BridgeExpr::Variable(name) => {
+                trees.push(str_token("{}"));
+                trees.push(TokenTree::Token(
+                    Token::new(TokenKind::Comma, DUMMY_SP),
+                    ast::tokenstream::Spacing::Alone,
+                ));
+                trees.push(TokenTree::Token(
+                    Token::from_ast_ident(Ident::from_str(name)),
+                    ast::tokenstream::Spacing::Alone,
+                ));
+            }
+            _ => {
+                return Err(anyhow!(
+                    "Only string literals and variables supported in println for now"
+                ))
             }
-            _ => return Err(anyhow!("Only string literals supported in println for now")),
         }
     }
 